@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set when the pretty views should stick to plain ASCII
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The emoji and box-drawing glyphs the pretty views use, with their plain
+/// replacements. Emoji presentation selectors (U+FE0F) are dropped as well.
+const GLYPHS: &[(char, &str)] = &[
+    ('▸', "*"),
+    ('├', "|-"),
+    ('└', "`-"),
+    ('│', "|"),
+    ('→', "->"),
+    ('↳', "->"),
+    ('…', "..."),
+    ('×', "x"),
+    ('µ', "u"),
+    ('✅', "[ok]"),
+    ('✔', "[ok]"),
+    ('❌', "[x]"),
+    ('✘', "[x]"),
+    ('⚠', "[!]"),
+    ('🔒', "[!]"),
+    ('ℹ', "[i]"),
+    ('💡', "[i]"),
+    ('🔑', "[pk]"),
+    ('⚡', "*"),
+    ('📋', "*"),
+    ('📊', "*"),
+    ('🔧', "*"),
+    ('📦', "*"),
+    ('🔀', "*"),
+    ('📈', "*"),
+    ('🌐', "*"),
+    ('📂', "*"),
+    ('🔎', "*"),
+    ('🔍', "*"),
+    ('🔗', "*"),
+    ('⏰', "*"),
+    ('🔁', "*"),
+    ('⚙', "*"),
+    ('🧹', "*"),
+    ('🧮', "*"),
+    ('🕒', "*"),
+    ('📇', "*"),
+    ('🩺', "*"),
+];
+
+/// Decide whether to write plain ASCII: when asked to, when stdout is not
+/// a terminal (pipes, files, CI logs) or when `TERM` is `dumb`
+pub fn use_ascii(requested: bool, stdout_is_terminal: bool, term: Option<&str>) -> bool {
+    requested || !stdout_is_terminal || term == Some("dumb")
+}
+
+/// Apply the decision for the rest of the run. Colors are decided
+/// separately (see `color::configure`).
+pub fn configure(requested: bool) {
+    let term = std::env::var("TERM").ok();
+    let enabled = use_ascii(
+        requested,
+        std::io::stdout().is_terminal() && !crate::output::is_redirected(),
+        term.as_deref(),
+    );
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// A glyph or label the pretty views emit, such as `"▸"` or
+/// `"📊 TABLES"`, in ASCII when that was asked for. Only the views' own
+/// text goes through here, never names or docs taken from the schema.
+pub fn glyphs(text: &'static str) -> Cow<'static, str> {
+    if is_enabled() {
+        transliterate(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `text` with every glyph in the table replaced; borrowed when there
+/// was nothing to replace
+pub fn transliterate(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut plain = String::with_capacity(text.len());
+    for c in text.chars() {
+        match GLYPHS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, replacement)) => plain.push_str(replacement),
+            None if c == '\u{fe0f}' => {}
+            None => plain.push(c),
+        }
+    }
+    Cow::Owned(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_when_asked_piped_or_dumb() {
+        for requested in [false, true] {
+            for terminal in [false, true] {
+                for term in [None, Some("xterm-256color"), Some("dumb")] {
+                    let expected = requested || !terminal || term == Some("dumb");
+                    assert_eq!(
+                        use_ascii(requested, terminal, term),
+                        expected,
+                        "requested={requested} terminal={terminal} term={term:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unicode_only_on_a_capable_terminal_without_the_flag() {
+        assert!(!use_ascii(false, true, Some("xterm")));
+        assert!(!use_ascii(false, true, None));
+    }
+
+    #[test]
+    fn glyphs_are_replaced() {
+        assert_eq!(transliterate("  ▸ player → Player"), "  * player -> Player");
+        assert_eq!(transliterate("    ├ id"), "    |- id");
+        assert_eq!(transliterate("    └ name"), "    `- name");
+        assert_eq!(
+            transliterate("⚠️ 3 naming warnings"),
+            "[!] 3 naming warnings"
+        );
+        assert_eq!(transliterate("🔑 ❌ ✅ ℹ️"), "[pk] [x] [ok] [i]");
+    }
+
+    #[test]
+    fn every_glyph_has_an_ascii_replacement() {
+        for (glyph, replacement) in GLYPHS {
+            assert!(replacement.is_ascii(), "{glyph}");
+            assert!(transliterate(&glyph.to_string()).is_ascii(), "{glyph}");
+        }
+    }
+
+    #[test]
+    fn plain_text_is_borrowed() {
+        assert!(matches!(transliterate("id: u64"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,118 @@
+use clap::{Command, ValueEnum};
+use serde_json::{json, Value};
+
+use crate::schema::OutputFormat;
+
+/// Machine-readable description of what this binary supports, built from
+/// the clap command tree and the format enums so it stays in sync with
+/// the actual CLI
+pub fn capabilities_json(command: &Command) -> Value {
+    let flags: Vec<_> = command
+        .get_arguments()
+        .filter_map(|arg| {
+            let long = arg.get_long()?;
+            Some(json!({
+                "name": format!("--{long}"),
+                "takes_value": arg.get_action().takes_values(),
+                "help": arg.get_help().map(ToString::to_string),
+            }))
+        })
+        .collect();
+
+    let subcommands: Vec<_> = command
+        .get_subcommands()
+        .map(|c| c.get_name().to_string())
+        .collect();
+
+    let output_formats: Vec<_> = OutputFormat::value_variants()
+        .iter()
+        .filter_map(|f| f.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect();
+
+    json!({
+        "name": command.get_name(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "subcommands": subcommands,
+        "flags": flags,
+        "output_formats": output_formats,
+        "input_formats": ["server", "schema-json-file"],
+        "fingerprint_algorithm": crate::history::FINGERPRINT_ALGORITHM,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn manifest() -> Value {
+        capabilities_json(&crate::Cli::command())
+    }
+
+    fn names(manifest: &Value, key: &str) -> Vec<String> {
+        manifest[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_output_format_is_listed() {
+        let formats = names(&manifest(), "output_formats");
+        assert_eq!(formats.len(), OutputFormat::value_variants().len());
+        for format in OutputFormat::value_variants() {
+            let name = format.to_possible_value().unwrap();
+            assert!(
+                formats.iter().any(|f| f == name.get_name()),
+                "{} is missing",
+                name.get_name()
+            );
+        }
+    }
+
+    #[test]
+    fn every_subcommand_is_listed() {
+        let command = crate::Cli::command();
+        let expected: Vec<_> = command
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect();
+        assert_eq!(names(&manifest(), "subcommands"), expected);
+    }
+
+    #[test]
+    fn every_long_flag_is_listed() {
+        let manifest = manifest();
+        let flags: Vec<_> = manifest["flags"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|flag| flag["name"].as_str().unwrap())
+            .collect();
+        for arg in crate::Cli::command().get_arguments() {
+            if let Some(long) = arg.get_long() {
+                assert!(flags.contains(&format!("--{long}").as_str()), "--{long}");
+            }
+        }
+        assert!(flags.contains(&"--capabilities"));
+        assert!(flags.contains(&"--format"));
+    }
+
+    #[test]
+    fn versions_are_included() {
+        let manifest = manifest();
+        assert_eq!(manifest["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            manifest["fingerprint_algorithm"],
+            crate::history::FINGERPRINT_ALGORITHM
+        );
+        assert_eq!(
+            names(&manifest, "input_formats"),
+            ["server", "schema-json-file"]
+        );
+    }
+}
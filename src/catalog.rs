@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+    get_option_inner_type, is_option_type,
+};
+
+/// Metadata catalog document layout for --format catalog
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum CatalogFlavor {
+    /// OpenMetadata table entities
+    #[default]
+    Openmetadata,
+    /// DataHub schemaMetadata aspects
+    Datahub,
+}
+
+/// A table column (or nested field) with its type mapped to catalog terms
+struct Column {
+    name: String,
+    /// OpenMetadata `dataType`; DataHub types are derived from it
+    data_type: &'static str,
+    /// The type as the pretty view shows it, e.g. `Vec<ItemStack>`
+    display: String,
+    nullable: bool,
+    primary_key: bool,
+    /// Variant names of an all-unit enum
+    enum_values: Vec<String>,
+    array_of: Option<&'static str>,
+    children: Vec<Column>,
+}
+
+/// Render one dataset document per table. URNs and fully qualified names
+/// are derived from the server host, database and table names, so they
+/// stay stable across runs.
+pub fn generate_catalog(
+    schema: &SatsSchema,
+    server: &str,
+    db: &str,
+    table: Option<&str>,
+    flavor: CatalogFlavor,
+) -> Result<Value> {
+    let tables: Vec<_> = match table {
+        Some(name) => {
+            let table = schema
+                .tables
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+            vec![table]
+        }
+        None => schema.tables.iter().collect(),
+    };
+
+    let type_names = build_type_names(schema);
+    let service = server
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+
+    let mut datasets = Vec::new();
+    for table in tables {
+        let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+        else {
+            continue;
+        };
+        let mut builder = ColumnBuilder {
+            schema,
+            type_names: &type_names,
+            visiting: vec![table.product_type_ref],
+        };
+        let mut columns = builder.product_columns(Product);
+        for &pk in &table.primary_key {
+            if let Some(column) = columns.get_mut(pk) {
+                column.primary_key = true;
+            }
+        }
+
+        datasets.push(match flavor {
+            CatalogFlavor::Openmetadata => openmetadata_table(service, db, &table.name, &columns),
+            CatalogFlavor::Datahub => datahub_dataset(service, db, &table.name, &columns),
+        });
+    }
+    Ok(Value::Array(datasets))
+}
+
+struct ColumnBuilder<'a> {
+    schema: &'a SatsSchema,
+    type_names: &'a HashMap<usize, String>,
+    /// Refs being expanded, so recursive types stop instead of nesting forever
+    visiting: Vec<usize>,
+}
+
+impl ColumnBuilder<'_> {
+    fn product_columns(&mut self, product: &ProductType) -> Vec<Column> {
+        product
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let name = element
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                self.column(name, &element.algebraic_type)
+            })
+            .collect()
+    }
+
+    fn column(&mut self, name: String, alg_type: &AlgebraicType) -> Column {
+        let mut column = Column {
+            name,
+            data_type: "UNKNOWN",
+            display: format_type(self.schema, alg_type, self.type_names),
+            nullable: false,
+            primary_key: false,
+            enum_values: Vec::new(),
+            array_of: None,
+            children: Vec::new(),
+        };
+        self.fill(&mut column, alg_type);
+        column
+    }
+
+    fn fill(&mut self, column: &mut Column, alg_type: &AlgebraicType) {
+        column.data_type = match alg_type {
+            AlgebraicType::Bool { .. } => "BOOLEAN",
+            AlgebraicType::I8 { .. } | AlgebraicType::U8 { .. } => "TINYINT",
+            AlgebraicType::I16 { .. } | AlgebraicType::U16 { .. } => "SMALLINT",
+            AlgebraicType::I32 { .. } | AlgebraicType::U32 { .. } => "INT",
+            AlgebraicType::I64 { .. } | AlgebraicType::U64 { .. } => "BIGINT",
+            AlgebraicType::I128 { .. }
+            | AlgebraicType::U128 { .. }
+            | AlgebraicType::I256 { .. }
+            | AlgebraicType::U256 { .. } => "NUMERIC",
+            AlgebraicType::F32 { .. } => "FLOAT",
+            AlgebraicType::F64 { .. } => "DOUBLE",
+            AlgebraicType::String { .. } => "STRING",
+            AlgebraicType::Array { Array } => {
+                let mut element = self.column(String::new(), Array);
+                column.array_of = Some(element.data_type);
+                column.children = std::mem::take(&mut element.children);
+                "ARRAY"
+            }
+            AlgebraicType::Map { .. } => "MAP",
+            AlgebraicType::Product { Product } => return self.fill_product(column, Product),
+            AlgebraicType::Sum { Sum } => return self.fill_sum(column, Sum),
+            AlgebraicType::Ref { Ref } => {
+                let idx = *Ref as usize;
+                if self.visiting.contains(&idx) {
+                    column.data_type = "STRUCT";
+                    return;
+                }
+                self.visiting.push(idx);
+                match self.schema.typespace.types.get(idx) {
+                    Some(TypeDef::Product { Product }) => self.fill_product(column, Product),
+                    Some(TypeDef::Sum { Sum }) => self.fill_sum(column, Sum),
+                    _ => {}
+                }
+                self.visiting.pop();
+                return;
+            }
+        };
+    }
+
+    fn fill_product(&mut self, column: &mut Column, product: &ProductType) {
+        column.data_type = match detect_spacetimedb_type(product).as_deref() {
+            Some("Identity") => "STRING",
+            Some("Timestamp") => "TIMESTAMP",
+            Some("Duration") => "INTERVAL",
+            _ => {
+                column.children = self.product_columns(product);
+                "STRUCT"
+            }
+        };
+    }
+
+    fn fill_sum(&mut self, column: &mut Column, sum: &SumType) {
+        if detect_spacetimedb_sum_type(sum).is_some() {
+            column.data_type = "UNION";
+            return;
+        }
+        if is_option_type(sum) {
+            if let Some(inner) = get_option_inner_type(sum) {
+                self.fill(column, inner);
+                column.nullable = true;
+                return;
+            }
+        }
+
+        let unit_only = sum.variants.iter().all(|v| {
+            matches!(&v.algebraic_type, AlgebraicType::Product { Product } if Product.elements.is_empty())
+        });
+        if unit_only && !sum.variants.is_empty() {
+            column.data_type = "ENUM";
+            column.enum_values = sum
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    v.name
+                        .as_option()
+                        .map_or_else(|| i.to_string(), str::to_string)
+                })
+                .collect();
+        } else {
+            column.data_type = "UNION";
+        }
+    }
+}
+
+/// OpenMetadata quotes name parts that contain dots
+fn fqn_part(part: &str) -> String {
+    if part.contains('.') {
+        format!("\"{part}\"")
+    } else {
+        part.to_string()
+    }
+}
+
+fn openmetadata_table(service: &str, db: &str, table: &str, columns: &[Column]) -> Value {
+    let fqn = [service, db, table].map(fqn_part).join(".");
+    let primary_key: Vec<_> = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+
+    let mut document = json!({
+        "name": table,
+        "fullyQualifiedName": fqn,
+        "tableType": "Regular",
+        "columns": openmetadata_columns(&fqn, columns),
+    });
+    if !primary_key.is_empty() {
+        document["tableConstraints"] = json!([{
+            "constraintType": "PRIMARY_KEY",
+            "columns": primary_key,
+        }]);
+    }
+    document
+}
+
+fn openmetadata_columns(parent_fqn: &str, columns: &[Column]) -> Vec<Value> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let fqn = format!("{parent_fqn}.{}", fqn_part(&column.name));
+            let constraint = if column.primary_key {
+                "PRIMARY_KEY"
+            } else if column.nullable {
+                "NULL"
+            } else {
+                "NOT_NULL"
+            };
+            let mut value = json!({
+                "name": column.name,
+                "fullyQualifiedName": fqn,
+                "dataType": column.data_type,
+                "dataTypeDisplay": column.display,
+                "constraint": constraint,
+                "ordinalPosition": i + 1,
+            });
+            if let Some(array_of) = column.array_of {
+                value["arrayDataType"] = json!(array_of);
+            }
+            if !column.enum_values.is_empty() {
+                value["tags"] = column
+                    .enum_values
+                    .iter()
+                    .map(|v| {
+                        json!({
+                            "tagFQN": format!("{}.{}", fqn_part(&column.display), fqn_part(v)),
+                            "source": "Classification",
+                            "labelType": "Derived",
+                            "state": "Confirmed",
+                        })
+                    })
+                    .collect();
+            }
+            if !column.children.is_empty() {
+                value["children"] = json!(openmetadata_columns(&fqn, &column.children));
+            }
+            value
+        })
+        .collect()
+}
+
+fn datahub_dataset(service: &str, db: &str, table: &str, columns: &[Column]) -> Value {
+    let urn =
+        format!("urn:li:dataset:(urn:li:dataPlatform:spacetimedb,{service}.{db}.{table},PROD)");
+    let mut fields = Vec::new();
+    datahub_fields("", columns, &mut fields);
+    let primary_keys: Vec<_> = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+
+    json!({
+        "entityType": "dataset",
+        "entityUrn": urn,
+        "changeType": "UPSERT",
+        "aspectName": "schemaMetadata",
+        "aspect": {
+            "schemaName": table,
+            "platform": "urn:li:dataPlatform:spacetimedb",
+            "version": 0,
+            "hash": "",
+            "platformSchema": {"com.linkedin.schema.OtherSchema": {"rawSchema": ""}},
+            "fields": fields,
+            "primaryKeys": primary_keys,
+        },
+    })
+}
+
+/// DataHub has no nested columns; children become dotted field paths
+fn datahub_fields(prefix: &str, columns: &[Column], fields: &mut Vec<Value>) {
+    for column in columns {
+        let path = format!("{prefix}{}", column.name);
+        let mut field = json!({
+            "fieldPath": path,
+            "nativeDataType": column.display,
+            "type": {"type": {datahub_type(column.data_type): {}}},
+            "nullable": column.nullable,
+            "isPartOfKey": column.primary_key,
+        });
+        if !column.enum_values.is_empty() {
+            let tags: Vec<_> = column
+                .enum_values
+                .iter()
+                .map(|v| json!({"tag": format!("urn:li:tag:{}.{v}", column.display)}))
+                .collect();
+            field["globalTags"] = json!({"tags": tags});
+        }
+        fields.push(field);
+        datahub_fields(&format!("{path}."), &column.children, fields);
+    }
+}
+
+fn datahub_type(data_type: &str) -> &'static str {
+    match data_type {
+        "BOOLEAN" => "com.linkedin.schema.BooleanType",
+        "TINYINT" | "SMALLINT" | "INT" | "BIGINT" | "NUMERIC" | "FLOAT" | "DOUBLE" | "INTERVAL" => {
+            "com.linkedin.schema.NumberType"
+        }
+        "STRING" => "com.linkedin.schema.StringType",
+        "TIMESTAMP" => "com.linkedin.schema.TimeType",
+        "ARRAY" => "com.linkedin.schema.ArrayType",
+        "STRUCT" => "com.linkedin.schema.RecordType",
+        "ENUM" => "com.linkedin.schema.EnumType",
+        "UNION" => "com.linkedin.schema.UnionType",
+        _ => "com.linkedin.schema.NullType",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::demo_schema;
+
+    fn catalog(table: Option<&str>, flavor: CatalogFlavor) -> Value {
+        generate_catalog(
+            &demo_schema(),
+            "http://localhost:3000/",
+            "demo",
+            table,
+            flavor,
+        )
+        .unwrap()
+    }
+
+    fn fixture(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn openmetadata_matches_fixture() {
+        assert_eq!(
+            catalog(None, CatalogFlavor::Openmetadata),
+            fixture(include_str!(
+                "../tests/fixtures/demo_catalog_openmetadata.json"
+            ))
+        );
+    }
+
+    #[test]
+    fn datahub_matches_fixture() {
+        assert_eq!(
+            catalog(None, CatalogFlavor::Datahub),
+            fixture(include_str!("../tests/fixtures/demo_catalog_datahub.json"))
+        );
+    }
+
+    #[test]
+    fn table_filter_picks_one_dataset() {
+        let datasets = catalog(Some("Item_Kind"), CatalogFlavor::Openmetadata);
+        let full = catalog(None, CatalogFlavor::Openmetadata);
+        assert_eq!(datasets.as_array().unwrap().len(), 1);
+        assert_eq!(datasets[0], full[1]);
+
+        let error = generate_catalog(
+            &demo_schema(),
+            "localhost:3000",
+            "demo",
+            Some("nope"),
+            CatalogFlavor::Datahub,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Table 'nope' not found");
+    }
+
+    #[test]
+    fn names_do_not_depend_on_the_url_scheme() {
+        let from_url = catalog(None, CatalogFlavor::Datahub);
+        let from_host = generate_catalog(
+            &demo_schema(),
+            "localhost:3000",
+            "demo",
+            None,
+            CatalogFlavor::Datahub,
+        )
+        .unwrap();
+        assert_eq!(from_url, from_host);
+        assert_eq!(
+            from_url[0]["entityUrn"],
+            "urn:li:dataset:(urn:li:dataPlatform:spacetimedb,localhost:3000.demo.player,PROD)"
+        );
+    }
+}
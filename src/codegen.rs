@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{
+    detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type, is_option_type,
+};
+
+/// A field type as the code generators see it: refs to declared types are
+/// kept by index, while builtins, options and aliases of other entries are
+/// resolved to what they stand for. Each backend only has to spell these.
+pub enum Shape<'a> {
+    Primitive(Primitive),
+    Special(Special),
+    Option(Box<Shape<'a>>),
+    Array(Box<Shape<'a>>),
+    Map(Box<Shape<'a>>, Box<Shape<'a>>),
+    /// A typespace entry with its own declaration (see `is_declared`)
+    Named(usize),
+    /// An inline struct or tuple (possibly empty), for the backend to hoist
+    Product(&'a ProductType),
+    /// An inline enum, for the backend to hoist
+    Sum(&'a SumType),
+    /// A dangling ref, or an alias cycle that never passes through a
+    /// declared type; there is nothing better to write than "any value"
+    Any,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Bool,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    I128,
+    U128,
+    I256,
+    U256,
+    F32,
+    F64,
+    String,
+}
+
+/// SpacetimeDB's own types, as `detect_spacetimedb_type` and
+/// `detect_spacetimedb_sum_type` find them
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Special {
+    Identity,
+    Timestamp,
+    Duration,
+    ScheduledAt,
+}
+
+impl Primitive {
+    /// The name `format_type` uses
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::I8 => "i8",
+            Self::U8 => "u8",
+            Self::I16 => "i16",
+            Self::U16 => "u16",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+            Self::I64 => "i64",
+            Self::U64 => "u64",
+            Self::I128 => "i128",
+            Self::U128 => "u128",
+            Self::I256 => "i256",
+            Self::U256 => "u256",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::String => "String",
+        }
+    }
+}
+
+impl Special {
+    fn from_detected(name: &str) -> Option<Self> {
+        match name {
+            "Identity" => Some(Self::Identity),
+            "Timestamp" => Some(Self::Timestamp),
+            "Duration" => Some(Self::Duration),
+            "ScheduledAt" => Some(Self::ScheduledAt),
+            _ => None,
+        }
+    }
+
+    /// The name the pretty view uses
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Identity => "Identity",
+            Self::Timestamp => "Timestamp",
+            Self::Duration => "Duration",
+            Self::ScheduledAt => "ScheduledAt",
+        }
+    }
+}
+
+/// Structs and enums get a declaration. Builtins, options and aliases of
+/// other entries are written out in place wherever they are used.
+pub fn is_declared(schema: &SatsSchema, idx: usize) -> bool {
+    match schema.typespace.types.get(idx) {
+        Some(TypeDef::Product { Product }) => detect_spacetimedb_type(Product).is_none(),
+        Some(TypeDef::Sum { Sum }) => {
+            detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum)
+        }
+        _ => false,
+    }
+}
+
+pub fn resolve<'a>(schema: &'a SatsSchema, alg_type: &'a AlgebraicType) -> Shape<'a> {
+    Resolver {
+        schema,
+        following: HashSet::new(),
+    }
+    .shape(alg_type)
+}
+
+/// `base`, or `base2`, `base3`, ... if it is already taken
+pub fn unique(taken: &mut HashSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut n = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{base}{n}");
+        n += 1;
+    }
+    name
+}
+
+struct Resolver<'a> {
+    schema: &'a SatsSchema,
+    /// Typespace entries being resolved in place, to stop alias cycles
+    following: HashSet<usize>,
+}
+
+impl<'a> Resolver<'a> {
+    fn shape(&mut self, alg_type: &'a AlgebraicType) -> Shape<'a> {
+        match alg_type {
+            AlgebraicType::Bool { .. } => Shape::Primitive(Primitive::Bool),
+            AlgebraicType::I8 { .. } => Shape::Primitive(Primitive::I8),
+            AlgebraicType::U8 { .. } => Shape::Primitive(Primitive::U8),
+            AlgebraicType::I16 { .. } => Shape::Primitive(Primitive::I16),
+            AlgebraicType::U16 { .. } => Shape::Primitive(Primitive::U16),
+            AlgebraicType::I32 { .. } => Shape::Primitive(Primitive::I32),
+            AlgebraicType::U32 { .. } => Shape::Primitive(Primitive::U32),
+            AlgebraicType::I64 { .. } => Shape::Primitive(Primitive::I64),
+            AlgebraicType::U64 { .. } => Shape::Primitive(Primitive::U64),
+            AlgebraicType::I128 { .. } => Shape::Primitive(Primitive::I128),
+            AlgebraicType::U128 { .. } => Shape::Primitive(Primitive::U128),
+            AlgebraicType::I256 { .. } => Shape::Primitive(Primitive::I256),
+            AlgebraicType::U256 { .. } => Shape::Primitive(Primitive::U256),
+            AlgebraicType::F32 { .. } => Shape::Primitive(Primitive::F32),
+            AlgebraicType::F64 { .. } => Shape::Primitive(Primitive::F64),
+            AlgebraicType::String { .. } => Shape::Primitive(Primitive::String),
+            AlgebraicType::Array { Array } => Shape::Array(Box::new(self.shape(Array))),
+            AlgebraicType::Map { Map } => Shape::Map(
+                Box::new(self.shape(&Map.key_ty)),
+                Box::new(self.shape(&Map.ty)),
+            ),
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize),
+            AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+                Some(detected) => special(&detected),
+                None => Shape::Product(Product),
+            },
+            AlgebraicType::Sum { Sum } => {
+                if let Some(detected) = detect_spacetimedb_sum_type(Sum) {
+                    return special(&detected);
+                }
+                match get_option_inner_type(Sum) {
+                    Some(inner) if is_option_type(Sum) => {
+                        Shape::Option(Box::new(self.shape(inner)))
+                    }
+                    _ => Shape::Sum(Sum),
+                }
+            }
+        }
+    }
+
+    fn reference(&mut self, idx: usize) -> Shape<'a> {
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => match detect_spacetimedb_type(Product) {
+                Some(detected) => special(&detected),
+                None => Shape::Named(idx),
+            },
+            Some(TypeDef::Sum { Sum }) => {
+                if let Some(detected) = detect_spacetimedb_sum_type(Sum) {
+                    return special(&detected);
+                }
+                match get_option_inner_type(Sum) {
+                    Some(inner) if is_option_type(Sum) => self.follow(idx, |resolver| {
+                        Shape::Option(Box::new(resolver.shape(inner)))
+                    }),
+                    _ => Shape::Named(idx),
+                }
+            }
+            Some(TypeDef::Builtin { Builtin }) => {
+                self.follow(idx, |resolver| resolver.builtin(Builtin))
+            }
+            Some(TypeDef::Ref { Ref }) => {
+                self.follow(idx, |resolver| resolver.reference(*Ref as usize))
+            }
+            None => Shape::Any,
+        }
+    }
+
+    fn follow(&mut self, idx: usize, resolve: impl FnOnce(&mut Self) -> Shape<'a>) -> Shape<'a> {
+        if !self.following.insert(idx) {
+            return Shape::Any;
+        }
+        let shape = resolve(self);
+        self.following.remove(&idx);
+        shape
+    }
+
+    fn builtin(&mut self, builtin: &'a BuiltinType) -> Shape<'a> {
+        match builtin {
+            BuiltinType::Bool { .. } => Shape::Primitive(Primitive::Bool),
+            BuiltinType::I8 { .. } => Shape::Primitive(Primitive::I8),
+            BuiltinType::U8 { .. } => Shape::Primitive(Primitive::U8),
+            BuiltinType::I16 { .. } => Shape::Primitive(Primitive::I16),
+            BuiltinType::U16 { .. } => Shape::Primitive(Primitive::U16),
+            BuiltinType::I32 { .. } => Shape::Primitive(Primitive::I32),
+            BuiltinType::U32 { .. } => Shape::Primitive(Primitive::U32),
+            BuiltinType::I64 { .. } => Shape::Primitive(Primitive::I64),
+            BuiltinType::U64 { .. } => Shape::Primitive(Primitive::U64),
+            BuiltinType::I128 { .. } => Shape::Primitive(Primitive::I128),
+            BuiltinType::U128 { .. } => Shape::Primitive(Primitive::U128),
+            BuiltinType::I256 { .. } => Shape::Primitive(Primitive::I256),
+            BuiltinType::U256 { .. } => Shape::Primitive(Primitive::U256),
+            BuiltinType::F32 { .. } => Shape::Primitive(Primitive::F32),
+            BuiltinType::F64 { .. } => Shape::Primitive(Primitive::F64),
+            BuiltinType::String { .. } => Shape::Primitive(Primitive::String),
+            BuiltinType::Array { Array } => Shape::Array(Box::new(self.shape(Array))),
+            BuiltinType::Map { Map } => Shape::Map(
+                Box::new(self.shape(&Map.key_ty)),
+                Box::new(self.shape(&Map.ty)),
+            ),
+        }
+    }
+}
+
+fn special<'a>(detected: &str) -> Shape<'a> {
+    Special::from_detected(detected).map_or(Shape::Any, Shape::Special)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::schema;
+
+    fn builtin_256() -> SatsSchema {
+        schema(serde_json::from_str(include_str!("../tests/fixtures/builtin_256.json")).unwrap())
+    }
+
+    /// The primitive a shape resolves to, through arrays
+    fn primitive(shape: &Shape) -> Option<String> {
+        match shape {
+            Shape::Primitive(primitive) => Some(primitive.name().to_string()),
+            Shape::Array(inner) => primitive(inner).map(|name| format!("Vec<{name}>")),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn builtin_256_bit_entries_resolve_to_primitives() {
+        let schema = builtin_256();
+        let TypeDef::Product { Product } = &schema.typespace.types[0] else {
+            panic!("row type is not a product");
+        };
+        let shapes: Vec<_> = Product
+            .elements
+            .iter()
+            .map(|e| primitive(&resolve(&schema, &e.algebraic_type)).unwrap())
+            .collect();
+        assert_eq!(shapes, ["u64", "u256", "i256", "Vec<u256>"]);
+        assert!(!is_declared(&schema, 1));
+        assert!(!is_declared(&schema, 2));
+        assert!(is_declared(&schema, 0));
+    }
+
+    #[test]
+    fn alias_cycles_resolve_to_any() {
+        let schema = schema(serde_json::json!({
+            "typespace": {"types": [{"Ref": 1}, {"Ref": 0}, {"Builtin": {"Array": {"Ref": 2}}}]},
+            "tables": [],
+            "reducers": [],
+            "types": [],
+        }));
+        assert!(matches!(
+            resolve(&schema, &AlgebraicType::Ref { Ref: 0 }),
+            Shape::Any
+        ));
+        let Shape::Array(inner) = resolve(&schema, &AlgebraicType::Ref { Ref: 2 }) else {
+            panic!("a builtin array stays an array");
+        };
+        assert!(matches!(*inner, Shape::Any));
+        assert!(matches!(
+            resolve(&schema, &AlgebraicType::Ref { Ref: 9 }),
+            Shape::Any
+        ));
+    }
+
+    #[test]
+    fn unique_numbers_clashes() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique(&mut taken, "Type".to_string()), "Type");
+        assert_eq!(unique(&mut taken, "Type".to_string()), "Type2");
+        assert_eq!(unique(&mut taken, "Type".to_string()), "Type3");
+        assert_eq!(unique(&mut taken, "Type2".to_string()), "Type22");
+    }
+}
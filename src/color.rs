@@ -0,0 +1,121 @@
+use std::io::IsTerminal;
+
+/// When to emit ANSI colors
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal (and not redirected with --output), honoring NO_COLOR and CLICOLOR_FORCE
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decide whether to color output.
+///
+/// `--color always`/`never` win outright. Under `auto`, a non-empty
+/// `NO_COLOR` disables color, then a `CLICOLOR_FORCE` other than `0`
+/// forces it, and otherwise color follows whether stdout is a terminal.
+pub fn use_color(
+    choice: ColorChoice,
+    stdout_is_terminal: bool,
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if no_color.is_some_and(|v| !v.is_empty()) {
+                false
+            } else if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") {
+                true
+            } else {
+                stdout_is_terminal
+            }
+        }
+    }
+}
+
+/// Apply the color decision to `colored` globally, instead of relying on
+/// its own detection (which misses redirected output on Windows)
+pub fn configure(choice: ColorChoice) {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let clicolor_force = std::env::var("CLICOLOR_FORCE").ok();
+    let enabled = use_color(
+        choice,
+        std::io::stdout().is_terminal() && !crate::output::is_redirected(),
+        no_color.as_deref(),
+        clicolor_force.as_deref(),
+    );
+
+    // Older Windows consoles only render ANSI with virtual terminal processing
+    #[cfg(windows)]
+    if enabled {
+        let _ = colored::control::set_virtual_terminal(true);
+    }
+
+    colored::control::set_override(enabled);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENV_VALUES: [Option<&str>; 4] = [None, Some(""), Some("0"), Some("1")];
+
+    #[test]
+    fn always_and_never_ignore_terminal_and_environment() {
+        for terminal in [false, true] {
+            for no_color in ENV_VALUES {
+                for force in ENV_VALUES {
+                    assert!(use_color(ColorChoice::Always, terminal, no_color, force));
+                    assert!(!use_color(ColorChoice::Never, terminal, no_color, force));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn auto_matrix() {
+        // (terminal, NO_COLOR, CLICOLOR_FORCE, expected)
+        let cases = [
+            (true, None, None, true),
+            (false, None, None, false),
+            // An empty NO_COLOR counts as unset
+            (true, Some(""), None, true),
+            (true, Some("1"), None, false),
+            // NO_COLOR only needs to be non-empty, "0" included
+            (true, Some("0"), None, false),
+            (false, None, Some("1"), true),
+            (false, None, Some("yes"), true),
+            (false, None, Some("0"), false),
+            (false, None, Some(""), false),
+            (true, None, Some("0"), true),
+            // NO_COLOR beats CLICOLOR_FORCE
+            (false, Some("1"), Some("1"), false),
+            (true, Some("1"), Some("1"), false),
+            (false, Some(""), Some("1"), true),
+        ];
+        for (terminal, no_color, force, expected) in cases {
+            assert_eq!(
+                use_color(ColorChoice::Auto, terminal, no_color, force),
+                expected,
+                "terminal={terminal} NO_COLOR={no_color:?} CLICOLOR_FORCE={force:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn auto_without_overrides_follows_the_terminal() {
+        for terminal in [false, true] {
+            for no_color in [None, Some("")] {
+                for force in [None, Some(""), Some("0")] {
+                    assert_eq!(
+                        use_color(ColorChoice::Auto, terminal, no_color, force),
+                        terminal
+                    );
+                }
+            }
+        }
+    }
+}
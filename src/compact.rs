@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::schema::sats_types::{SatsSchema, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, is_option_type,
+    primary_key_names,
+};
+
+/// Render one line per table, `player (8 fields, pk: id) -> Player`, then
+/// one per standalone struct and enum, `Vec2 (struct, 2 fields)`. Both
+/// lists are sorted by name, ignoring case.
+pub fn generate_compact(schema: &SatsSchema) -> String {
+    let type_names = build_type_names(schema);
+    let mut out = String::new();
+
+    let mut tables: Vec<_> = schema.tables.iter().collect();
+    tables.sort_by(|a, b| compare_names(&a.name, &b.name));
+    for table in tables {
+        let fields = match schema.typespace.types.get(table.product_type_ref) {
+            Some(TypeDef::Product { Product }) => Product.elements.len(),
+            _ => 0,
+        };
+        let primary_key = primary_key_names(schema, table);
+        let key = if primary_key.is_empty() {
+            String::new()
+        } else {
+            format!(", pk: {}", primary_key.join("+"))
+        };
+        let row_type = type_names
+            .get(&table.product_type_ref)
+            .cloned()
+            .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
+        let _ = writeln!(
+            out,
+            "{} ({fields} {}{key}) -> {row_type}",
+            table.name,
+            plural(fields, "field")
+        );
+    }
+
+    let table_refs: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+    let mut types: Vec<_> = schema
+        .types
+        .iter()
+        .filter(|named| !table_refs.contains(&named.ty))
+        .filter_map(|named| {
+            let summary = match schema.typespace.types.get(named.ty)? {
+                TypeDef::Product { Product } if detect_spacetimedb_type(Product).is_none() => {
+                    let fields = Product.elements.len();
+                    format!("struct, {fields} {}", plural(fields, "field"))
+                }
+                TypeDef::Sum { Sum }
+                    if detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum) =>
+                {
+                    let variants = Sum.variants.len();
+                    format!("enum, {variants} {}", plural(variants, "variant"))
+                }
+                _ => return None,
+            };
+            Some((named.name.name.as_str(), summary))
+        })
+        .collect();
+    types.sort_by(|(a, _), (b, _)| compare_names(a, b));
+    for (name, summary) in types {
+        let _ = writeln!(out, "{name} ({summary})");
+    }
+
+    out
+}
+
+/// Case-insensitive, with names differing only in case kept in a fixed order
+fn compare_names(a: &str, b: &str) -> std::cmp::Ordering {
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{noun}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, schema};
+    use serde_json::json;
+
+    #[test]
+    fn tables_then_standalone_types() {
+        assert_eq!(
+            generate_compact(&demo_schema()),
+            "chat_message (5 fields, pk: id) -> ChatMessage\n\
+             game_tick (3 fields, pk: scheduled_id) -> GameTick\n\
+             guild (4 fields, pk: id) -> Guild\n\
+             guild_member (4 fields, pk: guild_id+player_id) -> GuildMember\n\
+             item_kind (5 fields, pk: id) -> ItemKind\n\
+             player (8 fields, pk: id) -> Player\n\
+             GuildRole (enum, 3 variants)\n\
+             ItemStack (struct, 3 fields)\n\
+             PlayerStatus (enum, 3 variants)\n\
+             Rarity (enum, 5 variants)\n\
+             Vec2 (struct, 2 fields)\n"
+        );
+    }
+
+    #[test]
+    fn names_sort_ignoring_case_in_a_fixed_order() {
+        let unit = json!({"Product": {"elements": [
+            {"name": {"some": "id"}, "algebraic_type": {"U32": []}},
+        ]}});
+        let schema = schema(json!({
+            "typespace": {"types": [unit, unit, unit]},
+            "tables": [
+                {"name": "beta", "product_type_ref": 0, "primary_key": []},
+                {"name": "Alpha", "product_type_ref": 1, "primary_key": [0]},
+                {"name": "alpha", "product_type_ref": 2, "primary_key": [0]},
+            ],
+            "types": [
+                {"name": {"scope": [], "name": "Beta"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "UpperAlpha"}, "ty": 1, "custom_ordering": true},
+                {"name": {"scope": [], "name": "LowerAlpha"}, "ty": 2, "custom_ordering": true},
+            ],
+        }));
+        assert_eq!(
+            generate_compact(&schema),
+            "Alpha (1 field, pk: id) -> UpperAlpha\n\
+             alpha (1 field, pk: id) -> LowerAlpha\n\
+             beta (1 field) -> Beta\n"
+        );
+    }
+}
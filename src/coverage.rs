@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::ascii::glyphs;
+use crate::output::outln;
+use crate::schema::sats_types::SatsSchema;
+
+/// Keys of the raw document that the typed schema dropped, grouped by path
+/// pattern (array indices replaced with `*`)
+struct IgnoredKey {
+    count: usize,
+    bytes: usize,
+    example: String,
+}
+
+struct Coverage {
+    total_keys: usize,
+    total_bytes: usize,
+    ignored: BTreeMap<String, IgnoredKey>,
+}
+
+impl Coverage {
+    fn ignored_keys(&self) -> usize {
+        self.ignored.values().map(|k| k.count).sum()
+    }
+
+    fn ignored_bytes(&self) -> usize {
+        self.ignored.values().map(|k| k.bytes).sum()
+    }
+
+    /// Share of the document's bytes that the typed schema kept
+    fn percent_modeled(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 100.0;
+        }
+        100.0 * (self.total_bytes - self.ignored_bytes()) as f64 / self.total_bytes as f64
+    }
+}
+
+/// Compare the raw document with the parsed schema serialized back to JSON;
+/// whatever the round trip lost is what the tool does not model
+fn measure(raw: &Value, schema: &SatsSchema) -> Coverage {
+    let modeled = serde_json::to_value(schema).unwrap_or(Value::Null);
+    let mut coverage = Coverage {
+        total_keys: 0,
+        total_bytes: raw.to_string().len(),
+        ignored: BTreeMap::new(),
+    };
+    walk(raw, &modeled, "", "", &mut coverage);
+    coverage
+}
+
+fn walk(raw: &Value, modeled: &Value, path: &str, pattern: &str, coverage: &mut Coverage) {
+    match (raw, modeled) {
+        (Value::Object(raw), Value::Object(modeled)) => {
+            for (key, value) in raw {
+                coverage.total_keys += 1;
+                let path = format!("{path}/{key}");
+                let pattern = format!("{pattern}/{key}");
+                match modeled.get(key) {
+                    Some(kept) => walk(value, kept, &path, &pattern, coverage),
+                    None => {
+                        let entry = coverage.ignored.entry(pattern).or_insert(IgnoredKey {
+                            count: 0,
+                            bytes: 0,
+                            example: path,
+                        });
+                        entry.count += 1;
+                        entry.bytes += key.len() + value.to_string().len();
+                    }
+                }
+            }
+        }
+        (Value::Array(raw), Value::Array(modeled)) => {
+            for (i, (value, kept)) in raw.iter().zip(modeled).enumerate() {
+                walk(
+                    value,
+                    kept,
+                    &format!("{path}/{i}"),
+                    &format!("{pattern}/*"),
+                    coverage,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The one-line summary `--verbose` prints on every run
+pub fn coverage_summary(raw: &Value, schema: &SatsSchema) -> String {
+    let coverage = measure(raw, schema);
+    format!(
+        "{} Coverage: {:.1}% of the document modeled, {} of {} keys ignored (see --coverage)",
+        glyphs("🧮"),
+        coverage.percent_modeled(),
+        coverage.ignored_keys(),
+        coverage.total_keys
+    )
+}
+
+/// Print how much of the fetched document the tool models, and the keys it ignores
+pub fn display_coverage(raw: &Value, schema: &SatsSchema) {
+    let coverage = measure(raw, schema);
+
+    outln!("\n{}", glyphs("🧮 SCHEMA COVERAGE").bold().cyan());
+    outln!("{}", "-".repeat(40));
+    outln!(
+        "  Modeled:  {:.1}% of {} bytes (compact JSON)",
+        coverage.percent_modeled(),
+        coverage.total_bytes
+    );
+    outln!(
+        "  Ignored:  {} of {} keys ({} bytes)",
+        coverage.ignored_keys(),
+        coverage.total_keys,
+        coverage.ignored_bytes()
+    );
+
+    if coverage.ignored.is_empty() {
+        outln!(
+            "\n{} Every key in the document is modeled",
+            glyphs("✅").green()
+        );
+        return;
+    }
+
+    outln!("\nIgnored keys:");
+    for (pattern, key) in &coverage.ignored {
+        outln!(
+            "  {} {} {}",
+            glyphs("▸").yellow(),
+            pattern,
+            format!("{}{} e.g. {}", glyphs("×"), key.count, key.example).dimmed()
+        );
+    }
+}
+
+pub fn coverage_json(raw: &Value, schema: &SatsSchema) -> Value {
+    let coverage = measure(raw, schema);
+    let ignored: Vec<_> = coverage
+        .ignored
+        .iter()
+        .map(|(pattern, key)| {
+            json!({
+                "pattern": pattern,
+                "count": key.count,
+                "bytes": key.bytes,
+                "example": key.example,
+            })
+        })
+        .collect();
+
+    json!({
+        "percent_modeled": (coverage.percent_modeled() * 10.0).round() / 10.0,
+        "total_bytes": coverage.total_bytes,
+        "ignored_bytes": coverage.ignored_bytes(),
+        "total_keys": coverage.total_keys,
+        "ignored_keys": coverage.ignored_keys(),
+        "ignored": ignored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{schema, DEMO_SCHEMA};
+
+    fn report(fixture: &str) -> Value {
+        let raw: Value = serde_json::from_str(fixture).unwrap();
+        coverage_json(&raw, &schema(raw.clone()))
+    }
+
+    #[test]
+    fn extra_keys_are_reported_exactly() {
+        let expected: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/extra_keys_coverage.json"))
+                .unwrap();
+        assert_eq!(
+            report(include_str!("../tests/fixtures/extra_keys.json")),
+            expected
+        );
+    }
+
+    #[test]
+    fn fully_modeled_document_is_complete() {
+        let report = report(include_str!("../tests/fixtures/adversarial_names.json"));
+        assert_eq!(report["percent_modeled"], 100.0);
+        assert_eq!(report["ignored_keys"], 0);
+        assert_eq!(report["ignored"], json!([]));
+    }
+
+    #[test]
+    fn demo_ignores_only_unmodeled_table_details() {
+        let report = report(DEMO_SCHEMA);
+        let patterns: Vec<_> = report["ignored"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|key| key["pattern"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            patterns,
+            [
+                "/misc_exports",
+                "/row_level_security",
+                "/tables/*/sequences/*/increment",
+                "/tables/*/sequences/*/max_value",
+                "/tables/*/sequences/*/min_value",
+                "/tables/*/sequences/*/name",
+                "/tables/*/sequences/*/start",
+                "/tables/*/table_access",
+                "/tables/*/table_type",
+            ]
+        );
+    }
+
+    #[test]
+    fn array_indices_group_under_one_pattern() {
+        let raw = json!({"tables": [{"extra": 1}, {"extra": 22}], "top": "x"});
+        let mut coverage = Coverage {
+            total_keys: 0,
+            total_bytes: 0,
+            ignored: BTreeMap::new(),
+        };
+        let modeled = json!({"tables": [{}, {}]});
+        walk(&raw, &modeled, "", "", &mut coverage);
+
+        assert_eq!(coverage.total_keys, 4);
+        let extra = &coverage.ignored["/tables/*/extra"];
+        assert_eq!(extra.count, 2);
+        assert_eq!(extra.bytes, "extra".len() * 2 + "1".len() + "22".len());
+        assert_eq!(extra.example, "/tables/0/extra");
+        assert_eq!(coverage.ignored["/top"].bytes, "top".len() + "\"x\"".len());
+    }
+
+    #[test]
+    fn summary_line_points_at_the_full_report() {
+        let raw: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/extra_keys.json")).unwrap();
+        let line = coverage_summary(&raw, &schema(raw.clone()));
+        assert!(
+            line.ends_with(
+                "Coverage: 91.0% of the document modeled, 8 of 135 keys ignored (see --coverage)"
+            ),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn empty_document_counts_as_fully_modeled() {
+        let coverage = Coverage {
+            total_keys: 0,
+            total_bytes: 0,
+            ignored: BTreeMap::new(),
+        };
+        assert_eq!(coverage.percent_modeled(), 100.0);
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::codegen::{is_declared, resolve, unique, Primitive, Shape};
+use crate::identifiers::{csharp_identifier, pascal_case, string_literal};
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+
+/// SpacetimeDB's own types and the C# types they stand for unless
+/// `--csharp-type` says otherwise. Each is declared as a `using` alias at
+/// the top of the file when the schema uses it.
+const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("Identity", "SpacetimeDB.Identity"),
+    ("Timestamp", "SpacetimeDB.Timestamp"),
+    ("Duration", "SpacetimeDB.TimeDuration"),
+    ("ScheduledAt", "SpacetimeDB.ScheduleAt"),
+];
+
+const INDENT: &str = "    ";
+
+/// Render C# declarations for the schema inside `namespace`: a
+/// `public partial class` per struct, with PascalCase properties that keep
+/// the schema's field names through `[JsonPropertyName]`, a plain `enum`
+/// per enum whose variants carry no data, and an abstract base class with
+/// a nested subclass per variant for the rest.
+///
+/// `overrides` replace the default alias targets, e.g. `Identity=System.String`.
+pub fn generate_csharp(
+    schema: &SatsSchema,
+    namespace: &str,
+    overrides: &[(String, String)],
+) -> Result<String> {
+    let mut aliases: BTreeMap<&'static str, String> = DEFAULT_ALIASES
+        .iter()
+        .map(|(name, ty)| (*name, ty.to_string()))
+        .collect();
+    for (name, ty) in overrides {
+        let Some(target) = aliases.get_mut(name.as_str()) else {
+            let known: Vec<_> = DEFAULT_ALIASES.iter().map(|(name, _)| *name).collect();
+            return Err(anyhow!(
+                "--csharp-type: unknown type '{name}' (expected one of {})",
+                known.join(", ")
+            ));
+        };
+        *target = ty.clone();
+    }
+
+    let names = type_names(schema);
+    let mut generator = CSharpGenerator {
+        schema,
+        names: &names,
+        used_aliases: BTreeSet::new(),
+        hoisted: Vec::new(),
+    };
+
+    let mut entries: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| is_declared(schema, *idx))
+        .collect();
+    entries.sort_by(|a, b| names[a].cmp(&names[b]));
+
+    let mut declarations = Vec::new();
+    for idx in entries {
+        let name = &names[&idx];
+        let declaration = match &schema.typespace.types[idx] {
+            TypeDef::Product { Product } => generator.class(name, Product),
+            TypeDef::Sum { Sum } => generator.sum(name, Sum),
+            _ => continue,
+        };
+        declarations.push(declaration);
+        declarations.append(&mut generator.hoisted);
+    }
+
+    let mut out = String::from(
+        "#nullable enable\nusing System.Collections.Generic;\nusing System.Text.Json.Serialization;\n",
+    );
+    for (name, ty) in &aliases {
+        if generator.used_aliases.contains(name) {
+            let _ = writeln!(out, "using {name} = {ty};");
+        }
+    }
+    let _ = write!(out, "\nnamespace {namespace}\n{{\n");
+    for (i, declaration) in declarations.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for line in declaration.lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                let _ = writeln!(out, "{INDENT}{line}");
+            }
+        }
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// C# type name for each typespace entry, unique after sanitizing;
+/// unnamed entries become `Type<index>`
+fn type_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::new();
+    for named in &schema.types {
+        names
+            .entry(named.ty)
+            .or_insert_with(|| unique(&mut taken, csharp_identifier(&named.name.name)));
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(&mut taken, format!("Type{idx}")));
+    }
+    names
+}
+
+struct CSharpGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: &'a HashMap<usize, String>,
+    /// SpacetimeDB types referenced, whose `using` aliases go at the top
+    used_aliases: BTreeSet<&'static str>,
+    /// Classes and enums for anonymous types found inside the declaration
+    /// being generated, emitted right after it
+    hoisted: Vec<String>,
+}
+
+impl CSharpGenerator<'_> {
+    fn class(&mut self, name: &str, product: &ProductType) -> String {
+        let mut out = format!("public partial class {name}\n{{\n");
+        // A member can't share its class's name
+        let mut taken = HashSet::from([name.to_string()]);
+        for (i, element) in product.elements.iter().enumerate() {
+            let original = element.name.as_option();
+            let base = match original {
+                Some(field) => csharp_identifier(&pascal_case(field)),
+                None => format!("Item{}", i + 1),
+            };
+            let property = unique(&mut taken, base);
+            let ty = self.cs_type(&element.algebraic_type, name, &property);
+            if let Some(field) = original {
+                let _ = writeln!(out, "{INDENT}[JsonPropertyName({})]", string_literal(field));
+            }
+            let _ = writeln!(out, "{INDENT}public {ty} {property} {{ get; set; }}");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn sum(&mut self, name: &str, sum: &SumType) -> String {
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+        let all_unit = sum.variants.iter().all(|v| is_unit(&v.algebraic_type));
+
+        // Subclasses can't share the base class's name, nor the `Value`
+        // property they declare
+        let mut taken = if all_unit {
+            HashSet::new()
+        } else {
+            HashSet::from([name.to_string(), "Value".to_string()])
+        };
+        let variant_names: Vec<_> = sum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let base = v
+                    .name
+                    .as_option()
+                    .map_or_else(|| format!("Variant{i}"), csharp_identifier);
+                unique(&mut taken, base)
+            })
+            .collect();
+
+        if all_unit {
+            let mut out = format!("public enum {name}\n{{\n");
+            for variant in &variant_names {
+                let _ = writeln!(out, "{INDENT}{variant},");
+            }
+            out.push_str("}\n");
+            return out;
+        }
+
+        let mut out = format!("public abstract partial class {name}\n{{\n");
+        for (i, (variant, variant_name)) in sum.variants.iter().zip(&variant_names).enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            if is_unit(&variant.algebraic_type) {
+                let _ = writeln!(
+                    out,
+                    "{INDENT}public sealed partial class {variant_name} : {name} {{ }}"
+                );
+            } else {
+                let ty = self.cs_type(&variant.algebraic_type, name, variant_name);
+                let _ = write!(
+                    out,
+                    "{INDENT}public sealed partial class {variant_name} : {name}\n\
+                     {INDENT}{{\n\
+                     {INDENT}{INDENT}public {ty} Value {{ get; set; }}\n\
+                     {INDENT}}}\n"
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// `owner` and `member` name hoisted anonymous types (`PlayerStats`
+    /// for an inline struct in `Player.Stats`)
+    fn cs_type(&mut self, alg_type: &AlgebraicType, owner: &str, member: &str) -> String {
+        let shape = resolve(self.schema, alg_type);
+        self.spell(shape, owner, member)
+    }
+
+    fn spell(&mut self, shape: Shape, owner: &str, member: &str) -> String {
+        match shape {
+            Shape::Primitive(primitive) => match primitive {
+                Primitive::Bool => "bool",
+                Primitive::I8 => "sbyte",
+                Primitive::U8 => "byte",
+                Primitive::I16 => "short",
+                Primitive::U16 => "ushort",
+                Primitive::I32 => "int",
+                Primitive::U32 => "uint",
+                Primitive::I64 => "long",
+                Primitive::U64 => "ulong",
+                Primitive::I128 => "SpacetimeDB.I128",
+                Primitive::U128 => "SpacetimeDB.U128",
+                Primitive::I256 => "SpacetimeDB.I256",
+                Primitive::U256 => "SpacetimeDB.U256",
+                Primitive::F32 => "float",
+                Primitive::F64 => "double",
+                Primitive::String => "string",
+            }
+            .to_string(),
+            Shape::Special(special) => {
+                self.used_aliases.insert(special.name());
+                special.name().to_string()
+            }
+            Shape::Option(inner) => nullable(self.spell(*inner, owner, member)),
+            Shape::Array(element) => match *element {
+                Shape::Primitive(Primitive::U8) => "byte[]".to_string(),
+                element => format!("List<{}>", self.spell(element, owner, member)),
+            },
+            Shape::Map(key, value) => {
+                let key = self.spell(*key, owner, &format!("{member}Key"));
+                let value = self.spell(*value, owner, &format!("{member}Value"));
+                format!("Dictionary<{key}, {value}>")
+            }
+            Shape::Named(idx) => self.names[&idx].clone(),
+            Shape::Product(product)
+                if !product.elements.is_empty()
+                    && product
+                        .elements
+                        .iter()
+                        .all(|e| e.name.as_option().is_none()) =>
+            {
+                let types: Vec<_> = product
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        self.cs_type(&e.algebraic_type, owner, &format!("{member}{}", i + 1))
+                    })
+                    .collect();
+                match types.len() {
+                    1 => format!("System.ValueTuple<{}>", types[0]),
+                    _ => format!("({})", types.join(", ")),
+                }
+            }
+            Shape::Product(product) => {
+                let name = format!("{owner}{}", pascal_case(member));
+                let definition = self.class(&name, product);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Sum(sum) => {
+                let name = format!("{owner}{}", pascal_case(member));
+                let definition = self.sum(&name, sum);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Any => "object".to_string(),
+        }
+    }
+}
+
+/// `T?`; an option of an option can't be told apart in C#, so it stays `T?`
+fn nullable(ty: String) -> String {
+    if ty.ends_with('?') {
+        ty
+    } else {
+        format!("{ty}?")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    fn demo_csharp(overrides: &[(String, String)]) -> String {
+        generate_csharp(&demo_schema(), "SpacetimeDB.Types", overrides).unwrap()
+    }
+
+    #[test]
+    fn structs_keep_the_schema_field_names() {
+        let csharp = demo_csharp(&[]);
+        assert!(
+            csharp.contains(concat!(
+                "        [JsonPropertyName(\"created_at\")]\n",
+                "        public Timestamp CreatedAt { get; set; }\n",
+            )),
+            "{csharp}"
+        );
+        assert!(
+            csharp.contains("        public string? Nickname { get; set; }\n"),
+            "{csharp}"
+        );
+        assert!(
+            csharp.contains("        public List<ItemStack> Inventory { get; set; }\n"),
+            "{csharp}"
+        );
+    }
+
+    #[test]
+    fn enums_with_payloads_become_class_hierarchies() {
+        let csharp = demo_csharp(&[]);
+        assert!(
+            csharp.contains("    public enum Rarity\n    {\n        Common,\n"),
+            "{csharp}"
+        );
+        assert!(
+            csharp.contains("    public abstract partial class PlayerStatus\n"),
+            "{csharp}"
+        );
+        assert!(
+            csharp.contains(concat!(
+                "        public sealed partial class Banned : PlayerStatus\n",
+                "        {\n",
+                "            public string Value { get; set; }\n",
+                "        }\n",
+            )),
+            "{csharp}"
+        );
+    }
+
+    #[test]
+    fn alias_targets_can_be_overridden() {
+        let csharp = demo_csharp(&[("Identity".to_string(), "System.String".to_string())]);
+        assert!(
+            csharp.contains("using Identity = System.String;\n"),
+            "{csharp}"
+        );
+        assert!(
+            csharp.contains("using Timestamp = SpacetimeDB.Timestamp;\n"),
+            "{csharp}"
+        );
+
+        let error = generate_csharp(
+            &demo_schema(),
+            "SpacetimeDB.Types",
+            &[("Uuid".to_string(), "System.Guid".to_string())],
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown type 'Uuid'"), "{error}");
+    }
+
+    #[test]
+    fn recursive_types_refer_to_themselves() {
+        let csharp = generate_csharp(&recursive_schema(), "Trees", &[]).unwrap();
+        assert!(csharp.contains("namespace Trees\n"), "{csharp}");
+        assert!(
+            csharp.contains("        public List<Node> Children { get; set; }\n"),
+            "{csharp}"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use anyhow::{anyhow, Result};
+
+use crate::schema::sats_types::{AlgebraicType, BuiltinType, SatsSchema, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+    is_nullable, is_option_type,
+};
+
+const FIELD_HEADER: [&str; 7] = [
+    "table",
+    "field_index",
+    "field_name",
+    "resolved_type",
+    "is_primary_key",
+    "is_optional",
+    "is_array",
+];
+
+const TYPE_HEADER: [&str; 5] = [
+    "type",
+    "kind",
+    "member_index",
+    "member_name",
+    "resolved_type",
+];
+
+/// Render a spreadsheet-friendly dump: one row per table field (just the
+/// `--table` one's fields if given), then, after a blank line, one row per
+/// field of each standalone struct and per variant of each enum. With
+/// `types_only` only the second section is written.
+///
+/// Values containing the delimiter, quotes or line breaks are quoted as in
+/// RFC 4180. Unit variants have an empty type. Rows are written to `out`
+/// as they are produced.
+pub fn write_csv(
+    out: &mut impl Write,
+    schema: &SatsSchema,
+    table: Option<&str>,
+    delimiter: char,
+    types_only: bool,
+) -> Result<()> {
+    let type_names = build_type_names(schema);
+
+    if !types_only {
+        let tables: Vec<_> = match table {
+            Some(name) => {
+                let table = schema
+                    .tables
+                    .iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+                vec![table]
+            }
+            None => schema.tables.iter().collect(),
+        };
+
+        write_row(out, delimiter, FIELD_HEADER)?;
+        for table in tables {
+            let Some(TypeDef::Product { Product }) =
+                schema.typespace.types.get(table.product_type_ref)
+            else {
+                continue;
+            };
+            for (i, element) in Product.elements.iter().enumerate() {
+                write_row(
+                    out,
+                    delimiter,
+                    [
+                        table.name.as_str(),
+                        &i.to_string(),
+                        element.name.as_option().unwrap_or_default(),
+                        &format_type(schema, &element.algebraic_type, &type_names),
+                        bool_cell(table.primary_key.contains(&i)),
+                        bool_cell(is_nullable(schema, &element.algebraic_type)),
+                        bool_cell(is_array(schema, &element.algebraic_type)),
+                    ],
+                )?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    let table_refs: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+    write_row(out, delimiter, TYPE_HEADER)?;
+    for named in &schema.types {
+        let name = named.name.name.as_str();
+        match schema.typespace.types.get(named.ty) {
+            Some(TypeDef::Product { Product })
+                if !table_refs.contains(&named.ty)
+                    && detect_spacetimedb_type(Product).is_none() =>
+            {
+                for (i, element) in Product.elements.iter().enumerate() {
+                    write_row(
+                        out,
+                        delimiter,
+                        [
+                            name,
+                            "struct",
+                            &i.to_string(),
+                            element.name.as_option().unwrap_or_default(),
+                            &format_type(schema, &element.algebraic_type, &type_names),
+                        ],
+                    )?;
+                }
+            }
+            Some(TypeDef::Sum { Sum })
+                if detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum) =>
+            {
+                for (i, variant) in Sum.variants.iter().enumerate() {
+                    write_row(
+                        out,
+                        delimiter,
+                        [
+                            name,
+                            "enum",
+                            &i.to_string(),
+                            variant.name.as_option().unwrap_or_default(),
+                            &payload_type(schema, &variant.algebraic_type, &type_names),
+                        ],
+                    )?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn payload_type(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+) -> String {
+    match alg_type {
+        AlgebraicType::Product { Product } if Product.elements.is_empty() => String::new(),
+        payload => format_type(schema, payload, type_names),
+    }
+}
+
+/// `Vec<T>`, written inline or as a named alias
+fn is_array(schema: &SatsSchema, alg_type: &AlgebraicType) -> bool {
+    match alg_type {
+        AlgebraicType::Array { .. } => true,
+        AlgebraicType::Ref { Ref } => matches!(
+            schema.typespace.types.get(*Ref as usize),
+            Some(TypeDef::Builtin {
+                Builtin: BuiltinType::Array { .. }
+            })
+        ),
+        _ => false,
+    }
+}
+
+fn bool_cell(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+fn write_row<const N: usize>(
+    out: &mut impl Write,
+    delimiter: char,
+    cells: [&str; N],
+) -> io::Result<()> {
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            write!(out, "{delimiter}")?;
+        }
+        out.write_all(quote(cell, delimiter).as_bytes())?;
+    }
+    writeln!(out)
+}
+
+/// RFC 4180: quote cells containing the delimiter, a quote or a line
+/// break, doubling embedded quotes
+fn quote(cell: &str, delimiter: char) -> String {
+    if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, wide_schema, CountingWriter};
+
+    #[test]
+    fn demo_schema_matches_fixture() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &demo_schema(), None, ',', false).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            include_str!("../tests/fixtures/demo.csv")
+        );
+    }
+
+    #[test]
+    fn large_schema_is_written_in_small_pieces() {
+        let mut out = CountingWriter::default();
+        write_csv(&mut out, &wide_schema(5000), None, ',', false).unwrap();
+        assert!(out.total > 500_000, "wrote {} bytes", out.total);
+        assert!(
+            out.largest_write <= 4096,
+            "wrote {} bytes at once",
+            out.largest_write
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::schema::sats_types::{AlgebraicType, SatsSchema, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+};
+
+/// Render the schema as a Graphviz digraph: tables are record nodes listing
+/// their fields, standalone structs and enums get their own node shapes, and
+/// each table has an edge to every named type it uses. Edges for types a
+/// field names directly are labeled with the field; types only reached
+/// through another type are dashed.
+pub fn generate_dot(schema: &SatsSchema) -> String {
+    let type_names = build_type_names(schema);
+    let table_refs: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+
+    let mut out = String::from("digraph schema {\n");
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [fontname=\"Helvetica\", fontsize=10];\n");
+    out.push_str("    edge [fontname=\"Helvetica\", fontsize=9];\n");
+
+    out.push('\n');
+    for table in &schema.tables {
+        let mut fields = Vec::new();
+        if let Some(TypeDef::Product { Product }) =
+            schema.typespace.types.get(table.product_type_ref)
+        {
+            for (i, element) in Product.elements.iter().enumerate() {
+                let name = element
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                let key = if table.primary_key.contains(&i) {
+                    " (pk)"
+                } else {
+                    ""
+                };
+                fields.push(format!(
+                    "{}: {}{key}\\l",
+                    record_escape(&name),
+                    record_escape(&format_type(schema, &element.algebraic_type, &type_names))
+                ));
+            }
+        }
+        let _ = writeln!(
+            out,
+            "    {} [shape=record, label=\"{{{}|{}}}\"];",
+            table_id(&table.name),
+            record_escape(&table.name),
+            fields.join("")
+        );
+    }
+
+    let mut standalone: Vec<(usize, &String)> = type_names
+        .iter()
+        .filter(|(idx, _)| !table_refs.contains(idx) && is_diagram_type(schema, **idx))
+        .map(|(idx, name)| (*idx, name))
+        .collect();
+    standalone.sort_by_key(|(_, name)| name.to_lowercase());
+    if !standalone.is_empty() {
+        out.push('\n');
+    }
+    for (idx, name) in &standalone {
+        let shape = match schema.typespace.types.get(*idx) {
+            Some(TypeDef::Sum { .. }) => "shape=ellipse, style=filled, fillcolor=\"#fff3cd\"",
+            _ => "shape=box, style=rounded",
+        };
+        let _ = writeln!(
+            out,
+            "    {} [{shape}, label={}];",
+            type_id(name),
+            quoted(name)
+        );
+    }
+
+    let node_id = |idx: usize| -> String {
+        match schema.tables.iter().find(|t| t.product_type_ref == idx) {
+            Some(table) => table_id(&table.name),
+            None => type_id(&type_names[&idx]),
+        }
+    };
+
+    let mut wrote_edges = false;
+    for table in &schema.tables {
+        let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+        else {
+            continue;
+        };
+
+        // Named types each field uses directly, with the fields that use them
+        let mut direct: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for (i, element) in Product.elements.iter().enumerate() {
+            let field = element
+                .name
+                .as_option()
+                .map_or_else(|| i.to_string(), str::to_string);
+            let mut found = BTreeSet::new();
+            named_refs(
+                schema,
+                &type_names,
+                &element.algebraic_type,
+                &mut found,
+                &mut HashSet::new(),
+            );
+            for idx in found {
+                direct.entry(idx).or_default().push(field.clone());
+            }
+        }
+
+        // Everything reachable from those through other named types
+        let mut indirect = BTreeSet::new();
+        let mut queue: Vec<usize> = direct.keys().copied().collect();
+        let mut seen: HashSet<usize> = queue.iter().copied().collect();
+        seen.insert(table.product_type_ref);
+        while let Some(idx) = queue.pop() {
+            let mut found = BTreeSet::new();
+            named_refs_in_def(schema, &type_names, idx, &mut found, &mut HashSet::new());
+            for next in found {
+                if seen.insert(next) {
+                    indirect.insert(next);
+                    queue.push(next);
+                }
+            }
+        }
+
+        if (!direct.is_empty() || !indirect.is_empty()) && !wrote_edges {
+            out.push('\n');
+            wrote_edges = true;
+        }
+        let from = table_id(&table.name);
+        for (idx, fields) in &direct {
+            if *idx == table.product_type_ref {
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                "    {from} -> {} [label={}];",
+                node_id(*idx),
+                quoted(&fields.join(", "))
+            );
+        }
+        for idx in &indirect {
+            let _ = writeln!(out, "    {from} -> {} [style=dashed];", node_id(*idx));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Named typespace entries that get a node: SpacetimeDB's own types
+/// (`Identity`, `ScheduledAt`, ...) are shown as field types only
+fn is_diagram_type(schema: &SatsSchema, idx: usize) -> bool {
+    match schema.typespace.types.get(idx) {
+        Some(TypeDef::Product { Product }) => detect_spacetimedb_type(Product).is_none(),
+        Some(TypeDef::Sum { Sum }) => detect_spacetimedb_sum_type(Sum).is_none(),
+        _ => false,
+    }
+}
+
+/// Collect the named types `alg_type` mentions, without descending into
+/// them. Unnamed refs are followed into the typespace; `visiting` stops
+/// cycles among those.
+fn named_refs(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    alg_type: &AlgebraicType,
+    found: &mut BTreeSet<usize>,
+    visiting: &mut HashSet<usize>,
+) {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => {
+            let idx = *Ref as usize;
+            if type_names.contains_key(&idx) {
+                if is_diagram_type(schema, idx) {
+                    found.insert(idx);
+                }
+            } else if visiting.insert(idx) {
+                named_refs_in_def(schema, type_names, idx, found, visiting);
+            }
+        }
+        AlgebraicType::Array { Array } => named_refs(schema, type_names, Array, found, visiting),
+        AlgebraicType::Map { Map } => {
+            named_refs(schema, type_names, &Map.key_ty, found, visiting);
+            named_refs(schema, type_names, &Map.ty, found, visiting);
+        }
+        AlgebraicType::Product { Product } => {
+            for element in &Product.elements {
+                named_refs(schema, type_names, &element.algebraic_type, found, visiting);
+            }
+        }
+        AlgebraicType::Sum { Sum } => {
+            for variant in &Sum.variants {
+                named_refs(schema, type_names, &variant.algebraic_type, found, visiting);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn named_refs_in_def(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    idx: usize,
+    found: &mut BTreeSet<usize>,
+    visiting: &mut HashSet<usize>,
+) {
+    match schema.typespace.types.get(idx) {
+        Some(TypeDef::Product { Product }) => {
+            for element in &Product.elements {
+                named_refs(schema, type_names, &element.algebraic_type, found, visiting);
+            }
+        }
+        Some(TypeDef::Sum { Sum }) => {
+            for variant in &Sum.variants {
+                named_refs(schema, type_names, &variant.algebraic_type, found, visiting);
+            }
+        }
+        Some(TypeDef::Ref { Ref }) => {
+            let alias = AlgebraicType::Ref { Ref: *Ref };
+            named_refs(schema, type_names, &alias, found, visiting);
+        }
+        _ => {}
+    }
+}
+
+fn table_id(name: &str) -> String {
+    quoted(&format!("table:{name}"))
+}
+
+fn type_id(name: &str) -> String {
+    quoted(&format!("type:{name}"))
+}
+
+/// A double-quoted DOT string
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Escape the characters that structure a record label, plus the ones a
+/// quoted DOT string needs escaped
+fn record_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema, schema};
+    use serde_json::json;
+
+    #[test]
+    fn demo_schema_matches_fixture() {
+        assert_eq!(
+            generate_dot(&demo_schema()),
+            include_str!("../tests/fixtures/demo.dot")
+        );
+    }
+
+    #[test]
+    fn cycles_between_types_terminate() {
+        let dot = generate_dot(&recursive_schema());
+        assert!(dot.contains("\"table:tree\" [shape=record"), "{dot}");
+        assert!(dot.ends_with("}\n"));
+
+        // A <-> B through unnamed entries only, which the walk follows
+        let dot = generate_dot(&schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"some": "a"}, "algebraic_type": {"Ref": 1}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "b"}, "algebraic_type": {"Array": {"Ref": 2}}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "back"}, "algebraic_type": {"Ref": 1}},
+                    {"name": {"some": "leaf"}, "algebraic_type": {"Ref": 3}},
+                ]}},
+                {"Sum": {"variants": [
+                    {"name": {"some": "On"}, "algebraic_type": {"Product": {"elements": []}}},
+                ]}},
+            ]},
+            "tables": [{"name": "root", "product_type_ref": 0, "primary_key": []}],
+            "types": [
+                {"name": {"scope": [], "name": "Root"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Flag"}, "ty": 3, "custom_ordering": true},
+            ],
+        })));
+        assert!(
+            dot.contains("    \"table:root\" -> \"type:Flag\" [label=\"a\"];\n"),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn types_reached_through_other_types_get_dashed_edges() {
+        let dot = generate_dot(&schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"some": "home"}, "algebraic_type": {"Ref": 1}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "kind"}, "algebraic_type": {"Ref": 2}},
+                ]}},
+                {"Sum": {"variants": [
+                    {"name": {"some": "House"}, "algebraic_type": {"Product": {"elements": []}}},
+                ]}},
+            ]},
+            "tables": [{"name": "person", "product_type_ref": 0, "primary_key": [0]}],
+            "types": [
+                {"name": {"scope": [], "name": "Person"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Address"}, "ty": 1, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Kind"}, "ty": 2, "custom_ordering": true},
+            ],
+        })));
+        assert!(
+            dot.contains("    \"table:person\" -> \"type:Address\" [label=\"home\"];\n"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("    \"table:person\" -> \"type:Kind\" [style=dashed];\n"),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn spacetimedb_types_are_field_types_not_nodes() {
+        let dot = generate_dot(&schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"some": "owner"}, "algebraic_type": {"Ref": 1}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "__identity__"}, "algebraic_type": {"U256": []}},
+                ]}},
+            ]},
+            "tables": [{"name": "pet", "product_type_ref": 0, "primary_key": []}],
+            "types": [
+                {"name": {"scope": [], "name": "Pet"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Identity"}, "ty": 1, "custom_ordering": true},
+            ],
+        })));
+        assert!(dot.contains("owner: Identity\\l"), "{dot}");
+        assert!(!dot.contains("type:Identity"), "{dot}");
+    }
+
+    #[test]
+    fn record_labels_escape_their_structure() {
+        assert_eq!(record_escape("a|b{c}<d>"), "a\\|b\\{c\\}\\<d\\>");
+        assert_eq!(quoted("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::codegen::{is_declared, resolve, unique, Primitive, Shape, Special};
+use crate::identifiers::{go_identifier, pascal_case, string_literal};
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+
+const GO_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Declared when a field uses ScheduledAt
+const SCHEDULE_AT: &str = "\
+// ScheduleAt is when a scheduled reducer runs: every Interval, or once at Time.
+type ScheduleAt struct {
+\tInterval *time.Duration `json:\"Interval,omitempty\"`
+\tTime     *time.Time     `json:\"Time,omitempty\"`
+}
+";
+
+/// Render Go declarations for the schema in `package`: a struct per
+/// struct with exported fields tagged with the schema's field names, a
+/// `string` type with a constant per variant for enums whose variants
+/// carry no data, and for the rest a struct with one pointer field per
+/// variant, of which exactly one is set.
+///
+/// Options become pointers and arrays slices; Identity is `[]byte`,
+/// Timestamp `time.Time` and 128- and 256-bit integers `*big.Int`. The
+/// output is laid out the way gofmt would.
+pub fn generate_go(schema: &SatsSchema, package: &str) -> Result<String> {
+    if !is_package_name(package) {
+        return Err(anyhow!(
+            "--go-package: '{package}' is not a valid Go package name"
+        ));
+    }
+
+    let names = type_names(schema);
+    let mut generator = GoGenerator {
+        schema,
+        names: &names,
+        taken: names.values().cloned().collect(),
+        imports: BTreeSet::new(),
+        schedule_at: false,
+        hoisted: Vec::new(),
+    };
+
+    let mut entries: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| is_declared(schema, *idx))
+        .collect();
+    entries.sort_by(|a, b| names[a].cmp(&names[b]));
+
+    let mut declarations = Vec::new();
+    for idx in entries {
+        let name = &names[&idx];
+        let declaration = match &schema.typespace.types[idx] {
+            TypeDef::Product { Product } => generator.strukt(name, Product),
+            TypeDef::Sum { Sum } => generator.sum(name, Sum),
+            _ => continue,
+        };
+        declarations.push(declaration);
+        declarations.append(&mut generator.hoisted);
+    }
+    if generator.schedule_at {
+        declarations.push(SCHEDULE_AT.to_string());
+    }
+
+    let mut out =
+        format!("// Code generated by spacetime-schema-tool. DO NOT EDIT.\n\npackage {package}\n");
+    match generator.imports.len() {
+        0 => {}
+        1 => {
+            let _ = writeln!(out, "\nimport \"{}\"", generator.imports.first().unwrap());
+        }
+        _ => {
+            out.push_str("\nimport (\n");
+            for import in &generator.imports {
+                let _ = writeln!(out, "\t\"{import}\"");
+            }
+            out.push_str(")\n");
+        }
+    }
+    for declaration in &declarations {
+        out.push('\n');
+        out.push_str(declaration);
+    }
+    Ok(out)
+}
+
+fn is_package_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "_"
+        && !GO_KEYWORDS.contains(&name)
+}
+
+/// Go type name for each typespace entry, unique after sanitizing (so
+/// `player` and `Player` don't both become `Player`); unnamed entries
+/// become `Type<index>`. `ScheduleAt` is kept free for the declaration
+/// above.
+fn type_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::from(["ScheduleAt".to_string()]);
+    for named in &schema.types {
+        names
+            .entry(named.ty)
+            .or_insert_with(|| unique(&mut taken, go_identifier(&named.name.name)));
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(&mut taken, format!("Type{idx}")));
+    }
+    names
+}
+
+/// One struct field: name, type and, for named fields, the tag
+struct Field {
+    name: String,
+    ty: String,
+    tag: Option<String>,
+}
+
+struct GoGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: &'a HashMap<usize, String>,
+    /// Package-level names in use: types, hoisted types and constants
+    taken: HashSet<String>,
+    imports: BTreeSet<&'static str>,
+    /// Whether the ScheduleAt struct is needed
+    schedule_at: bool,
+    /// Types for anonymous structs and enums found inside the declaration
+    /// being generated, emitted right after it
+    hoisted: Vec<String>,
+}
+
+impl GoGenerator<'_> {
+    fn strukt(&mut self, name: &str, product: &ProductType) -> String {
+        let mut taken = HashSet::new();
+        let mut fields = Vec::new();
+        for (i, element) in product.elements.iter().enumerate() {
+            let original = element.name.as_option();
+            let base = original.map_or_else(|| format!("Field{i}"), go_identifier);
+            let field = unique(&mut taken, base);
+            let ty = self.go_type(&element.algebraic_type, name, &field);
+            fields.push(Field {
+                name: field,
+                ty,
+                tag: original.map(|original| json_tag(original, false)),
+            });
+        }
+        struct_declaration(name, &fields)
+    }
+
+    fn sum(&mut self, name: &str, sum: &SumType) -> String {
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+
+        if sum.variants.iter().all(|v| is_unit(&v.algebraic_type)) {
+            let mut out = format!("type {name} string\n");
+            if sum.variants.is_empty() {
+                return out;
+            }
+            let constants: Vec<_> = sum
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let variant = v
+                        .name
+                        .as_option()
+                        .map_or_else(|| format!("Variant{i}"), go_identifier);
+                    let value = v
+                        .name
+                        .as_option()
+                        .map_or_else(|| i.to_string(), str::to_string);
+                    (
+                        unique(&mut self.taken, format!("{name}{variant}")),
+                        string_literal(&value),
+                    )
+                })
+                .collect();
+            let width = constants.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+            out.push_str("\nconst (\n");
+            for (constant, value) in constants {
+                let _ = writeln!(out, "\t{constant:width$} {name} = {value}");
+            }
+            out.push_str(")\n");
+            return out;
+        }
+
+        let mut taken = HashSet::new();
+        let mut fields = Vec::new();
+        for (i, variant) in sum.variants.iter().enumerate() {
+            let original = variant.name.as_option();
+            let base = original.map_or_else(|| format!("Variant{i}"), go_identifier);
+            let field = unique(&mut taken, base);
+            let ty = if is_unit(&variant.algebraic_type) {
+                "*struct{}".to_string()
+            } else {
+                pointer(self.go_type(&variant.algebraic_type, name, &field))
+            };
+            let tag = json_tag(original.unwrap_or(&i.to_string()), true);
+            fields.push(Field {
+                name: field,
+                ty,
+                tag: Some(tag),
+            });
+        }
+        format!(
+            "// {name} is a tagged union: exactly one field is set.\n{}",
+            struct_declaration(name, &fields)
+        )
+    }
+
+    /// `owner` and `member` name hoisted anonymous types (`PlayerStats`
+    /// for an inline struct in `Player.Stats`)
+    fn go_type(&mut self, alg_type: &AlgebraicType, owner: &str, member: &str) -> String {
+        let shape = resolve(self.schema, alg_type);
+        self.spell(shape, owner, member)
+    }
+
+    fn spell(&mut self, shape: Shape, owner: &str, member: &str) -> String {
+        match shape {
+            Shape::Primitive(primitive) => match primitive {
+                Primitive::Bool => "bool".to_string(),
+                Primitive::I8 => "int8".to_string(),
+                Primitive::U8 => "uint8".to_string(),
+                Primitive::I16 => "int16".to_string(),
+                Primitive::U16 => "uint16".to_string(),
+                Primitive::I32 => "int32".to_string(),
+                Primitive::U32 => "uint32".to_string(),
+                Primitive::I64 => "int64".to_string(),
+                Primitive::U64 => "uint64".to_string(),
+                Primitive::I128 | Primitive::U128 | Primitive::I256 | Primitive::U256 => {
+                    self.imports.insert("math/big");
+                    "*big.Int".to_string()
+                }
+                Primitive::F32 => "float32".to_string(),
+                Primitive::F64 => "float64".to_string(),
+                Primitive::String => "string".to_string(),
+            },
+            Shape::Special(special) => self.special(special),
+            Shape::Option(inner) => pointer(self.spell(*inner, owner, member)),
+            Shape::Array(element) => match *element {
+                Shape::Primitive(Primitive::U8) => "[]byte".to_string(),
+                element => format!("[]{}", self.spell(element, owner, member)),
+            },
+            Shape::Map(key, value) => {
+                let key = self.spell(*key, owner, &format!("{member}Key"));
+                let value = self.spell(*value, owner, &format!("{member}Value"));
+                format!("map[{key}]{value}")
+            }
+            Shape::Named(idx) => self.names[&idx].clone(),
+            Shape::Product(product) if product.elements.is_empty() => "struct{}".to_string(),
+            Shape::Product(product) => {
+                let name = unique(&mut self.taken, format!("{owner}{}", pascal_case(member)));
+                let definition = self.strukt(&name, product);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Sum(sum) => {
+                let name = unique(&mut self.taken, format!("{owner}{}", pascal_case(member)));
+                let definition = self.sum(&name, sum);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Any => "any".to_string(),
+        }
+    }
+
+    fn special(&mut self, special: Special) -> String {
+        match special {
+            Special::Identity => "[]byte".to_string(),
+            Special::Timestamp => {
+                self.imports.insert("time");
+                "time.Time".to_string()
+            }
+            Special::Duration => {
+                self.imports.insert("time");
+                "time.Duration".to_string()
+            }
+            Special::ScheduledAt => {
+                self.imports.insert("time");
+                self.schedule_at = true;
+                "ScheduleAt".to_string()
+            }
+        }
+    }
+}
+
+/// `*T`; an option of an option can't be told apart in Go, and a value
+/// that is already a pointer is nullable as it is
+fn pointer(ty: String) -> String {
+    if ty.starts_with('*') {
+        ty
+    } else {
+        format!("*{ty}")
+    }
+}
+
+/// `json:"name"` as a raw string, or as an interpreted one when the name
+/// holds characters a raw string can't
+fn json_tag(name: &str, omitempty: bool) -> String {
+    let value = if omitempty {
+        format!("{name},omitempty")
+    } else {
+        name.to_string()
+    };
+    let tag = format!("json:{}", string_literal(&value));
+    if tag.contains('`') || value.contains(['"', '\\']) || value.chars().any(char::is_control) {
+        string_literal(&tag)
+    } else {
+        format!("`{tag}`")
+    }
+}
+
+/// A struct type with its fields in gofmt's columns: names padded to the
+/// longest name, and types padded to the longest type among the adjacent
+/// fields that carry a tag
+fn struct_declaration(name: &str, fields: &[Field]) -> String {
+    if fields.is_empty() {
+        return format!("type {name} struct{{}}\n");
+    }
+    let name_width = fields.iter().map(|f| f.name.len()).max().unwrap_or(0);
+    let mut out = format!("type {name} struct {{\n");
+    let mut i = 0;
+    while i < fields.len() {
+        // Fields up to the next change between tagged and untagged
+        let tagged = fields[i].tag.is_some();
+        let end = fields[i..]
+            .iter()
+            .position(|f| f.tag.is_some() != tagged)
+            .map_or(fields.len(), |n| i + n);
+        let type_width = fields[i..end].iter().map(|f| f.ty.len()).max().unwrap_or(0);
+        for field in &fields[i..end] {
+            match &field.tag {
+                Some(tag) => {
+                    let _ = writeln!(
+                        out,
+                        "\t{:name_width$} {:type_width$} {tag}",
+                        field.name, field.ty
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "\t{:name_width$} {}", field.name, field.ty);
+                }
+            }
+        }
+        i = end;
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema, schema};
+    use serde_json::json;
+
+    #[test]
+    fn structs_tag_fields_with_the_schema_names() {
+        let go = generate_go(&demo_schema(), "schema").unwrap();
+        assert!(go.starts_with("// Code generated by spacetime-schema-tool. DO NOT EDIT.\n\npackage schema\n\nimport \"time\"\n"), "{go}");
+        assert!(
+            go.contains(concat!(
+                "type Guild struct {\n",
+                "\tId      uint64    `json:\"id\"`\n",
+                "\tName    string    `json:\"name\"`\n",
+                "\tFounded time.Time `json:\"founded\"`\n",
+                "\tMotto   *string   `json:\"motto\"`\n",
+                "}\n",
+            )),
+            "{go}"
+        );
+        assert!(
+            go.contains("\tInventory []ItemStack  `json:\"inventory\"`\n"),
+            "{go}"
+        );
+    }
+
+    #[test]
+    fn enums_become_constants_or_tagged_unions() {
+        let go = generate_go(&demo_schema(), "schema").unwrap();
+        assert!(
+            go.contains(concat!(
+                "type GuildRole string\n\n",
+                "const (\n",
+                "\tGuildRoleMember  GuildRole = \"Member\"\n",
+            )),
+            "{go}"
+        );
+        assert!(
+            go.contains(concat!(
+                "type PlayerStatus struct {\n",
+                "\tOnline *struct{} `json:\"Online,omitempty\"`\n",
+                "\tAway   *struct{} `json:\"Away,omitempty\"`\n",
+                "\tBanned *string   `json:\"Banned,omitempty\"`\n",
+                "}\n",
+            )),
+            "{go}"
+        );
+        assert!(go.ends_with("\tTime     *time.Time     `json:\"Time,omitempty\"`\n}\n"));
+    }
+
+    #[test]
+    fn names_that_differ_only_in_case_stay_distinct() {
+        let go = generate_go(
+            &schema(json!({
+                "typespace": {"types": [
+                    {"Product": {"elements": [
+                        {"name": {"some": "balance"}, "algebraic_type": {"U128": []}},
+                    ]}},
+                    {"Product": {"elements": [
+                        {"name": {"some": "id"}, "algebraic_type": {"U32": []}},
+                    ]}},
+                ]},
+                "tables": [{"name": "player", "product_type_ref": 0, "primary_key": []}],
+                "types": [
+                    {"name": {"scope": [], "name": "player"}, "ty": 0, "custom_ordering": true},
+                    {"name": {"scope": [], "name": "Player"}, "ty": 1, "custom_ordering": true},
+                ],
+            })),
+            "schema",
+        )
+        .unwrap();
+        assert!(go.contains("type Player struct {\n"), "{go}");
+        assert!(go.contains("type Player2 struct {\n"), "{go}");
+        assert!(go.contains("\nimport \"math/big\"\n"), "{go}");
+        assert!(
+            go.contains("\tBalance *big.Int `json:\"balance\"`\n"),
+            "{go}"
+        );
+    }
+
+    #[test]
+    fn recursive_types_refer_to_themselves() {
+        let go = generate_go(&recursive_schema(), "trees").unwrap();
+        assert!(go.contains("\npackage trees\n"), "{go}");
+        assert!(
+            go.contains("\tChildren []Node `json:\"children\"`\n"),
+            "{go}"
+        );
+    }
+
+    #[test]
+    fn package_names_must_be_go_identifiers() {
+        for package in ["9lives", "my-schema", "_", "func", ""] {
+            let error = generate_go(&demo_schema(), package).unwrap_err();
+            assert!(error.to_string().contains("--go-package"), "{error}");
+        }
+        assert!(generate_go(&demo_schema(), "game_v2").is_ok());
+    }
+}
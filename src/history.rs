@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ascii::glyphs;
+use crate::output::outln;
+
+/// Fingerprint changes kept per database; older ones are dropped
+const MAX_ENTRIES_PER_DB: usize = 50;
+
+/// Fetch history keyed by server URL, then database name
+type History = BTreeMap<String, BTreeMap<String, Vec<HistoryEntry>>>;
+
+/// A run of consecutive fetches that all saw the same schema
+#[derive(Debug, Deserialize, Serialize)]
+struct HistoryEntry {
+    fingerprint: String,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    fetches: u64,
+}
+
+/// Name of the algorithm behind [`fingerprint`], for tools that compare fingerprints
+pub const FINGERPRINT_ALGORITHM: &str = "fnv1a-64/sorted-json";
+
+/// FNV-1a hash of the schema's JSON text. `serde_json` keeps object keys
+/// sorted, so the same schema always hashes the same.
+pub fn fingerprint(schema_json: &Value) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in schema_json.to_string().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// Record a successful fetch. Best effort: failures are ignored so that
+/// history problems never break the command itself.
+pub fn record_fetch(server: &str, db: &str, schema_json: &Value) {
+    let _ = try_record_fetch(server, db, &fingerprint(schema_json));
+}
+
+fn try_record_fetch(server: &str, db: &str, fingerprint: &str) -> Result<()> {
+    let path = history_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Serialize concurrent runs; the lock is released when the file is dropped
+    let lock = File::create(path.with_extension("lock"))?;
+    lock.lock()?;
+
+    let mut history = read_history(&path);
+    let entries = history
+        .entry(server.to_string())
+        .or_default()
+        .entry(db.to_string())
+        .or_default();
+
+    let now = Utc::now();
+    match entries.last_mut() {
+        Some(last) if last.fingerprint == fingerprint => {
+            last.last_seen = now;
+            last.fetches += 1;
+        }
+        _ => entries.push(HistoryEntry {
+            fingerprint: fingerprint.to_string(),
+            first_seen: now,
+            last_seen: now,
+            fetches: 1,
+        }),
+    }
+    if entries.len() > MAX_ENTRIES_PER_DB {
+        entries.drain(..entries.len() - MAX_ENTRIES_PER_DB);
+    }
+
+    // Write to a temporary file first so readers never see a partial file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&history)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Print when this tool last fetched the database's schema and when the
+/// schema last changed
+pub fn display_last_seen(server: &str, db: &str) -> Result<()> {
+    let history = read_history(&history_path()?);
+    let Some(entries) = history
+        .get(server)
+        .and_then(|dbs| dbs.get(db))
+        .filter(|e| !e.is_empty())
+    else {
+        outln!(
+            "{} No fetches of '{}' from {} recorded yet",
+            glyphs("ℹ️").blue(),
+            db,
+            server
+        );
+        return Ok(());
+    };
+
+    let latest = &entries[entries.len() - 1];
+    let distinct: HashSet<_> = entries.iter().map(|e| &e.fingerprint).collect();
+
+    outln!("\n{} {}", glyphs("🕒 LAST SEEN:").yellow(), db.bold());
+    outln!("{}", "-".repeat(40));
+    outln!("  Server:          {server}");
+    outln!("  Last fetch:      {}", format_time(latest.last_seen));
+    outln!(
+        "  Last change:     {}",
+        if entries.len() > 1 {
+            format_time(latest.first_seen)
+        } else {
+            format!("none seen since {}", format_time(latest.first_seen))
+        }
+    );
+    outln!("  Fingerprint:     {}", latest.fingerprint.dimmed());
+    outln!("  Distinct seen:   {}", distinct.len());
+    outln!(
+        "  Fetches:         {}",
+        entries.iter().map(|e| e.fetches).sum::<u64>()
+    );
+    Ok(())
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// A missing or unreadable history file is treated as empty
+fn read_history(path: &Path) -> History {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn history_path() -> Result<PathBuf> {
+    let dir = state_dir().ok_or_else(|| anyhow!("Could not find config directory"))?;
+    Ok(dir.join("history.json"))
+}
+
+/// Where the tool keeps what it remembers between runs: the fetch history
+/// and whether the marker legend was shown. `SPACETIME_SCHEMA_TOOL_STATE_DIR`
+/// overrides the default under the config directory.
+pub fn state_dir() -> Option<PathBuf> {
+    match std::env::var_os("SPACETIME_SCHEMA_TOOL_STATE_DIR") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => dirs::config_dir().map(|dir| dir.join("spacetime-schema-tool")),
+    }
+}
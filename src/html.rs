@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::metadata::SourceMetadata;
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type,
+    is_option_type,
+};
+
+const STYLE: &str = "\
+body { margin: 0; font-family: system-ui, sans-serif; color: #1f2328; display: flex; }
+nav { position: sticky; top: 0; height: 100vh; overflow-y: auto; box-sizing: border-box;
+  width: 16rem; flex-shrink: 0; padding: 1rem; background: #f6f8fa; border-right: 1px solid #d0d7de; }
+nav h2 { font-size: 0.8rem; text-transform: uppercase; color: #656d76; margin: 1.2rem 0 0.4rem; }
+nav ul { list-style: none; margin: 0; padding: 0; }
+nav li { margin: 0.2rem 0; }
+main { flex: 1; min-width: 0; padding: 1rem 2rem 3rem; }
+section { margin-top: 2rem; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+code, td { font-family: ui-monospace, monospace; font-size: 0.9rem; }
+table { border-collapse: collapse; margin-top: 0.5rem; }
+th, td { border: 1px solid #d0d7de; padding: 0.3rem 0.7rem; text-align: left; }
+th { background: #f6f8fa; font-family: system-ui, sans-serif; }
+.badge { display: inline-block; padding: 0 0.45rem; border-radius: 1rem; font-size: 0.8rem;
+  font-family: system-ui, sans-serif; background: #ddf4ff; color: #0550ae; border: 1px solid #54aeff; }
+.pk { background: #fff8c5; color: #7d4e00; border-color: #d4a72c; }
+.muted { color: #656d76; }
+footer { margin-top: 3rem; font-size: 0.8rem; color: #656d76; }
+";
+
+/// Render a self-contained HTML report: a sidebar linking every table,
+/// struct and enum, then a section for each. Field types link to the
+/// section of the named type they use, and SpacetimeDB's own types
+/// (Identity, Timestamp, ...) are shown as badges. Enums over
+/// `collapse_threshold` variants (0 never collapses) are folded into a
+/// `<details>` block.
+///
+/// Sections are written to `out` one at a time, so memory use is bounded
+/// by the largest table rather than the whole report.
+pub fn write_html(
+    out: &mut impl Write,
+    schema: &SatsSchema,
+    db: &str,
+    metadata: Option<&SourceMetadata>,
+    collapse_threshold: usize,
+) -> io::Result<()> {
+    let report = Report::new(schema);
+
+    let mut structs = Vec::new();
+    let mut enums = Vec::new();
+    for named in &schema.types {
+        if report.table_rows.contains_key(&named.ty) {
+            continue;
+        }
+        match schema.typespace.types.get(named.ty) {
+            Some(TypeDef::Product { Product }) => structs.push((named.name.name.as_str(), Product)),
+            Some(TypeDef::Sum { Sum }) => enums.push((named.name.name.as_str(), Sum)),
+            _ => {}
+        }
+    }
+    structs.sort_by_key(|(name, _)| name.to_lowercase());
+    enums.sort_by_key(|(name, _)| name.to_lowercase());
+
+    let title = format!("Schema: {db}");
+    writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    )?;
+    writeln!(out, "<title>{}</title>", escape(&title))?;
+    writeln!(out, "<style>\n{STYLE}</style>\n</head>\n<body>")?;
+
+    out.write_all(b"<nav>\n")?;
+    writeln!(out, "<strong>{}</strong>", escape(db))?;
+    nav_list(
+        out,
+        "Tables",
+        schema
+            .tables
+            .iter()
+            .map(|t| (t.name.as_str(), table_anchor(&t.name))),
+    )?;
+    nav_list(
+        out,
+        "Types",
+        structs.iter().map(|(name, _)| (*name, type_anchor(name))),
+    )?;
+    nav_list(
+        out,
+        "Enums",
+        enums.iter().map(|(name, _)| (*name, type_anchor(name))),
+    )?;
+    out.write_all(b"</nav>\n<main>\n")?;
+    writeln!(out, "<h1>{}</h1>", escape(&title))?;
+
+    for table in &schema.tables {
+        writeln!(
+            out,
+            "<section id=\"{}\">\n<h2>{}</h2>",
+            table_anchor(&table.name),
+            escape(&table.name)
+        )?;
+        let row_type = report
+            .type_names
+            .get(&table.product_type_ref)
+            .cloned()
+            .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
+        writeln!(
+            out,
+            "<p class=\"muted\">Row type <code>{}</code></p>",
+            escape(&row_type)
+        )?;
+        if let Some(schedule) = table.schedule.as_ref().and_then(|s| s.as_option()) {
+            writeln!(
+                out,
+                "<p>Scheduled by reducer <code>{}</code></p>",
+                escape(&schedule.reducer_name)
+            )?;
+        }
+        if let Some(TypeDef::Product { Product }) =
+            schema.typespace.types.get(table.product_type_ref)
+        {
+            report.fields_table(out, Product, &table.primary_key)?;
+        }
+        out.write_all(b"</section>\n")?;
+    }
+
+    for (name, product) in &structs {
+        writeln!(
+            out,
+            "<section id=\"{}\">\n<h2>{} <span class=\"muted\">struct</span></h2>",
+            type_anchor(name),
+            escape(name)
+        )?;
+        match detect_spacetimedb_type(product) {
+            Some(special) => {
+                writeln!(out, "<p>SpacetimeDB type {}</p>", badge(&special))?;
+            }
+            None if product.elements.is_empty() => out.write_all(b"<p>No fields</p>\n")?,
+            None => report.fields_table(out, product, &[])?,
+        }
+        out.write_all(b"</section>\n")?;
+    }
+
+    for (name, sum) in &enums {
+        writeln!(
+            out,
+            "<section id=\"{}\">\n<h2>{} <span class=\"muted\">enum</span></h2>",
+            type_anchor(name),
+            escape(name)
+        )?;
+        match detect_spacetimedb_sum_type(sum) {
+            Some(special) => {
+                writeln!(out, "<p>SpacetimeDB type {}</p>", badge(&special))?;
+            }
+            None if collapse_threshold > 0 && sum.variants.len() > collapse_threshold => {
+                writeln!(
+                    out,
+                    "<details>\n<summary>{} variants</summary>",
+                    sum.variants.len()
+                )?;
+                report.variants_table(out, sum)?;
+                out.write_all(b"</details>\n")?;
+            }
+            None => report.variants_table(out, sum)?,
+        }
+        out.write_all(b"</section>\n")?;
+    }
+
+    if let Some(metadata) = metadata {
+        out.write_all(b"<footer>\n")?;
+        for line in metadata.lines() {
+            writeln!(out, "<div>{}</div>", escape(&line))?;
+        }
+        out.write_all(b"</footer>\n")?;
+    }
+    out.write_all(b"</main>\n</body>\n</html>\n")
+}
+
+struct Report<'a> {
+    schema: &'a SatsSchema,
+    type_names: HashMap<usize, String>,
+    /// Row type index to the first table using it, so references link there
+    table_rows: HashMap<usize, &'a str>,
+}
+
+impl<'a> Report<'a> {
+    fn new(schema: &'a SatsSchema) -> Self {
+        let mut table_rows = HashMap::new();
+        for table in &schema.tables {
+            table_rows
+                .entry(table.product_type_ref)
+                .or_insert(table.name.as_str());
+        }
+        Self {
+            schema,
+            type_names: build_type_names(schema),
+            table_rows,
+        }
+    }
+
+    fn fields_table(
+        &self,
+        out: &mut impl Write,
+        product: &ProductType,
+        primary_key: &[usize],
+    ) -> io::Result<()> {
+        out.write_all(b"<table>\n<tr><th>#</th><th>Field</th><th>Type</th></tr>\n")?;
+        for (i, element) in product.elements.iter().enumerate() {
+            let name = element.name.as_option().unwrap_or_default();
+            let key = if primary_key.contains(&i) {
+                " <span class=\"badge pk\">PK</span>"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "<tr><td>{i}</td><td>{}{key}</td><td>{}</td></tr>",
+                escape(name),
+                self.type_html(&element.algebraic_type)
+            )?;
+        }
+        out.write_all(b"</table>\n")
+    }
+
+    fn variants_table(&self, out: &mut impl Write, sum: &SumType) -> io::Result<()> {
+        out.write_all(b"<table>\n<tr><th>#</th><th>Variant</th><th>Payload</th></tr>\n")?;
+        for (i, variant) in sum.variants.iter().enumerate() {
+            let payload = match &variant.algebraic_type {
+                AlgebraicType::Product { Product } if Product.elements.is_empty() => String::new(),
+                payload => self.type_html(payload),
+            };
+            writeln!(
+                out,
+                "<tr><td>{i}</td><td>{}</td><td>{payload}</td></tr>",
+                escape(variant.name.as_option().unwrap_or_default())
+            )?;
+        }
+        out.write_all(b"</table>\n")
+    }
+
+    /// `format_type` as HTML, with named types linked and SpacetimeDB
+    /// types as badges
+    fn type_html(&self, alg_type: &AlgebraicType) -> String {
+        match alg_type {
+            AlgebraicType::Array { Array } => format!("Vec&lt;{}&gt;", self.type_html(Array)),
+            AlgebraicType::Map { Map } => format!(
+                "HashMap&lt;{}, {}&gt;",
+                self.type_html(&Map.key_ty),
+                self.type_html(&Map.ty)
+            ),
+            AlgebraicType::Ref { Ref } => self.reference_html(*Ref as usize),
+            AlgebraicType::Sum { Sum } => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    badge(&special)
+                } else if is_option_type(Sum) {
+                    let inner = get_option_inner_type(Sum)
+                        .map_or_else(|| "?".to_string(), |inner| self.type_html(inner));
+                    format!("Option&lt;{inner}&gt;")
+                } else {
+                    format!("Sum({} variants)", Sum.variants.len())
+                }
+            }
+            AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+                Some(special) => badge(&special),
+                None if Product.elements.is_empty() => "()".to_string(),
+                None if Product
+                    .elements
+                    .iter()
+                    .all(|e| e.name.as_option().is_none()) =>
+                {
+                    let types: Vec<_> = Product
+                        .elements
+                        .iter()
+                        .map(|e| self.type_html(&e.algebraic_type))
+                        .collect();
+                    format!("({})", types.join(", "))
+                }
+                None => format!("Product({} fields)", Product.elements.len()),
+            },
+            builtin => escape(&crate::schema::format_type(
+                self.schema,
+                builtin,
+                &self.type_names,
+            )),
+        }
+    }
+
+    fn reference_html(&self, idx: usize) -> String {
+        let Some(name) = self.type_names.get(&idx) else {
+            return format!("Type_{idx}");
+        };
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => {
+                if let Some(special) = detect_spacetimedb_type(Product) {
+                    return badge(&special);
+                }
+            }
+            Some(TypeDef::Sum { Sum }) => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    return badge(&special);
+                }
+            }
+            _ => return escape(name),
+        }
+        let anchor = match self.table_rows.get(&idx) {
+            Some(table) => table_anchor(table),
+            None => type_anchor(name),
+        };
+        format!("<a href=\"#{anchor}\">{}</a>", escape(name))
+    }
+}
+
+fn nav_list<'a>(
+    out: &mut impl Write,
+    heading: &str,
+    items: impl Iterator<Item = (&'a str, String)>,
+) -> io::Result<()> {
+    let items: Vec<_> = items.collect();
+    if items.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "<h2>{heading}</h2>\n<ul>")?;
+    for (name, anchor) in items {
+        writeln!(out, "<li><a href=\"#{anchor}\">{}</a></li>", escape(name))?;
+    }
+    out.write_all(b"</ul>\n")
+}
+
+fn badge(text: &str) -> String {
+    format!("<span class=\"badge\">{}</span>", escape(text))
+}
+
+fn table_anchor(name: &str) -> String {
+    format!("table-{}", anchor_text(name))
+}
+
+fn type_anchor(name: &str) -> String {
+    format!("type-{}", anchor_text(name))
+}
+
+/// A fragment identifier: anything but letters, digits, `-` and `_`
+/// becomes `-`
+fn anchor_text(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{big_enum_schema, demo_schema, wide_schema, CountingWriter};
+
+    #[test]
+    fn demo_schema_matches_fixture() {
+        let mut out = Vec::new();
+        write_html(&mut out, &demo_schema(), "demo", None, 50).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            include_str!("../tests/fixtures/demo.html")
+        );
+    }
+
+    #[test]
+    fn large_schema_is_written_in_small_pieces() {
+        let mut out = CountingWriter::default();
+        write_html(&mut out, &wide_schema(5000), "wide", None, 50).unwrap();
+        assert!(out.total > 1 << 20, "wrote {} bytes", out.total);
+        assert!(
+            out.largest_write <= 4096,
+            "wrote {} bytes at once",
+            out.largest_write
+        );
+    }
+
+    fn render(schema: &SatsSchema, collapse_threshold: usize) -> String {
+        let mut out = Vec::new();
+        write_html(&mut out, schema, "game", None, collapse_threshold).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn enums_over_the_threshold_are_folded() {
+        let html = render(&big_enum_schema(400), 50);
+        assert!(html.contains(
+            "<details>\n<summary>400 variants</summary>\n<table>\n<tr><th>#</th><th>Variant</th><th>Payload</th></tr>\n<tr><td>0</td><td>Key0</td><td></td></tr>\n"
+        ));
+        assert!(html.contains(
+            "<tr><td>399</td><td>Key399</td><td></td></tr>\n</table>\n</details>\n</section>"
+        ));
+    }
+
+    #[test]
+    fn enums_at_or_under_the_threshold_stay_open() {
+        assert!(!render(&big_enum_schema(400), 400).contains("<details>"));
+        assert!(render(&big_enum_schema(401), 400).contains("<summary>401 variants</summary>"));
+        assert!(!render(&big_enum_schema(400), 0).contains("<details>"));
+    }
+}
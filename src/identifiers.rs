@@ -0,0 +1,590 @@
+use crate::reserved_names::is_sql_keyword;
+
+/// Words TypeScript rejects as a `const` or `type` alias name
+const TYPESCRIPT_RESERVED: &[&str] = &[
+    "any",
+    "as",
+    "await",
+    "boolean",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "declare",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "never",
+    "new",
+    "null",
+    "number",
+    "object",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "string",
+    "super",
+    "switch",
+    "symbol",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "undefined",
+    "unknown",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// `[A-Za-z_][A-Za-z0-9_]*`, the identifiers every target accepts as-is
+pub fn is_plain_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// SQL: names that aren't plain identifiers or that are keywords are
+/// double-quoted, with embedded quotes doubled
+pub fn sql_identifier(name: &str) -> String {
+    if is_plain_identifier(name) && !is_sql_keyword(name, &[]) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// PostgreSQL: like `sql_identifier`, but names with uppercase letters are
+/// quoted too, since Postgres folds unquoted names to lowercase
+pub fn postgres_identifier(name: &str) -> String {
+    if name.chars().any(|c| c.is_ascii_uppercase()) {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    } else {
+        sql_identifier(name)
+    }
+}
+
+/// TypeScript declarations: characters outside `[A-Za-z0-9_]` become `_`,
+/// a leading digit gets a `_` prefix and reserved words a `_` suffix. The
+/// rename is lossy, so callers must resolve collisions themselves.
+pub fn typescript_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if TYPESCRIPT_RESERVED.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// TypeScript object keys: left bare when they are valid identifiers,
+/// quoted otherwise. Reserved words are valid keys.
+pub fn typescript_key(key: &str) -> String {
+    let bare = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if bare {
+        key.to_string()
+    } else {
+        string_literal(key)
+    }
+}
+
+/// A double-quoted string literal, valid in both TypeScript and JSON
+pub fn string_literal(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// KDL: every value is a quoted string, which is valid under KDL v1 and v2
+pub fn kdl_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Rust keywords, strict and reserved
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Rust identifiers: characters outside `[A-Za-z0-9_]` become `_` and a
+/// leading digit gets a `_` prefix. Keywords become raw identifiers
+/// (`r#type`), except the few that can't be raw, which get a `_` suffix.
+pub fn rust_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    match ident.as_str() {
+        "self" | "Self" | "super" | "crate" => format!("{ident}_"),
+        word if RUST_KEYWORDS.contains(&word) => format!("r#{ident}"),
+        _ => ident,
+    }
+}
+
+/// C# keywords (contextual keywords are valid identifiers and not listed)
+const CSHARP_KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// C# identifiers: characters outside `[A-Za-z0-9_]` become `_`, a leading
+/// digit gets a `_` prefix and keywords are escaped with `@`
+pub fn csharp_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if CSHARP_KEYWORDS.contains(&ident.as_str()) {
+        ident.insert(0, '@');
+    }
+    ident
+}
+
+/// `home_base` -> `HomeBase`: split at anything that isn't a letter or
+/// digit and capitalize each part. The rest of each part is kept as is,
+/// so `createdAt` stays `CreatedAt`.
+pub fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Protobuf names: characters outside `[A-Za-z0-9_]` become `_` and a
+/// leading digit gets a `_` prefix. Protobuf keywords are valid field and
+/// message names, so nothing is escaped.
+pub fn proto_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// Exported Go names: `pascal_case`, with an `X` in front when that leaves
+/// nothing or a leading digit. Go keywords are all lowercase, so an
+/// exported name never collides with one.
+pub fn go_identifier(name: &str) -> String {
+    let ident = pascal_case(name);
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("X{ident}")
+    } else {
+        ident
+    }
+}
+
+/// `home_base` -> `homeBase`: `pascal_case` with the first letter lowered
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Kotlin's hard keywords, which can't be used as names without backticks
+const KOTLIN_KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+/// Kotlin identifiers: characters outside `[A-Za-z0-9_]` become `_`, a
+/// leading digit gets a `_` prefix and keywords are wrapped in backticks
+pub fn kotlin_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if KOTLIN_KEYWORDS.contains(&ident.as_str()) {
+        format!("`{ident}`")
+    } else {
+        ident
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names that are legal in SATS but not as identifiers in some target
+    const ADVERSARIAL: &[&str] = &[
+        "",
+        "_",
+        "user-data",
+        "2fa_settings",
+        "0",
+        "type",
+        "class",
+        "self",
+        "Self",
+        "super",
+        "crate",
+        "fn",
+        "match",
+        "select",
+        "SELECT",
+        "delete",
+        "größe",
+        "a b",
+        "quote\"d",
+        "back\\slash",
+        "new\nline",
+        "🚀",
+        "__proto__",
+        "when",
+        "object",
+        "constructor",
+        "r#raw",
+    ];
+
+    fn check_all(target: &str, sanitize: fn(&str) -> String, valid: impl Fn(&str) -> bool) {
+        for name in ADVERSARIAL {
+            let ident = sanitize(name);
+            assert!(valid(&ident), "{target}: {name:?} became {ident:?}");
+            assert_eq!(
+                sanitize(name),
+                ident,
+                "{target}: {name:?} is not deterministic"
+            );
+            if is_plain_identifier(name) && valid(name) {
+                assert_eq!(&ident, name, "{target}: {name:?} was renamed needlessly");
+            }
+        }
+    }
+
+    #[test]
+    fn rust_identifiers_compile() {
+        check_all("rust", rust_identifier, |ident| {
+            match ident.strip_prefix("r#") {
+                Some(raw) => RUST_KEYWORDS.contains(&raw) && is_plain_identifier(raw),
+                None => {
+                    is_plain_identifier(ident)
+                        && !RUST_KEYWORDS.contains(&ident)
+                        && !["self", "Self", "super", "crate"].contains(&ident)
+                }
+            }
+        });
+        assert_eq!(rust_identifier("type"), "r#type");
+        assert_eq!(rust_identifier("self"), "self_");
+        assert_eq!(rust_identifier("2fa_settings"), "_2fa_settings");
+        assert_eq!(rust_identifier("user-data"), "user_data");
+    }
+
+    #[test]
+    fn typescript_identifiers_avoid_reserved_words() {
+        check_all("typescript", typescript_identifier, |ident| {
+            is_plain_identifier(ident) && !TYPESCRIPT_RESERVED.contains(&ident)
+        });
+        assert_eq!(typescript_identifier("type"), "type_");
+        assert_eq!(typescript_identifier(""), "_");
+    }
+
+    #[test]
+    fn typescript_keys_are_bare_or_json_strings() {
+        for name in ADVERSARIAL {
+            let key = typescript_key(name);
+            if key.starts_with('"') {
+                let parsed: String = serde_json::from_str(&key).unwrap();
+                assert_eq!(&parsed, name);
+            } else {
+                assert_eq!(&key, name);
+                assert!(!key.is_empty() && !key.starts_with(|c: char| c.is_ascii_digit()));
+            }
+        }
+        assert_eq!(typescript_key("type"), "type");
+        assert_eq!(typescript_key("a b"), "\"a b\"");
+    }
+
+    #[test]
+    fn csharp_identifiers_escape_keywords() {
+        check_all("csharp", csharp_identifier, |ident| {
+            match ident.strip_prefix('@') {
+                Some(keyword) => CSHARP_KEYWORDS.contains(&keyword),
+                None => is_plain_identifier(ident) && !CSHARP_KEYWORDS.contains(&ident),
+            }
+        });
+        assert_eq!(csharp_identifier("class"), "@class");
+    }
+
+    #[test]
+    fn proto_identifiers_are_plain() {
+        check_all("proto", proto_identifier, is_plain_identifier);
+    }
+
+    #[test]
+    fn sql_identifiers_are_plain_or_quoted() {
+        for name in ADVERSARIAL {
+            for ident in [sql_identifier(name), postgres_identifier(name)] {
+                match ident.strip_prefix('"').and_then(|i| i.strip_suffix('"')) {
+                    Some(quoted) => {
+                        // Every quote inside is doubled, so the name can't end early
+                        assert_eq!(quoted.replace("\"\"", "").matches('"').count(), 0);
+                        assert_eq!(&quoted.replace("\"\"", "\""), name);
+                    }
+                    None => {
+                        assert!(is_plain_identifier(&ident), "{name:?} became {ident:?}");
+                        assert!(!is_sql_keyword(&ident, &[]), "{ident}");
+                        assert!(
+                            !ident.chars().any(|c| c.is_ascii_uppercase())
+                                || ident == sql_identifier(name)
+                        );
+                    }
+                }
+            }
+        }
+        assert_eq!(sql_identifier("user-data"), "\"user-data\"");
+        assert_eq!(sql_identifier("select"), "\"select\"");
+        assert_eq!(sql_identifier("quote\"d"), "\"quote\"\"d\"");
+        assert_eq!(postgres_identifier("Player"), "\"Player\"");
+        assert_eq!(postgres_identifier("player"), "player");
+    }
+
+    #[test]
+    fn go_identifiers_are_exported() {
+        check_all("go", go_identifier, |ident| {
+            is_plain_identifier(ident) && ident.starts_with(|c: char| c.is_ascii_uppercase())
+        });
+        assert_eq!(go_identifier("2fa_settings"), "X2faSettings");
+        assert_eq!(go_identifier(""), "X");
+        assert_eq!(go_identifier("player_id"), "PlayerId");
+    }
+
+    #[test]
+    fn kotlin_identifiers_avoid_keywords() {
+        check_all("kotlin", kotlin_identifier, |ident| {
+            match ident
+                .strip_prefix('`')
+                .and_then(|ident| ident.strip_suffix('`'))
+            {
+                Some(keyword) => KOTLIN_KEYWORDS.contains(&keyword),
+                None => is_plain_identifier(ident) && !KOTLIN_KEYWORDS.contains(&ident),
+            }
+        });
+        assert_eq!(kotlin_identifier("when"), "`when`");
+        assert_eq!(kotlin_identifier("2fa_settings"), "_2fa_settings");
+        assert_eq!(camel_case("home_base"), "homeBase");
+    }
+
+    #[test]
+    fn kdl_strings_escape_everything_that_would_end_them() {
+        for name in ADVERSARIAL {
+            let quoted = kdl_string(name);
+            let inner = &quoted[1..quoted.len() - 1];
+            assert!(quoted.starts_with('"') && quoted.ends_with('"'));
+            assert!(!inner.contains('\n'));
+            let unescaped = inner
+                .replace("\\\\", "\u{0}")
+                .replace("\\\"", "\"")
+                .replace("\\n", "\n")
+                .replace('\u{0}', "\\");
+            assert_eq!(&unescaped, name);
+        }
+    }
+
+    #[test]
+    fn case_conversions() {
+        assert_eq!(pascal_case("home_base"), "HomeBase");
+        assert_eq!(pascal_case("createdAt"), "CreatedAt");
+        assert_eq!(pascal_case("user-data v2"), "UserDataV2");
+        assert_eq!(pascal_case("größe"), "GrE");
+        assert_eq!(pascal_case("__"), "");
+        assert_eq!(camel_case("home_base"), "homeBase");
+        assert_eq!(camel_case("HomeBase"), "homeBase");
+        assert_eq!(camel_case("2fa"), "2fa");
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::ascii::glyphs;
+use crate::output::outln;
+use crate::schema::sats_types::{ConstraintData, ProductType, SatsSchema, TableInfo, TypeDef};
+use crate::schema::{build_type_names, format_type, is_nullable};
+
+/// Lint rule ids, stable so CI scripts can match on them
+const RULE_DUPLICATE_PK_PREFIX: &str = "index-duplicates-pk-prefix";
+const RULE_OPTION_COLUMN: &str = "index-on-option-column";
+const RULE_WIDE_UNINDEXED: &str = "wide-table-without-index";
+
+/// Default for --wide-table-columns
+pub const DEFAULT_WIDE_TABLE_COLUMNS: usize = 10;
+
+struct ReportColumn {
+    name: String,
+    ty: String,
+}
+
+struct ReportIndex {
+    name: String,
+    algorithm: &'static str,
+    columns: Vec<ReportColumn>,
+    unique: bool,
+}
+
+struct IndexWarning {
+    rule: &'static str,
+    message: String,
+}
+
+struct TableReport {
+    table: String,
+    primary_key: Vec<ReportColumn>,
+    indexes: Vec<ReportIndex>,
+    warnings: Vec<IndexWarning>,
+}
+
+/// Build the report for every table, sorted by table name. The index
+/// SpacetimeDB creates for the primary key itself is not listed as a
+/// secondary index.
+fn build_report(schema: &SatsSchema, wide_table_columns: usize) -> Vec<TableReport> {
+    let type_names = build_type_names(schema);
+    let mut tables: Vec<_> = schema.tables.iter().collect();
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    tables
+        .into_iter()
+        .map(|table| table_report(schema, table, &type_names, wide_table_columns))
+        .collect()
+}
+
+fn table_report(
+    schema: &SatsSchema,
+    table: &TableInfo,
+    type_names: &HashMap<usize, String>,
+    wide_table_columns: usize,
+) -> TableReport {
+    let empty = ProductType {
+        elements: Vec::new(),
+    };
+    let product = match schema.typespace.types.get(table.product_type_ref) {
+        Some(TypeDef::Product { Product }) => Product,
+        _ => &empty,
+    };
+    let column = |idx: usize| {
+        let element = product.elements.get(idx);
+        ReportColumn {
+            name: element
+                .and_then(|e| e.name.as_option())
+                .map_or_else(|| idx.to_string(), str::to_string),
+            ty: element.map_or_else(
+                || "?".to_string(),
+                |e| format_type(schema, &e.algebraic_type, type_names),
+            ),
+        }
+    };
+
+    let unique_sets: Vec<&Vec<usize>> = table
+        .constraints
+        .iter()
+        .filter_map(|c| match &c.data {
+            ConstraintData::Unique { Unique } => Some(&Unique.columns),
+            ConstraintData::Other(_) => None,
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+    let mut indexes = Vec::new();
+    let mut pk_index_seen = false;
+    for index in &table.indexes {
+        let columns = index.algorithm.columns();
+        let name = index.name().unwrap_or("(unnamed)").to_string();
+
+        if !table.primary_key.is_empty() && columns == table.primary_key && !pk_index_seen {
+            pk_index_seen = true;
+            continue;
+        }
+
+        if !columns.is_empty() && table.primary_key.starts_with(&columns) {
+            warnings.push(IndexWarning {
+                rule: RULE_DUPLICATE_PK_PREFIX,
+                message: format!(
+                    "index '{name}' covers a prefix of the primary key, which is already indexed"
+                ),
+            });
+        }
+        for &idx in &columns {
+            let is_option = product
+                .elements
+                .get(idx)
+                .is_some_and(|e| is_nullable(schema, &e.algebraic_type));
+            if is_option {
+                warnings.push(IndexWarning {
+                    rule: RULE_OPTION_COLUMN,
+                    message: format!(
+                        "index '{name}' includes the optional column '{}'",
+                        column(idx).name
+                    ),
+                });
+            }
+        }
+
+        indexes.push(ReportIndex {
+            name,
+            algorithm: index.algorithm.label(),
+            unique: unique_sets.contains(&&columns),
+            columns: columns.into_iter().map(column).collect(),
+        });
+    }
+
+    if indexes.is_empty() && product.elements.len() > wide_table_columns {
+        warnings.push(IndexWarning {
+            rule: RULE_WIDE_UNINDEXED,
+            message: format!(
+                "{} columns and no secondary indexes",
+                product.elements.len()
+            ),
+        });
+    }
+
+    TableReport {
+        table: table.name.clone(),
+        primary_key: table.primary_key.iter().map(|&idx| column(idx)).collect(),
+        indexes,
+        warnings,
+    }
+}
+
+fn format_columns(columns: &[ReportColumn]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("{}: {}", c.name, c.ty))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Print the index report: primary key, secondary indexes and warnings per table
+pub fn display_index_report(schema: &SatsSchema, wide_table_columns: usize) {
+    let report = build_report(schema, wide_table_columns);
+
+    outln!("\n{}", glyphs("📇 INDEX REPORT").bold().cyan());
+    outln!("{}", "=".repeat(60));
+
+    for table in &report {
+        outln!("\n  {} {}", glyphs("▸").green(), table.table.bold());
+        if table.primary_key.is_empty() {
+            outln!("    Primary key: {}", "none".dimmed());
+        } else {
+            outln!("    Primary key: {}", format_columns(&table.primary_key));
+        }
+
+        if table.indexes.is_empty() {
+            outln!("    Indexes:     {}", "none".dimmed());
+        } else {
+            outln!("    Indexes:");
+            for (i, index) in table.indexes.iter().enumerate() {
+                let prefix = glyphs(if i == table.indexes.len() - 1 {
+                    "└"
+                } else {
+                    "├"
+                });
+                outln!(
+                    "      {} {} ({}){}",
+                    prefix.dimmed(),
+                    index.name,
+                    format_columns(&index.columns),
+                    format!(
+                        " {}{}",
+                        index.algorithm,
+                        if index.unique { ", unique" } else { "" }
+                    )
+                    .dimmed()
+                );
+            }
+        }
+
+        for warning in &table.warnings {
+            outln!(
+                "    {} {} {}",
+                glyphs("⚠️").yellow(),
+                warning.message,
+                format!("[{}]", warning.rule).dimmed()
+            );
+        }
+    }
+
+    let warning_count: usize = report.iter().map(|t| t.warnings.len()).sum();
+    outln!(
+        "\n{} {} tables, {} warnings",
+        glyphs("📈").yellow(),
+        report.len(),
+        warning_count
+    );
+}
+
+pub fn index_report_json(schema: &SatsSchema, wide_table_columns: usize) -> Value {
+    let columns_json = |columns: &[ReportColumn]| -> Vec<Value> {
+        columns
+            .iter()
+            .map(|c| json!({ "column": c.name, "type": c.ty }))
+            .collect()
+    };
+
+    let tables: Vec<Value> = build_report(schema, wide_table_columns)
+        .iter()
+        .map(|table| {
+            json!({
+                "table": table.table,
+                "primary_key": columns_json(&table.primary_key),
+                "indexes": table.indexes.iter().map(|index| json!({
+                    "name": index.name,
+                    "algorithm": index.algorithm,
+                    "unique": index.unique,
+                    "columns": columns_json(&index.columns),
+                })).collect::<Vec<_>>(),
+                "warnings": table.warnings.iter().map(|w| json!({
+                    "rule": w.rule,
+                    "message": w.message,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({ "tables": tables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> SatsSchema {
+        serde_json::from_str(include_str!("../tests/fixtures/index_tables.json")).unwrap()
+    }
+
+    fn rules(report: &Value, table: &str) -> Vec<String> {
+        report["tables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["table"] == table)
+            .unwrap()["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["rule"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn json_matches_fixture() {
+        let expected: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/index_report.json")).unwrap();
+        assert_eq!(
+            index_report_json(&fixture(), DEFAULT_WIDE_TABLE_COLUMNS),
+            expected
+        );
+    }
+
+    #[test]
+    fn tables_are_sorted_by_name() {
+        let report = index_report_json(&fixture(), DEFAULT_WIDE_TABLE_COLUMNS);
+        let names: Vec<_> = report["tables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["table"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["audit_log", "membership", "profile", "setting"]);
+    }
+
+    #[test]
+    fn primary_key_index_is_not_listed_or_flagged() {
+        let report = index_report_json(&fixture(), DEFAULT_WIDE_TABLE_COLUMNS);
+        let membership = &report["tables"][1];
+        let indexes: Vec<_> = membership["indexes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            indexes,
+            [
+                "membership_guild_id_idx_btree",
+                "membership_player_id_idx_btree"
+            ]
+        );
+        // player_id alone is not a prefix of (guild_id, player_id)
+        assert_eq!(rules(&report, "membership"), [RULE_DUPLICATE_PK_PREFIX]);
+        assert_eq!(rules(&report, "setting"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn option_columns_and_unique_constraints() {
+        let report = index_report_json(&fixture(), DEFAULT_WIDE_TABLE_COLUMNS);
+        assert_eq!(rules(&report, "profile"), [RULE_OPTION_COLUMN]);
+        assert_eq!(report["tables"][2]["indexes"][1]["unique"], true);
+        assert_eq!(report["tables"][2]["indexes"][0]["unique"], false);
+    }
+
+    #[test]
+    fn wide_table_threshold_is_configurable() {
+        let report = index_report_json(&fixture(), 12);
+        assert_eq!(rules(&report, "audit_log"), Vec::<String>::new());
+        let report = index_report_json(&fixture(), 11);
+        assert_eq!(rules(&report, "audit_log"), [RULE_WIDE_UNINDEXED]);
+        // Narrow tables with only a primary key count too
+        let report = index_report_json(&fixture(), 1);
+        assert_eq!(rules(&report, "setting"), [RULE_WIDE_UNINDEXED]);
+    }
+
+    #[test]
+    fn demo_report_is_stable() {
+        let schema = crate::test_support::demo_schema();
+        let first = index_report_json(&schema, DEFAULT_WIDE_TABLE_COLUMNS);
+        assert_eq!(
+            index_report_json(&schema, DEFAULT_WIDE_TABLE_COLUMNS),
+            first
+        );
+    }
+}
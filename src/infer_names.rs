@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::schema::sats_types::{
+    AlgebraicType, NamedType, ProductType, SatsSchema, SumType, TypeDef, TypeName,
+};
+use crate::schema::{detect_spacetimedb_sum_type, detect_spacetimedb_type, is_option_type};
+
+/// Prefix that marks a guessed name wherever it is shown
+pub const INFERRED_MARKER: char = '~';
+
+/// Name the typespace entries that the schema's `types` list leaves
+/// unnamed, in order of confidence:
+///
+/// 1. table row types after their table (`player_state` -> `~PlayerState`)
+/// 2. sums whose variants match a well-known pattern (`Ok`/`Err` -> `~Result`)
+/// 3. everything else after the field name it is most often used under
+///
+/// Inferred names are added to `types` with a leading `~`. Returns how many
+/// names were added.
+pub fn infer_type_names(schema: &mut SatsSchema) -> usize {
+    let named: HashSet<usize> = schema.types.iter().map(|t| t.ty).collect();
+    let mut taken: HashSet<String> = schema.types.iter().map(|t| t.name.name.clone()).collect();
+    let mut inferred: BTreeMap<usize, String> = BTreeMap::new();
+
+    let mut assign = |idx: usize, base: &str, inferred: &mut BTreeMap<usize, String>| {
+        if named.contains(&idx) || inferred.contains_key(&idx) || base.is_empty() {
+            return;
+        }
+        let mut name = format!("{INFERRED_MARKER}{base}");
+        let mut n = 2;
+        while taken.contains(&name) {
+            name = format!("{INFERRED_MARKER}{base}{n}");
+            n += 1;
+        }
+        taken.insert(name.clone());
+        inferred.insert(idx, name);
+    };
+
+    for table in &schema.tables {
+        assign(
+            table.product_type_ref,
+            &pascal_case(&table.name),
+            &mut inferred,
+        );
+    }
+
+    for (idx, def) in schema.typespace.types.iter().enumerate() {
+        if let TypeDef::Sum { Sum } = def {
+            if let Some(name) = well_known_sum_name(Sum) {
+                assign(idx, name, &mut inferred);
+            }
+        }
+    }
+
+    let usage = field_usage(schema);
+    for (idx, def) in schema.typespace.types.iter().enumerate() {
+        let needs_name = match def {
+            TypeDef::Product { Product } => detect_spacetimedb_type(Product).is_none(),
+            TypeDef::Sum { Sum } => {
+                detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum)
+            }
+            _ => false,
+        };
+        if !needs_name {
+            continue;
+        }
+        // Most frequent field name; ties go to the alphabetically first
+        let best = usage.get(&idx).and_then(|names| {
+            names
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(name, _)| name.as_str())
+        });
+        if let Some(field_name) = best {
+            assign(idx, &pascal_case(field_name), &mut inferred);
+        }
+    }
+
+    let count = inferred.len();
+    schema
+        .types
+        .extend(inferred.into_iter().map(|(ty, name)| NamedType {
+            name: TypeName {
+                scope: Vec::new(),
+                name,
+            },
+            ty,
+            custom_ordering: false,
+        }));
+    count
+}
+
+fn well_known_sum_name(sum: &SumType) -> Option<&'static str> {
+    let mut variants: Vec<_> = sum
+        .variants
+        .iter()
+        .filter_map(|v| v.name.as_option())
+        .collect();
+    variants.sort_unstable();
+    match variants.as_slice() {
+        ["Err", "Ok"] => Some("Result"),
+        ["Left", "Right"] => Some("Either"),
+        _ => None,
+    }
+}
+
+/// For each typespace entry, how often it appears (directly, inside an
+/// array or an option) under each field or parameter name
+fn field_usage(schema: &SatsSchema) -> HashMap<usize, BTreeMap<String, usize>> {
+    let mut usage: HashMap<usize, BTreeMap<String, usize>> = HashMap::new();
+    let mut record_product = |product: &ProductType| {
+        for element in &product.elements {
+            if let (Some(name), Some(idx)) = (
+                element.name.as_option(),
+                referenced_type(&element.algebraic_type),
+            ) {
+                *usage
+                    .entry(idx)
+                    .or_default()
+                    .entry(name.to_string())
+                    .or_default() += 1;
+            }
+        }
+    };
+
+    for def in &schema.typespace.types {
+        if let TypeDef::Product { Product } = def {
+            record_product(Product);
+        }
+    }
+    for reducer in &schema.reducers {
+        record_product(&reducer.params);
+    }
+    usage
+}
+
+/// The typespace entry a field's type points at, looking through `Vec<T>`
+/// and `Option<T>`
+fn referenced_type(alg_type: &AlgebraicType) -> Option<usize> {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => Some(*Ref as usize),
+        AlgebraicType::Array { Array } => referenced_type(Array),
+        AlgebraicType::Sum { Sum } if is_option_type(Sum) => Sum
+            .variants
+            .iter()
+            .find_map(|v| referenced_type(&v.algebraic_type)),
+        _ => None,
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::{demo_schema, demo_schema_with, schema};
+
+    fn names(schema: &SatsSchema) -> BTreeMap<usize, &str> {
+        schema
+            .types
+            .iter()
+            .map(|t| (t.ty, t.name.name.as_str()))
+            .collect()
+    }
+
+    fn unit(name: &str) -> serde_json::Value {
+        json!({"name": {"some": name}, "algebraic_type": {"Product": {"elements": []}}})
+    }
+
+    fn field(name: &str, ty: serde_json::Value) -> serde_json::Value {
+        json!({"name": {"some": name}, "algebraic_type": ty})
+    }
+
+    #[test]
+    fn stripped_demo_gets_marked_names() {
+        let mut stripped = demo_schema_with(|document| document["types"] = json!([]));
+        assert_eq!(infer_type_names(&mut stripped), 11);
+        assert_eq!(
+            names(&stripped),
+            BTreeMap::from([
+                (0, "~Position"),
+                (1, "~Status"),
+                (2, "~Inventory"),
+                (3, "~Rarity"),
+                (4, "~Role"),
+                (5, "~Player"),
+                (6, "~ItemKind"),
+                (7, "~Guild"),
+                (8, "~GuildMember"),
+                (9, "~ChatMessage"),
+                (10, "~GameTick"),
+            ])
+        );
+        assert!(stripped.types.iter().all(|t| !t.custom_ordering));
+    }
+
+    #[test]
+    fn named_schemas_are_left_alone() {
+        let mut named = demo_schema();
+        let before: Vec<_> = names(&named)
+            .into_iter()
+            .map(|(i, n)| (i, n.to_string()))
+            .collect();
+        assert_eq!(infer_type_names(&mut named), 0);
+        let after: Vec<_> = names(&named)
+            .into_iter()
+            .map(|(i, n)| (i, n.to_string()))
+            .collect();
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn well_known_sums_and_name_clashes() {
+        let mut schema = schema(json!({
+            "typespace": {"types": [
+                {"Sum": {"variants": [field("Ok", json!({"U32": []})), field("Err", json!({"String": []}))]}},
+                {"Sum": {"variants": [field("Err", json!({"String": []})), unit("Ok")]}},
+                {"Sum": {"variants": [unit("Right"), unit("Left")]}},
+                {"Product": {"elements": [
+                    field("first", json!({"Ref": 0})),
+                    field("second", json!({"Ref": 1})),
+                    field("side", json!({"Ref": 2})),
+                ]}},
+            ]},
+            "tables": [{"name": "outcome_log", "product_type_ref": 3, "primary_key": []}],
+            "types": [],
+        }));
+        assert_eq!(infer_type_names(&mut schema), 4);
+        assert_eq!(
+            names(&schema),
+            BTreeMap::from([
+                (0, "~Result"),
+                (1, "~Result2"),
+                (2, "~Either"),
+                (3, "~OutcomeLog"),
+            ])
+        );
+    }
+
+    #[test]
+    fn structs_are_named_after_their_most_common_field() {
+        let stats = json!({"Ref": 0});
+        let mut schema = schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [field("hp", json!({"U32": []}))]}},
+                {"Product": {"elements": [field("level", json!({"U8": []}))]}},
+                {"Product": {"elements": [
+                    field("owner_stats", stats.clone()),
+                    field("guest_stats", json!({"Array": stats.clone()})),
+                    // Ties go to the alphabetically first name
+                    field("zeta", json!({"Ref": 1})),
+                    field("alpha", json!({"Ref": 1})),
+                ]}},
+            ]},
+            "tables": [{"name": "match", "product_type_ref": 2, "primary_key": []}],
+            "reducers": [{"name": "award", "params": {"elements": [field("owner_stats", stats)]}}],
+            "types": [{"name": {"scope": [], "name": "~Alpha"}, "ty": 2, "custom_ordering": true}],
+        }));
+        assert_eq!(infer_type_names(&mut schema), 2);
+        assert_eq!(
+            names(&schema),
+            BTreeMap::from([(0, "~OwnerStats"), (1, "~Alpha2"), (2, "~Alpha")])
+        );
+    }
+
+    #[test]
+    fn pascal_case_splits_on_separators() {
+        assert_eq!(pascal_case("player_state"), "PlayerState");
+        assert_eq!(pascal_case("guild-member role"), "GuildMemberRole");
+        assert_eq!(pascal_case("__x__y"), "XY");
+        assert_eq!(pascal_case("élan"), "Élan");
+        assert_eq!(pascal_case("_"), "");
+    }
+}
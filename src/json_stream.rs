@@ -0,0 +1,173 @@
+use std::io::{self, Write};
+
+/// Re-indents a JSON document as it streams through, in the same layout as
+/// `serde_json::to_string_pretty`. Tokens are copied byte for byte, so
+/// number literals too large for `f64` (u128/u256 values) are not rounded.
+///
+/// The input is assumed to be valid JSON; nothing is validated.
+pub struct PrettyPrinter<W: Write> {
+    out: W,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// A `{` or `[` whose first member hasn't been seen yet, so an empty
+    /// container can be written as `{}` / `[]`
+    pending_open: bool,
+}
+
+impl<W: Write> PrettyPrinter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            pending_open: false,
+        }
+    }
+
+    pub fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        for &byte in chunk {
+            self.byte(byte)?;
+        }
+        Ok(())
+    }
+
+    /// End the document with a newline and flush
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+
+    fn byte(&mut self, byte: u8) -> io::Result<()> {
+        if self.in_string {
+            match byte {
+                _ if self.escaped => self.escaped = false,
+                b'\\' => self.escaped = true,
+                b'"' => self.in_string = false,
+                _ => {}
+            }
+            return self.out.write_all(&[byte]);
+        }
+        if byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+
+        if self.pending_open {
+            self.pending_open = false;
+            if matches!(byte, b'}' | b']') {
+                return self.out.write_all(&[byte]);
+            }
+            self.depth += 1;
+            self.newline()?;
+        }
+
+        match byte {
+            b'{' | b'[' => {
+                self.pending_open = true;
+                self.out.write_all(&[byte])
+            }
+            b'}' | b']' => {
+                self.depth = self.depth.saturating_sub(1);
+                self.newline()?;
+                self.out.write_all(&[byte])
+            }
+            b',' => {
+                self.out.write_all(b",")?;
+                self.newline()
+            }
+            b':' => self.out.write_all(b": "),
+            b'"' => {
+                self.in_string = true;
+                self.out.write_all(b"\"")
+            }
+            _ => self.out.write_all(&[byte]),
+        }
+    }
+
+    fn newline(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\n")?;
+        for _ in 0..self.depth {
+            self.out.write_all(b"  ")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::DEMO_SCHEMA;
+
+    const LITERALS: &str = include_str!("../tests/fixtures/u256_literals.json");
+
+    /// Pretty-print `input`, fed in chunks of `chunk_size` bytes
+    fn pretty(input: &str, chunk_size: usize) -> String {
+        let mut out = Vec::new();
+        let mut printer = PrettyPrinter::new(&mut out);
+        for chunk in input.as_bytes().chunks(chunk_size) {
+            printer.write_chunk(chunk).unwrap();
+        }
+        printer.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn layout_matches_serde_json() {
+        let value: serde_json::Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+        let compact = value.to_string();
+        let expected = serde_json::to_string_pretty(&value).unwrap() + "\n";
+        assert_eq!(pretty(&compact, compact.len()), expected);
+        // Keys keep the source order, which serde_json's map would sort
+        let reindented: serde_json::Value =
+            serde_json::from_str(&pretty(DEMO_SCHEMA, 4096)).unwrap();
+        assert_eq!(reindented, value);
+    }
+
+    #[test]
+    fn chunk_boundaries_do_not_matter() {
+        let whole = pretty(LITERALS, LITERALS.len());
+        for chunk_size in [1, 2, 3, 7, 64] {
+            assert_eq!(
+                pretty(LITERALS, chunk_size),
+                whole,
+                "chunks of {chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn large_number_literals_are_copied_exactly() {
+        let output = pretty(LITERALS, 5);
+        for literal in [
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968",
+            "340282366920938463463374607431768211455",
+            "9007199254740993",
+            "0.1000000000000000055511151231257827",
+        ] {
+            assert!(
+                output.contains(&format!("\"value\": {literal}\n")),
+                "{literal} in\n{output}"
+            );
+        }
+    }
+
+    #[test]
+    fn strings_and_empty_containers() {
+        assert_eq!(
+            pretty(r#"{"a":"{[,:\"]}","b":{},"c":[ ],"d":"\\"}"#, 1),
+            "{\n  \"a\": \"{[,:\\\"]}\",\n  \"b\": {},\n  \"c\": [],\n  \"d\": \"\\\\\"\n}\n"
+        );
+        assert_eq!(pretty("[]", 1), "[]\n");
+        assert_eq!(pretty("[1,[2]]", 1), "[\n  1,\n  [\n    2\n  ]\n]\n");
+    }
+
+    #[test]
+    fn whitespace_inside_strings_is_kept() {
+        assert_eq!(
+            pretty("{ \"a b\" :\n \" x \\n \" }", 2),
+            "{\n  \"a b\": \" x \\n \"\n}\n"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::model::{EnumModel, SchemaModel, StructModel, TableModel};
+use crate::schema::sats_types::SatsSchema;
+use crate::schema::SchemaArgs;
+
+/// One line of `--format jsonl`, tagged by `kind`
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Line<'a> {
+    Table(&'a TableModel),
+    Struct(&'a StructModel),
+    Enum(&'a EnumModel),
+    /// How many lines of each kind came before it
+    Summary {
+        tables: usize,
+        structs: usize,
+        enums: usize,
+    },
+}
+
+/// Render the schema as JSON Lines: a line per table, then per standalone
+/// struct and enum (as in `--format model`), then a summary line.
+///
+/// `--table`, `--type`, `--enum` and `--search` pick the lines the same way
+/// they narrow the pretty view; the summary counts what was written.
+pub fn generate_jsonl(schema: &SatsSchema, args: &SchemaArgs) -> Result<String> {
+    let model = SchemaModel::new(schema);
+    let row_types: HashSet<&str> = model
+        .tables
+        .iter()
+        .filter_map(|t| t.row_type.as_deref())
+        .collect();
+    let standalone: Vec<_> = model
+        .structs
+        .iter()
+        .filter(|s| !row_types.contains(s.name.as_str()))
+        .collect();
+
+    let (tables, structs, enums): (Vec<_>, Vec<_>, Vec<_>) = if let Some(name) = &args.table {
+        let table = model
+            .tables
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+        (vec![table], Vec::new(), Vec::new())
+    } else if let Some(name) = &args.enum_filter {
+        let found = model
+            .enums
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Enum '{name}' not found"))?;
+        (Vec::new(), Vec::new(), vec![found])
+    } else if let Some(name) = &args.type_filter {
+        let structs: Vec<_> = standalone
+            .into_iter()
+            .filter(|s| s.name.eq_ignore_ascii_case(name))
+            .collect();
+        let enums: Vec<_> = model
+            .enums
+            .iter()
+            .filter(|e| e.name.eq_ignore_ascii_case(name))
+            .collect();
+        if structs.is_empty() && enums.is_empty() {
+            return Err(anyhow!("Type '{name}' not found"));
+        }
+        (Vec::new(), structs, enums)
+    } else if let Some(matcher) = &args.search {
+        (
+            model
+                .tables
+                .iter()
+                .filter(|t| matcher.is_match(&t.name))
+                .collect(),
+            standalone
+                .into_iter()
+                .filter(|s| matcher.is_match(&s.name))
+                .collect(),
+            model
+                .enums
+                .iter()
+                .filter(|e| matcher.is_match(&e.name))
+                .collect(),
+        )
+    } else {
+        (
+            model.tables.iter().collect(),
+            standalone,
+            model.enums.iter().collect(),
+        )
+    };
+
+    let summary = Line::Summary {
+        tables: tables.len(),
+        structs: structs.len(),
+        enums: enums.len(),
+    };
+    let lines = tables
+        .into_iter()
+        .map(Line::Table)
+        .chain(structs.into_iter().map(Line::Struct))
+        .chain(enums.into_iter().map(Line::Enum))
+        .chain([summary]);
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&serde_json::to_string(&line)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{args, demo_schema};
+    use serde_json::Value;
+
+    fn jsonl(flags: &[&str]) -> Vec<Value> {
+        generate_jsonl(&demo_schema(), &args(flags))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn tables_then_standalone_types_then_a_summary() {
+        let lines = jsonl(&[]);
+        let kinds: Vec<_> = lines.iter().map(|l| l["kind"].as_str().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "table", "table", "table", "table", "table", "table", "struct", "struct", "enum",
+                "enum", "enum", "summary"
+            ]
+        );
+        assert_eq!(lines[0]["name"], "player");
+        assert_eq!(lines[0]["primaryKey"], serde_json::json!(["id"]));
+        assert_eq!(
+            lines.last().unwrap(),
+            &serde_json::json!({"kind": "summary", "tables": 6, "structs": 2, "enums": 3})
+        );
+    }
+
+    #[test]
+    fn filters_pick_the_lines_and_the_summary_counts_them() {
+        let lines = jsonl(&["--search", "guild"]);
+        let names: Vec<_> = lines.iter().filter_map(|l| l["name"].as_str()).collect();
+        assert_eq!(names, ["guild", "guild_member", "GuildRole"]);
+        assert_eq!(lines[3]["tables"], 2);
+
+        let lines = jsonl(&["--type", "vec2"]);
+        assert_eq!(lines[0]["kind"], "struct");
+        assert_eq!(lines[0]["name"], "Vec2");
+
+        let error = generate_jsonl(&demo_schema(), &args(&["--table", "nope"])).unwrap_err();
+        assert_eq!(error.to_string(), "Table 'nope' not found");
+    }
+}
@@ -0,0 +1,595 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{json, Value};
+
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{
+    detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type, is_option_type,
+    qualified_type_name,
+};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A JSON value whose objects keep their keys in insertion order.
+/// `serde_json::Map` sorts keys, but `properties` should list fields in
+/// the order the schema declares them.
+enum Node {
+    Value(Value),
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>),
+}
+
+impl From<Value> for Node {
+    fn from(value: Value) -> Self {
+        Node::Value(value)
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Node::Value(value) => value.serialize(serializer),
+            Node::Array(items) => items.serialize(serializer),
+            Node::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+fn object<const N: usize>(entries: [(&str, Node); N]) -> Node {
+    Node::Object(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// Render a JSON Schema (draft 2020-12) document for table rows.
+///
+/// Every struct and enum the rows use is a `$defs` entry, referenced with
+/// `$ref`. With `table` the document validates rows of that table and
+/// `$defs` holds just the types it needs; without, it accepts a row of
+/// any table and `$defs` holds every named type. Objects list their
+/// fields in schema order, all of them `required`; options are nullable
+/// (`anyOf` with `null`).
+pub fn generate_json_schema(schema: &SatsSchema, table: Option<&str>) -> Result<String> {
+    let mut generator = JsonSchemaGenerator {
+        schema,
+        names: def_names(schema),
+        referenced: BTreeSet::new(),
+        inlining: HashSet::new(),
+    };
+
+    let mut root = vec![("$schema".to_string(), Node::from(json!(DIALECT)))];
+    match table {
+        Some(name) => {
+            let table = schema
+                .tables
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+            root.push(("title".to_string(), json!(table.name).into()));
+            root.extend(generator.row(table.product_type_ref));
+        }
+        None => {
+            let rows: Vec<_> = schema
+                .tables
+                .iter()
+                .map(|t| {
+                    let mut row = vec![("title".to_string(), json!(t.name).into())];
+                    row.extend(generator.row(t.product_type_ref));
+                    Node::Object(row)
+                })
+                .collect();
+            root.push(("anyOf".to_string(), Node::Array(rows)));
+            for named in &schema.types {
+                if generator.special(named.ty).is_none() {
+                    generator.referenced.insert(named.ty);
+                }
+            }
+        }
+    }
+
+    // Definitions can reference further entries; keep going until every
+    // $ref has a target
+    let mut defs: Vec<(String, Node)> = Vec::new();
+    let mut defined = BTreeSet::new();
+    while let Some(&idx) = generator.referenced.difference(&defined).next() {
+        defined.insert(idx);
+        let node = generator.type_def(idx);
+        defs.push((generator.names[&idx].clone(), node));
+    }
+    defs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    if !defs.is_empty() {
+        root.push(("$defs".to_string(), Node::Object(defs)));
+    }
+
+    Ok(serde_json::to_string_pretty(&Node::Object(root))?)
+}
+
+/// `$defs` key for each typespace entry: the type's (scoped) name, or
+/// `Type_<index>` when it has none, suffixed with the index when two
+/// entries would share a key
+fn def_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::new();
+    for named in &schema.types {
+        if names.contains_key(&named.ty) {
+            continue;
+        }
+        let mut name = qualified_type_name(&named.name);
+        if !taken.insert(name.clone()) {
+            name = format!("{name}_{}", named.ty);
+            taken.insert(name.clone());
+        }
+        names.insert(named.ty, name);
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names.entry(idx).or_insert_with(|| {
+            let mut name = format!("Type_{idx}");
+            while !taken.insert(name.clone()) {
+                name.push('_');
+            }
+            name
+        });
+    }
+    names
+}
+
+/// JSON pointer escaping for a `$defs` key
+fn pointer_escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+struct JsonSchemaGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: HashMap<usize, String>,
+    /// Typespace entries that a `$ref` points at and so need a `$defs` entry
+    referenced: BTreeSet<usize>,
+    /// Aliases being written out in place, to stop cycles
+    inlining: HashSet<usize>,
+}
+
+impl JsonSchemaGenerator<'_> {
+    /// What `detect_spacetimedb_type` calls typespace entry `idx`, if it is
+    /// one of SpacetimeDB's own types
+    fn special(&self, idx: usize) -> Option<String> {
+        match self.schema.typespace.types.get(idx)? {
+            TypeDef::Product { Product } => detect_spacetimedb_type(Product),
+            TypeDef::Sum { Sum } => detect_spacetimedb_sum_type(Sum),
+            _ => None,
+        }
+    }
+
+    /// The keywords for a table's row type, to merge into the object that
+    /// describes the table
+    fn row(&mut self, idx: usize) -> Vec<(String, Node)> {
+        match self.reference(idx) {
+            Node::Object(entries) => entries,
+            _ => Vec::new(),
+        }
+    }
+
+    /// SpacetimeDB's own types are written in place; structs and enums are
+    /// `$ref`s, and aliases are followed to what they name
+    fn reference(&mut self, idx: usize) -> Node {
+        if let Some(special) = self.special(idx) {
+            return special_schema(&special);
+        }
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { .. } | TypeDef::Sum { .. }) => {
+                self.referenced.insert(idx);
+                let target = format!("#/$defs/{}", pointer_escape(&self.names[&idx]));
+                object([("$ref", json!(target).into())])
+            }
+            Some(TypeDef::Builtin { .. } | TypeDef::Ref { .. }) => {
+                if !self.inlining.insert(idx) {
+                    return Node::Object(Vec::new());
+                }
+                let node = self.type_def(idx);
+                self.inlining.remove(&idx);
+                node
+            }
+            None => Node::Object(Vec::new()),
+        }
+    }
+
+    fn type_def(&mut self, idx: usize) -> Node {
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => self.product(Product),
+            Some(TypeDef::Sum { Sum }) => self.sum(Sum),
+            Some(TypeDef::Builtin { Builtin }) => match Builtin {
+                BuiltinType::Bool { .. } => object([("type", json!("boolean").into())]),
+                BuiltinType::I8 { .. } => integer(i8::MIN.into(), i8::MAX.into()),
+                BuiltinType::U8 { .. } => integer(0.into(), u8::MAX.into()),
+                BuiltinType::I16 { .. } => integer(i16::MIN.into(), i16::MAX.into()),
+                BuiltinType::U16 { .. } => integer(0.into(), u16::MAX.into()),
+                BuiltinType::I32 { .. } => integer(i32::MIN.into(), i32::MAX.into()),
+                BuiltinType::U32 { .. } => integer(0.into(), u32::MAX.into()),
+                BuiltinType::I64 { .. } => integer(i64::MIN.into(), i64::MAX.into()),
+                BuiltinType::U64 { .. } => integer(0.into(), u64::MAX.into()),
+                BuiltinType::I128 { .. }
+                | BuiltinType::U128 { .. }
+                | BuiltinType::I256 { .. }
+                | BuiltinType::U256 { .. } => object([("type", json!("integer").into())]),
+                BuiltinType::F32 { .. } | BuiltinType::F64 { .. } => {
+                    object([("type", json!("number").into())])
+                }
+                BuiltinType::String { .. } => object([("type", json!("string").into())]),
+                BuiltinType::Array { Array } => self.array(Array),
+                BuiltinType::Map { Map } => self.map(&Map.key_ty, &Map.ty),
+            },
+            Some(TypeDef::Ref { Ref }) => self.reference(*Ref as usize),
+            None => Node::Object(Vec::new()),
+        }
+    }
+
+    fn alg_type(&mut self, alg_type: &AlgebraicType) -> Node {
+        match alg_type {
+            AlgebraicType::Bool { .. } => object([("type", json!("boolean").into())]),
+            AlgebraicType::I8 { .. } => integer(i8::MIN.into(), i8::MAX.into()),
+            AlgebraicType::U8 { .. } => integer(0.into(), u8::MAX.into()),
+            AlgebraicType::I16 { .. } => integer(i16::MIN.into(), i16::MAX.into()),
+            AlgebraicType::U16 { .. } => integer(0.into(), u16::MAX.into()),
+            AlgebraicType::I32 { .. } => integer(i32::MIN.into(), i32::MAX.into()),
+            AlgebraicType::U32 { .. } => integer(0.into(), u32::MAX.into()),
+            AlgebraicType::I64 { .. } => integer(i64::MIN.into(), i64::MAX.into()),
+            AlgebraicType::U64 { .. } => integer(0.into(), u64::MAX.into()),
+            // Too wide for a JSON number bound
+            AlgebraicType::I128 { .. }
+            | AlgebraicType::U128 { .. }
+            | AlgebraicType::I256 { .. }
+            | AlgebraicType::U256 { .. } => object([("type", json!("integer").into())]),
+            AlgebraicType::F32 { .. } | AlgebraicType::F64 { .. } => {
+                object([("type", json!("number").into())])
+            }
+            AlgebraicType::String { .. } => object([("type", json!("string").into())]),
+            AlgebraicType::Array { Array } => self.array(Array),
+            AlgebraicType::Map { Map } => self.map(&Map.key_ty, &Map.ty),
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize),
+            AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+                Some(special) => special_schema(&special),
+                None => self.product(Product),
+            },
+            AlgebraicType::Sum { Sum } => match detect_spacetimedb_sum_type(Sum) {
+                Some(special) => special_schema(&special),
+                None => self.sum(Sum),
+            },
+        }
+    }
+
+    fn array(&mut self, element: &AlgebraicType) -> Node {
+        object([
+            ("type", json!("array").into()),
+            ("items", self.alg_type(element)),
+        ])
+    }
+
+    /// Maps are written as a list of `[key, value]` pairs
+    fn map(&mut self, key: &AlgebraicType, value: &AlgebraicType) -> Node {
+        let pair = object([
+            ("type", json!("array").into()),
+            (
+                "prefixItems",
+                Node::Array(vec![self.alg_type(key), self.alg_type(value)]),
+            ),
+            ("items", json!(false).into()),
+            ("minItems", json!(2).into()),
+        ]);
+        object([("type", json!("array").into()), ("items", pair)])
+    }
+
+    /// Named fields make an object, unnamed ones a fixed-length array
+    fn product(&mut self, product: &ProductType) -> Node {
+        if !product.elements.is_empty()
+            && product
+                .elements
+                .iter()
+                .all(|e| e.name.as_option().is_none())
+        {
+            let items: Vec<_> = product
+                .elements
+                .iter()
+                .map(|e| self.alg_type(&e.algebraic_type))
+                .collect();
+            let len = items.len();
+            return object([
+                ("type", json!("array").into()),
+                ("prefixItems", Node::Array(items)),
+                ("items", json!(false).into()),
+                ("minItems", json!(len).into()),
+            ]);
+        }
+
+        let mut properties = Vec::new();
+        let mut required = Vec::new();
+        for (i, element) in product.elements.iter().enumerate() {
+            let name = element
+                .name
+                .as_option()
+                .map_or_else(|| i.to_string(), str::to_string);
+            properties.push((name.clone(), self.alg_type(&element.algebraic_type)));
+            required.push(name);
+        }
+        object([
+            ("type", json!("object").into()),
+            ("properties", Node::Object(properties)),
+            ("required", json!(required).into()),
+            ("additionalProperties", json!(false).into()),
+        ])
+    }
+
+    /// Options are the inner type or `null`. Enums whose variants carry no
+    /// data are a string `enum`; otherwise each variant is a single-key
+    /// object holding its payload, or the bare name for unit variants.
+    fn sum(&mut self, sum: &SumType) -> Node {
+        if is_option_type(sum) {
+            if let Some(inner) = get_option_inner_type(sum) {
+                let inner = self.alg_type(inner);
+                return object([(
+                    "anyOf",
+                    Node::Array(vec![inner, object([("type", json!("null").into())])]),
+                )]);
+            }
+        }
+
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+        let tag = |i: usize, name: Option<&str>| name.map_or_else(|| i.to_string(), str::to_string);
+
+        if sum.variants.iter().all(|v| is_unit(&v.algebraic_type)) {
+            let tags: Vec<_> = sum
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| tag(i, v.name.as_option()))
+                .collect();
+            return object([("enum", json!(tags).into())]);
+        }
+
+        let variants = sum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let tag = tag(i, v.name.as_option());
+                if is_unit(&v.algebraic_type) {
+                    return object([("const", json!(tag).into())]);
+                }
+                let payload = self.alg_type(&v.algebraic_type);
+                object([
+                    ("type", json!("object").into()),
+                    ("properties", Node::Object(vec![(tag.clone(), payload)])),
+                    ("required", json!([tag]).into()),
+                    ("additionalProperties", json!(false).into()),
+                ])
+            })
+            .collect();
+        object([("oneOf", Node::Array(variants))])
+    }
+}
+
+fn integer(minimum: Value, maximum: Value) -> Node {
+    object([
+        ("type", json!("integer").into()),
+        ("minimum", minimum.into()),
+        ("maximum", maximum.into()),
+    ])
+}
+
+/// SpacetimeDB's own types, as they are usually exchanged: identities as
+/// hex strings, timestamps as RFC 3339 and durations in microseconds
+fn special_schema(special: &str) -> Node {
+    match special {
+        "Identity" => object([
+            ("type", json!("string").into()),
+            ("pattern", json!("^(0x)?[0-9a-fA-F]{64}$").into()),
+        ]),
+        "Timestamp" => object([
+            ("type", json!("string").into()),
+            ("format", json!("date-time").into()),
+        ]),
+        "Duration" => object([
+            ("type", json!("integer").into()),
+            ("description", json!("Microseconds").into()),
+        ]),
+        "ScheduledAt" => {
+            let variant = |tag: &str, payload: Node| {
+                object([
+                    ("type", json!("object").into()),
+                    ("properties", Node::Object(vec![(tag.to_string(), payload)])),
+                    ("required", json!([tag]).into()),
+                    ("additionalProperties", json!(false).into()),
+                ])
+            };
+            object([(
+                "oneOf",
+                Node::Array(vec![
+                    variant("Interval", special_schema("Duration")),
+                    variant("Time", special_schema("Timestamp")),
+                ]),
+            )])
+        }
+        _ => Node::Object(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, schema};
+
+    fn document(schema: &SatsSchema, table: Option<&str>) -> Value {
+        serde_json::from_str(&generate_json_schema(schema, table).unwrap()).unwrap()
+    }
+
+    fn def_keys(document: &Value) -> Vec<&str> {
+        document["$defs"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn demo_matches_snapshot() {
+        assert_eq!(
+            generate_json_schema(&demo_schema(), None).unwrap() + "\n",
+            include_str!("../tests/fixtures/demo.schema.json")
+        );
+    }
+
+    #[test]
+    fn without_a_filter_every_named_type_is_defined() {
+        let document = document(&demo_schema(), None);
+        assert_eq!(document["$schema"], DIALECT);
+        let titles: Vec<_> = document["anyOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "player",
+                "item_kind",
+                "guild",
+                "guild_member",
+                "chat_message",
+                "game_tick"
+            ]
+        );
+        assert_eq!(
+            def_keys(&document),
+            [
+                "ChatMessage",
+                "GameTick",
+                "Guild",
+                "GuildMember",
+                "GuildRole",
+                "ItemKind",
+                "ItemStack",
+                "Player",
+                "PlayerStatus",
+                "Rarity",
+                "Vec2",
+            ]
+        );
+    }
+
+    #[test]
+    fn table_filter_keeps_only_reachable_types() {
+        let document = document(&demo_schema(), Some("PLAYER"));
+        assert_eq!(document["title"], "player");
+        assert_eq!(document["$ref"], "#/$defs/Player");
+        assert!(document.get("anyOf").is_none());
+        assert_eq!(
+            def_keys(&document),
+            ["ItemStack", "Player", "PlayerStatus", "Vec2"]
+        );
+
+        let error = generate_json_schema(&demo_schema(), Some("nope")).unwrap_err();
+        assert_eq!(error.to_string(), "Table 'nope' not found");
+    }
+
+    #[test]
+    fn properties_and_required_follow_the_element_order() {
+        let text = generate_json_schema(&demo_schema(), Some("player")).unwrap();
+        let fields = [
+            "id",
+            "identity",
+            "name",
+            "status",
+            "position",
+            "nickname",
+            "created_at",
+            "inventory",
+        ];
+        let player = &text[text.find("\"Player\": {").unwrap()..];
+        let positions: Vec<_> = fields
+            .iter()
+            .map(|f| player.find(&format!("\"{f}\": {{")).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]), "{player}");
+
+        let document: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(document["$defs"]["Player"]["required"], json!(fields));
+        assert_eq!(document["$defs"]["Player"]["additionalProperties"], false);
+    }
+
+    #[test]
+    fn types_map_to_json_schema_keywords() {
+        let document = document(&demo_schema(), Some("player"));
+        let player = &document["$defs"]["Player"]["properties"];
+        assert_eq!(
+            player["id"],
+            json!({"type": "integer", "minimum": 0, "maximum": u64::MAX})
+        );
+        assert_eq!(player["name"], json!({"type": "string"}));
+        assert_eq!(
+            player["nickname"],
+            json!({"anyOf": [{"type": "string"}, {"type": "null"}]})
+        );
+        assert_eq!(
+            player["inventory"],
+            json!({"type": "array", "items": {"$ref": "#/$defs/ItemStack"}})
+        );
+        assert_eq!(
+            player["created_at"],
+            json!({"type": "string", "format": "date-time"})
+        );
+        assert_eq!(
+            document["$defs"]["ItemStack"]["properties"]["next"],
+            json!({"anyOf": [{"$ref": "#/$defs/ItemStack"}, {"type": "null"}]})
+        );
+        assert_eq!(
+            document["$defs"]["PlayerStatus"]["oneOf"][0],
+            json!({"const": "Online"})
+        );
+        assert_eq!(
+            document["$defs"]["PlayerStatus"]["oneOf"][2]["required"],
+            json!(["Banned"])
+        );
+
+        let rarity = &self::document(&demo_schema(), None)["$defs"]["Rarity"];
+        assert!(rarity["enum"].is_array(), "{rarity}");
+    }
+
+    #[test]
+    fn def_names_are_unique_and_escaped_in_refs() {
+        let field =
+            |name: &str, r: u32| json!({"name": {"some": name}, "algebraic_type": {"Ref": r}});
+        let schema = schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [field("a", 1), field("b", 2), field("c", 3)]}},
+                {"Product": {"elements": []}},
+                {"Product": {"elements": [{"name": {"some": "y"}, "algebraic_type": {"Bool": []}}]}},
+                {"Sum": {"variants": [{"name": {"some": "A"}, "algebraic_type": {"U8": []}}]}},
+            ]},
+            "tables": [{"name": "t", "product_type_ref": 0, "primary_key": []}],
+            "reducers": [],
+            "types": [
+                {"name": {"scope": [], "name": "Row"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "a/b"}, "ty": 1, "custom_ordering": true},
+                {"name": {"scope": [], "name": "a/b"}, "ty": 2, "custom_ordering": true},
+            ],
+        }));
+        let document = document(&schema, Some("t"));
+        assert_eq!(def_keys(&document), ["Row", "Type_3", "a/b", "a/b_2"]);
+        let row = &document["$defs"]["Row"]["properties"];
+        assert_eq!(row["a"], json!({"$ref": "#/$defs/a~1b"}));
+        assert_eq!(row["b"], json!({"$ref": "#/$defs/a~1b_2"}));
+        assert_eq!(row["c"], json!({"$ref": "#/$defs/Type_3"}));
+    }
+}
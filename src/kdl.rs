@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crate::identifiers::kdl_string;
+use crate::schema::sats_types::{AlgebraicType, SatsSchema, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+};
+
+/// Render the schema as a KDL document: one node per table (with a child
+/// node per field) followed by the standalone structs and enums.
+///
+/// All values are emitted as quoted strings so the output is valid under
+/// both KDL v1 and v2.
+///
+/// Nodes are written to `out` as they are rendered, so memory use is
+/// bounded by the largest node rather than the whole document.
+pub fn write_kdl(out: &mut impl Write, schema: &SatsSchema) -> io::Result<()> {
+    let type_names = build_type_names(schema);
+
+    for table in &schema.tables {
+        let type_name = type_names
+            .get(&table.product_type_ref)
+            .cloned()
+            .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
+        writeln!(
+            out,
+            "table {} type={} {{",
+            kdl_string(&table.name),
+            kdl_string(&type_name)
+        )?;
+
+        if let Some(TypeDef::Product { Product }) =
+            schema.typespace.types.get(table.product_type_ref)
+        {
+            for (i, element) in Product.elements.iter().enumerate() {
+                let field_name = element
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                let field_type = format_type(schema, &element.algebraic_type, &type_names);
+                writeln!(
+                    out,
+                    "    field {} {}",
+                    kdl_string(&field_name),
+                    kdl_string(&field_type)
+                )?;
+            }
+
+            for &pk in &table.primary_key {
+                let pk_name = Product
+                    .elements
+                    .get(pk)
+                    .and_then(|e| e.name.as_option())
+                    .map_or_else(|| pk.to_string(), str::to_string);
+                writeln!(out, "    primary-key {}", kdl_string(&pk_name))?;
+            }
+        }
+
+        writeln!(out, "}}")?;
+    }
+
+    // Standalone types, in the same order as the pretty view
+    let table_type_refs: HashSet<usize> =
+        schema.tables.iter().map(|t| t.product_type_ref).collect();
+    let mut standalone_types: Vec<_> = type_names
+        .iter()
+        .filter(|(type_idx, _)| !table_type_refs.contains(type_idx))
+        .collect();
+    standalone_types.sort_by_key(|(_, name)| name.to_lowercase());
+
+    for (type_idx, real_name) in standalone_types {
+        match schema.typespace.types.get(*type_idx) {
+            Some(TypeDef::Product { Product }) => {
+                if let Some(special) = detect_spacetimedb_type(Product) {
+                    writeln!(out, "alias {} {}", kdl_string(real_name), kdl_string(&special))?;
+                    continue;
+                }
+                writeln!(out, "struct {} {{", kdl_string(real_name))?;
+                for (i, element) in Product.elements.iter().enumerate() {
+                    let field_name = element
+                        .name
+                        .as_option()
+                        .map_or_else(|| i.to_string(), str::to_string);
+                    let field_type = format_type(schema, &element.algebraic_type, &type_names);
+                    writeln!(
+                        out,
+                        "    field {} {}",
+                        kdl_string(&field_name),
+                        kdl_string(&field_type)
+                    )?;
+                }
+                writeln!(out, "}}")?;
+            }
+            Some(TypeDef::Sum { Sum }) => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    writeln!(out, "alias {} {}", kdl_string(real_name), kdl_string(&special))?;
+                    continue;
+                }
+                writeln!(out, "enum {} {{", kdl_string(real_name))?;
+                for (i, variant) in Sum.variants.iter().enumerate() {
+                    let variant_name = variant
+                        .name
+                        .as_option()
+                        .map_or_else(|| i.to_string(), str::to_string);
+                    match &variant.algebraic_type {
+                        AlgebraicType::Product { Product } if Product.elements.is_empty() => {
+                            writeln!(out, "    variant {}", kdl_string(&variant_name))?;
+                        }
+                        other => {
+                            let variant_type = format_type(schema, other, &type_names);
+                            writeln!(
+                                out,
+                                "    variant {} {}",
+                                kdl_string(&variant_name),
+                                kdl_string(&variant_type)
+                            )?;
+                        }
+                    }
+                }
+                writeln!(out, "}}")?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, wide_schema, CountingWriter};
+
+    fn kdl(schema: &SatsSchema) -> String {
+        let mut out = Vec::new();
+        write_kdl(&mut out, schema).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn demo_matches_fixture() {
+        assert_eq!(
+            kdl(&demo_schema()),
+            include_str!("../tests/fixtures/demo.kdl")
+        );
+    }
+
+    #[test]
+    fn huge_schemas_are_written_node_by_node() {
+        let schema = wide_schema(5000);
+        let mut out = CountingWriter::default();
+        write_kdl(&mut out, &schema).unwrap();
+        assert!(out.total > 500_000, "{} bytes", out.total);
+        assert!(
+            out.largest_write <= 4096,
+            "largest write {} bytes",
+            out.largest_write
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::codegen::{is_declared, resolve, unique, Primitive, Shape, Special};
+use crate::identifiers::{camel_case, kotlin_identifier, pascal_case};
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+
+const INDENT: &str = "    ";
+
+/// Declared when a field uses ScheduledAt
+const SCHEDULE_AT: &str = "\
+@Serializable
+sealed interface ScheduleAt {
+    @Serializable
+    @SerialName(\"Interval\")
+    data class Interval(val value: TimeDuration) : ScheduleAt
+
+    @Serializable
+    @SerialName(\"Time\")
+    data class Time(val value: Timestamp) : ScheduleAt
+}
+";
+
+/// Render Kotlin declarations for the schema, for kotlinx.serialization:
+/// a `data class` per struct whose camelCase properties keep the schema's
+/// field names through `@SerialName`, an `enum class` per enum whose
+/// variants carry no data, and a `sealed interface` with a data class or
+/// data object per variant for the rest.
+///
+/// Options are nullable and arrays `List<T>`. SpacetimeDB's own types are
+/// `typealias`es (Identity is its hex string, Timestamp and TimeDuration
+/// microseconds as `Long`) declared at the top when used.
+pub fn generate_kotlin(schema: &SatsSchema, package: Option<&str>) -> String {
+    let names = type_names(schema);
+    let mut generator = KotlinGenerator {
+        schema,
+        names: &names,
+        taken: names.values().cloned().collect(),
+        specials: BTreeSet::new(),
+        big_integers: false,
+        hoisted: Vec::new(),
+    };
+
+    let mut entries: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| is_declared(schema, *idx))
+        .collect();
+    entries.sort_by(|a, b| names[a].cmp(&names[b]));
+
+    let mut declarations = Vec::new();
+    for idx in entries {
+        let name = &names[&idx];
+        let declaration = match &schema.typespace.types[idx] {
+            TypeDef::Product { Product } => generator.data_class(name, Product),
+            TypeDef::Sum { Sum } => generator.sum(name, Sum),
+            _ => continue,
+        };
+        declarations.push(declaration);
+        declarations.append(&mut generator.hoisted);
+    }
+    if generator.specials.contains(&Special::ScheduledAt) {
+        generator.specials.insert(Special::Duration);
+        generator.specials.insert(Special::Timestamp);
+        declarations.push(SCHEDULE_AT.to_string());
+    }
+
+    let mut out = String::new();
+    if let Some(package) = package {
+        let _ = writeln!(out, "package {package}\n");
+    }
+    let mut imports = vec![
+        "kotlinx.serialization.SerialName",
+        "kotlinx.serialization.Serializable",
+    ];
+    if generator.big_integers {
+        imports.extend(["java.math.BigInteger", "kotlinx.serialization.Contextual"]);
+    }
+    imports.sort_unstable();
+    for import in imports {
+        let _ = writeln!(out, "import {import}");
+    }
+
+    let aliases: Vec<_> = generator
+        .specials
+        .iter()
+        .filter_map(|special| match special {
+            Special::Identity => Some("// Hex-encoded\ntypealias Identity = String"),
+            Special::Timestamp => {
+                Some("// Microseconds since the Unix epoch\ntypealias Timestamp = Long")
+            }
+            Special::Duration => Some("// Microseconds\ntypealias TimeDuration = Long"),
+            Special::ScheduledAt => None,
+        })
+        .collect();
+    if !aliases.is_empty() {
+        let _ = write!(out, "\n{}\n", aliases.join("\n\n"));
+    }
+
+    for declaration in &declarations {
+        out.push('\n');
+        out.push_str(declaration);
+    }
+    out
+}
+
+/// Kotlin type name for each typespace entry, PascalCase and unique
+/// after sanitizing (so `player` and `Player` don't clash);
+/// unnamed entries become `Type<index>`. The names of the SpacetimeDB
+/// aliases are kept free.
+fn type_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken: HashSet<String> = ["Identity", "Timestamp", "TimeDuration", "ScheduleAt"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    for named in &schema.types {
+        names.entry(named.ty).or_insert_with(|| {
+            unique(
+                &mut taken,
+                kotlin_identifier(&pascal_case(&named.name.name)),
+            )
+        });
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(&mut taken, format!("Type{idx}")));
+    }
+    names
+}
+
+struct KotlinGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: &'a HashMap<usize, String>,
+    /// Top-level names in use: types and hoisted types
+    taken: HashSet<String>,
+    /// SpacetimeDB types referenced, whose declarations go at the top
+    specials: BTreeSet<Special>,
+    /// Whether a 128- or 256-bit integer needs `BigInteger`
+    big_integers: bool,
+    /// Classes for anonymous types found inside the declaration being
+    /// generated, emitted right after it
+    hoisted: Vec<String>,
+}
+
+impl KotlinGenerator<'_> {
+    fn data_class(&mut self, name: &str, product: &ProductType) -> String {
+        if product.elements.is_empty() {
+            return format!("@Serializable\nclass {name}\n");
+        }
+        let mut taken = HashSet::new();
+        let mut properties = Vec::new();
+        for (i, element) in product.elements.iter().enumerate() {
+            let original = element.name.as_option();
+            let base = match original {
+                Some(field) => kotlin_identifier(&camel_case(field)),
+                None => format!("item{}", i + 1),
+            };
+            let property = unique(&mut taken, base);
+            let ty = self.kotlin_type(&element.algebraic_type, name, &property);
+            properties.push(match original {
+                Some(field) => format!(
+                    "{INDENT}@SerialName({}) val {property}: {ty}",
+                    kotlin_string(field)
+                ),
+                None => format!("{INDENT}val {property}: {ty}"),
+            });
+        }
+        format!(
+            "@Serializable\ndata class {name}(\n{}\n)\n",
+            properties.join(",\n")
+        )
+    }
+
+    fn sum(&mut self, name: &str, sum: &SumType) -> String {
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+        let all_unit = sum.variants.iter().all(|v| is_unit(&v.algebraic_type));
+
+        // Nested classes can't share the interface's name
+        let mut taken = if all_unit {
+            HashSet::new()
+        } else {
+            HashSet::from([name.to_string()])
+        };
+        let variants: Vec<_> = sum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let original = v
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                let base = v
+                    .name
+                    .as_option()
+                    .map_or_else(|| format!("Variant{i}"), kotlin_identifier);
+                (unique(&mut taken, base), original, &v.algebraic_type)
+            })
+            .collect();
+
+        if all_unit {
+            let entries: Vec<_> = variants
+                .iter()
+                .map(|(variant, original, _)| {
+                    format!("{INDENT}@SerialName({}) {variant}", kotlin_string(original))
+                })
+                .collect();
+            return if entries.is_empty() {
+                format!("@Serializable\nenum class {name}\n")
+            } else {
+                format!(
+                    "@Serializable\nenum class {name} {{\n{}\n}}\n",
+                    entries.join(",\n")
+                )
+            };
+        }
+
+        let mut out = format!("@Serializable\nsealed interface {name} {{\n");
+        for (i, (variant, original, payload)) in variants.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(
+                out,
+                "{INDENT}@Serializable\n{INDENT}@SerialName({})",
+                kotlin_string(original)
+            );
+            if is_unit(payload) {
+                let _ = writeln!(out, "{INDENT}data object {variant} : {name}");
+            } else {
+                let ty = self.kotlin_type(payload, name, variant);
+                let _ = writeln!(
+                    out,
+                    "{INDENT}data class {variant}(val value: {ty}) : {name}"
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// `owner` and `member` name hoisted anonymous types (`PlayerStats`
+    /// for an inline struct in `Player.stats`)
+    fn kotlin_type(&mut self, alg_type: &AlgebraicType, owner: &str, member: &str) -> String {
+        let shape = resolve(self.schema, alg_type);
+        self.spell(shape, owner, member)
+    }
+
+    fn spell(&mut self, shape: Shape, owner: &str, member: &str) -> String {
+        match shape {
+            Shape::Primitive(primitive) => match primitive {
+                Primitive::Bool => "Boolean".to_string(),
+                Primitive::I8 => "Byte".to_string(),
+                Primitive::U8 => "UByte".to_string(),
+                Primitive::I16 => "Short".to_string(),
+                Primitive::U16 => "UShort".to_string(),
+                Primitive::I32 => "Int".to_string(),
+                Primitive::U32 => "UInt".to_string(),
+                Primitive::I64 => "Long".to_string(),
+                Primitive::U64 => "ULong".to_string(),
+                Primitive::I128 | Primitive::U128 | Primitive::I256 | Primitive::U256 => {
+                    self.big_integers = true;
+                    "@Contextual BigInteger".to_string()
+                }
+                Primitive::F32 => "Float".to_string(),
+                Primitive::F64 => "Double".to_string(),
+                Primitive::String => "String".to_string(),
+            },
+            Shape::Special(special) => {
+                self.specials.insert(special);
+                match special {
+                    Special::Identity => "Identity",
+                    Special::Timestamp => "Timestamp",
+                    Special::Duration => "TimeDuration",
+                    Special::ScheduledAt => "ScheduleAt",
+                }
+                .to_string()
+            }
+            Shape::Option(inner) => nullable(self.spell(*inner, owner, member)),
+            Shape::Array(element) => format!("List<{}>", self.spell(*element, owner, member)),
+            Shape::Map(key, value) => {
+                let key = self.spell(*key, owner, &format!("{member}Key"));
+                let value = self.spell(*value, owner, &format!("{member}Value"));
+                format!("Map<{key}, {value}>")
+            }
+            Shape::Named(idx) => self.names[&idx].clone(),
+            Shape::Product(product) => {
+                let name = unique(&mut self.taken, format!("{owner}{}", pascal_case(member)));
+                let definition = self.data_class(&name, product);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Sum(sum) => {
+                let name = unique(&mut self.taken, format!("{owner}{}", pascal_case(member)));
+                let definition = self.sum(&name, sum);
+                self.hoisted.push(definition);
+                name
+            }
+            Shape::Any => "kotlinx.serialization.json.JsonElement".to_string(),
+        }
+    }
+}
+
+/// `T?`; an option of an option can't be told apart in Kotlin, so it stays `T?`
+fn nullable(ty: String) -> String {
+    if ty.ends_with('?') {
+        ty
+    } else {
+        format!("{ty}?")
+    }
+}
+
+/// A double-quoted Kotlin string; `$` is escaped so it doesn't start a template
+fn kotlin_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema, schema};
+    use serde_json::json;
+
+    #[test]
+    fn data_classes_keep_the_schema_field_names() {
+        let kotlin = generate_kotlin(&demo_schema(), Some("com.example.game"));
+        assert!(kotlin.starts_with("package com.example.game\n"), "{kotlin}");
+        assert!(
+            kotlin.contains(concat!(
+                "@Serializable\n",
+                "data class Guild(\n",
+                "    @SerialName(\"id\") val id: ULong,\n",
+                "    @SerialName(\"name\") val name: String,\n",
+                "    @SerialName(\"founded\") val founded: Timestamp,\n",
+                "    @SerialName(\"motto\") val motto: String?\n",
+                ")\n",
+            )),
+            "{kotlin}"
+        );
+        assert!(
+            kotlin.contains("    @SerialName(\"inventory\") val inventory: List<ItemStack>\n"),
+            "{kotlin}"
+        );
+    }
+
+    #[test]
+    fn enums_become_enum_classes_or_sealed_interfaces() {
+        let kotlin = generate_kotlin(&demo_schema(), None);
+        assert!(
+            kotlin.contains("enum class GuildRole {\n    @SerialName(\"Member\") Member,\n"),
+            "{kotlin}"
+        );
+        assert!(
+            kotlin.contains(concat!(
+                "sealed interface PlayerStatus {\n",
+                "    @Serializable\n",
+                "    @SerialName(\"Online\")\n",
+                "    data object Online : PlayerStatus\n",
+            )),
+            "{kotlin}"
+        );
+        assert!(
+            kotlin.contains("    data class Banned(val value: String) : PlayerStatus\n"),
+            "{kotlin}"
+        );
+        assert!(
+            kotlin.contains("\nsealed interface ScheduleAt {\n"),
+            "{kotlin}"
+        );
+    }
+
+    #[test]
+    fn aliases_are_declared_only_when_used() {
+        let kotlin = generate_kotlin(&recursive_schema(), None);
+        assert!(!kotlin.contains("typealias"), "{kotlin}");
+        assert!(
+            kotlin.contains("    @SerialName(\"children\") val children: List<Node>\n"),
+            "{kotlin}"
+        );
+
+        let kotlin = generate_kotlin(
+            &schema(json!({
+                "typespace": {"types": [{"Product": {"elements": [
+                    {"name": {"some": "when"}, "algebraic_type": {"U128": []}},
+                ]}}]},
+                "tables": [{"name": "ledger", "product_type_ref": 0, "primary_key": []}],
+                "types": [{"name": {"scope": [], "name": "Ledger"}, "ty": 0, "custom_ordering": true}],
+            })),
+            None,
+        );
+        assert!(!kotlin.contains("typealias"), "{kotlin}");
+        assert!(kotlin.contains("import java.math.BigInteger\n"), "{kotlin}");
+        assert!(
+            kotlin.contains("@SerialName(\"when\") val `when`: @Contextual BigInteger\n"),
+            "{kotlin}"
+        );
+    }
+}
@@ -1,10 +1,50 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
+mod ascii;
+mod capabilities;
+mod catalog;
+mod codegen;
+mod color;
+mod compact;
+mod coverage;
+mod csharp;
+mod csv;
+mod dot;
+mod go;
+mod history;
+mod html;
+mod identifiers;
+mod index_report;
+mod infer_names;
+mod json_stream;
+mod jsonl;
+mod jsonschema;
+mod kdl;
+mod kotlin;
+mod markdown;
+mod mermaid;
+mod metadata;
+mod model;
+mod output;
+mod proto;
+mod reserved_names;
+mod resolved;
+mod rust;
 mod schema;
+mod selftest;
 mod spacetime_client;
+mod spacetime_commands;
+mod sql;
+mod template;
+#[cfg(test)]
+mod test_support;
+mod typescript;
+mod validate;
+mod zod;
 
-use schema::{SchemaArgs, OutputFormat};
+use output::outln;
+use schema::{NameMatcher, OutputFormat, SchemaArgs};
 
 #[derive(Parser)]
 #[command(name = "spacetime-schema-tool")]
@@ -12,8 +52,8 @@ use schema::{SchemaArgs, OutputFormat};
 #[command(version)]
 struct Cli {
     /// Database name
-    #[arg(long)]
-    db: String,
+    #[arg(long, required_unless_present_any = ["validate_file", "capabilities", "demo", "file", "list", "whoami"])]
+    db: Option<String>,
 
     /// Server URL (default: <http://localhost:3000>)
     #[arg(long, default_value = "http://localhost:3000")]
@@ -21,50 +61,448 @@ struct Cli {
 
     /// Schema version to fetch
     #[arg(long = "schema-version")]
-    version: Option<String>,
+    schema_version: Option<String>,
+
+    /// Extra query parameter for the schema request (repeatable)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_query_param)]
+    query: Vec<(String, String)>,
+
+    /// Log each schema request URL, query string included, to stderr
+    #[arg(long, short = 'v')]
+    verbose: bool,
+
+    /// Bearer token to authenticate with (default: the SPACETIME_TOKEN environment variable, then the token `spacetime login` saved if it is for this server)
+    #[arg(long, value_name = "TOKEN")]
+    token: Option<String>,
+
+    /// Fail instead of warning when the token `spacetime login` saved is for another server
+    #[arg(long)]
+    strict_auth: bool,
+
+    /// Show which token requests to the server would use and the server it belongs to
+    #[arg(long, conflicts_with_all = ["db", "demo", "file", "list", "selftest"])]
+    whoami: bool,
+
+    /// Seconds to wait for each request to the server; 0 waits forever
+    #[arg(long, value_name = "SECONDS", default_value_t = spacetime_client::DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+
+    /// Times to retry a schema request that fails with a connection error or a 5xx response
+    #[arg(long, value_name = "N", default_value_t = spacetime_client::DEFAULT_RETRIES)]
+    retries: u32,
 
     /// Use `SpacetimeDB` cloud
     #[arg(long, conflicts_with = "server")]
     cloud: bool,
 
+    /// When to use colors: auto (terminals only), always or never
+    #[arg(long, value_enum, default_value = "auto")]
+    color: color::ColorChoice,
+
+    /// Shorthand for --color never
+    #[arg(long, conflicts_with = "color")]
+    no_color: bool,
+
+    /// Replace emoji and box-drawing characters in the pretty views with plain ASCII
+    /// (automatic when stdout is not a terminal or TERM=dumb)
+    #[arg(long)]
+    ascii: bool,
+
     /// Output format
     #[arg(long, value_enum, default_value = "pretty")]
     format: OutputFormat,
 
+    /// Write the results to this file instead of stdout (progress messages always go to stderr)
+    #[arg(long, value_name = "PATH")]
+    output: Option<std::path::PathBuf>,
+
+    /// Replace an existing --output file whose content differs from the new results
+    #[arg(long, requires = "output")]
+    force: bool,
+
+    /// Leave the source metadata comments out of the generated code and document formats
+    #[arg(long)]
+    no_metadata_footer: bool,
+
+    /// Omit the fetch time from the source metadata so regenerated files only change with the schema
+    #[arg(long)]
+    reproducible: bool,
+
+    /// Document layout for --format catalog
+    #[arg(long, value_enum, default_value = "openmetadata")]
+    catalog_flavor: catalog::CatalogFlavor,
+
+    /// Namespace for --format csharp
+    #[arg(long, default_value = "SpacetimeDB.Types")]
+    namespace: String,
+
+    /// C# type to use for a SpacetimeDB type under --format csharp, e.g.
+    /// Identity=System.String (repeatable; Identity, Timestamp, Duration or ScheduledAt)
+    #[arg(long = "csharp-type", value_name = "NAME=TYPE", value_parser = parse_query_param)]
+    csharp_types: Vec<(String, String)>,
+
+    /// Package declared by --format proto
+    #[arg(long)]
+    package: Option<String>,
+
+    /// Define every type at the top level in --format rust instead of in modules following their scopes
+    #[arg(long)]
+    rust_flat: bool,
+
+    /// Package clause for --format go
+    #[arg(long, default_value = "schema")]
+    go_package: String,
+
+    /// Package declared by --format kotlin
+    #[arg(long)]
+    kotlin_package: Option<String>,
+
+    /// Cell separator for --format csv: a single character, or "tab" for TSV
+    #[arg(long, default_value = ",", value_parser = parse_delimiter)]
+    delimiter: char,
+
+    /// Leave the table fields out of --format csv and list only structs and enums
+    #[arg(long)]
+    types_only: bool,
+
+    /// Handlebars template to render with --format template
+    #[arg(long, value_name = "PATH", required_if_eq("format", "template"))]
+    template: Option<std::path::PathBuf>,
+
+    /// Print the JSON that --format template renders templates with
+    #[arg(long)]
+    template_context: bool,
+
     /// Filter to show only specific table
-    #[arg(long, conflicts_with_all = ["type_filter", "enum_filter"])]
+    #[arg(long, conflicts_with_all = ["type_filter", "enum_filter", "reducer_filter"])]
     table: Option<String>,
 
     /// Filter to show only specific type
-    #[arg(long = "type", conflicts_with_all = ["table", "enum_filter"])]
+    #[arg(long = "type", conflicts_with_all = ["table", "enum_filter", "reducer_filter"])]
     type_filter: Option<String>,
 
     /// Filter to show only specific enum
-    #[arg(long = "enum", conflicts_with_all = ["table", "type_filter"])]
+    #[arg(long = "enum", conflicts_with_all = ["table", "type_filter", "reducer_filter"])]
     enum_filter: Option<String>,
 
+    /// Filter to show only specific reducer
+    #[arg(long = "reducer", conflicts_with_all = ["table", "type_filter", "enum_filter"])]
+    reducer_filter: Option<String>,
+
     /// Search pattern (matches table/type/enum names)
     #[arg(long, short = 's')]
     search: Option<String>,
+
+    /// Treat the --search pattern as a regular expression (case-sensitive; see --ignore-case)
+    #[arg(long, requires = "search")]
+    regex: bool,
+
+    /// Match the --regex pattern case-insensitively, like a leading (?i)
+    #[arg(long, requires = "regex")]
+    ignore_case: bool,
+
+    /// List the tables and types whose definitions reference this type
+    #[arg(
+        long,
+        value_name = "TYPE",
+        conflicts_with_all = ["table", "type_filter", "enum_filter", "reducer_filter", "search"]
+    )]
+    referenced_by: Option<String>,
+
+    /// Only list tables with at least this many fields
+    #[arg(long)]
+    min_fields: Option<usize>,
+
+    /// Only list tables with at most this many fields
+    #[arg(long)]
+    max_fields: Option<usize>,
+
+    /// Print ready-to-run `spacetime` CLI commands in the table and reducer views
+    #[arg(long)]
+    show_commands: bool,
+
+    /// Show lifecycle reducers and scheduled tables
+    #[arg(long)]
+    automation: bool,
+
+    /// Print only the summary counts (as JSON with --format json)
+    #[arg(long, alias = "count-only")]
+    stats: bool,
+
+    /// List named types that no table, reducer or other type references
+    #[arg(long)]
+    unused: bool,
+
+    /// Report how much of the schema document this tool models and which keys it ignores
+    #[arg(long)]
+    coverage: bool,
+
+    /// Report primary keys, secondary indexes and index warnings per table
+    #[arg(long)]
+    index_report: bool,
+
+    /// Warn about tables with more columns than this and no secondary indexes (--index-report)
+    #[arg(long, value_name = "N", default_value_t = index_report::DEFAULT_WIDE_TABLE_COLUMNS)]
+    wide_table_columns: usize,
+
+    /// Compare the --table side by side with the same table in another database (a Markdown table with --format markdown)
+    #[arg(long, requires = "table")]
+    against_db: Option<String>,
+
+    /// Report the tables and fields that changed between --schema-version and this version
+    #[arg(long, value_name = "VERSION", conflicts_with_all = ["demo", "file", "against_db"])]
+    diff_version: Option<String>,
+
+    /// Annotate Timestamp and Duration fields with their units (pretty and markdown)
+    #[arg(long)]
+    annotate_units: bool,
+
+    /// Inline every referenced type in field types
+    #[arg(long)]
+    expand_all: bool,
+
+    /// Explain how each field's type label was detected
+    #[arg(long)]
+    explain: bool,
+
+    /// Replace long field types with placeholders (T1, T2, ...) listed after each table
+    #[arg(long)]
+    abbreviate_types: bool,
+
+    /// Color field types by category (primitive, collection, option, user type, SpacetimeDB type)
+    #[arg(long)]
+    color_by_type_category: bool,
+
+    /// Print a legend explaining the field markers (and the type colors with --color-by-type-category)
+    #[arg(long)]
+    legend: bool,
+
+    /// Collapse enums with more variants than this: one line in the overview, a folded block in Markdown and HTML (0 never collapses)
+    #[arg(long, value_name = "N", default_value_t = schema::DEFAULT_ENUM_COLLAPSE_THRESHOLD)]
+    enum_collapse_threshold: usize,
+
+    /// Show only this many variants in the --enum and --type views
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+
+    /// Skip this many variants in the --enum and --type views
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    skip: usize,
+
+    /// Print a `field  type  flags` header above table fields and align the columns
+    #[arg(long)]
+    header: bool,
+
+    /// Print the typespace index -> type name map as JSON
+    #[arg(long)]
+    dump_names: bool,
+
+    /// Include module scopes in dumped names (e.g. `math::Vec2`)
+    #[arg(long, requires = "dump_names")]
+    qualified_names: bool,
+
+    /// Report which SpacetimeDB API endpoints the server supports
+    #[arg(long)]
+    probe_endpoints: bool,
+
+    /// List the databases on the server and exit
+    #[arg(long, conflicts_with_all = ["db", "demo", "file", "selftest", "probe_endpoints", "last_seen"])]
+    list: bool,
+
+    /// Try the tool offline on a bundled example schema instead of fetching one
+    #[arg(long, conflicts_with_all = ["against_db", "selftest", "probe_endpoints", "last_seen"])]
+    demo: bool,
+
+    /// Load the schema from a saved JSON file instead of fetching it from a server
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["db", "server", "cloud", "demo", "against_db", "selftest", "probe_endpoints", "last_seen"]
+    )]
+    file: Option<String>,
+
+    /// Run a step-by-step diagnostic against the server and database (JSON report with --format json)
+    #[arg(long)]
+    selftest: bool,
+
+    /// Show when this tool last fetched the database's schema and when it last changed
+    #[arg(long)]
+    last_seen: bool,
+
+    /// List every table and column name that collides with a reserved name (as JSON with --format json)
+    #[arg(long)]
+    lint: bool,
+
+    /// Guess names for types the schema leaves unnamed (shown with a leading ~)
+    #[arg(long)]
+    infer_names: bool,
+
+    /// Don't report table and column names that collide with reserved SpacetimeDB names
+    #[arg(long)]
+    no_warnings: bool,
+
+    /// Additional words to treat as SQL keywords when checking names (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "WORDS")]
+    extra_keywords: Vec<String>,
+
+    /// Check a schema JSON file for problems instead of fetching one
+    #[arg(long, value_name = "PATH")]
+    validate_file: Option<String>,
+
+    /// Print the flags and formats this binary supports as JSON
+    #[arg(long)]
+    capabilities: bool,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// Stands in for the database name when reading --file: the file name
+/// without its extension
+fn file_label(path: &str) -> String {
+    std::path::Path::new(path).file_stem().map_or_else(
+        || path.to_string(),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
+
+fn parse_query_param(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{param}'")),
+    }
+}
 
-    let args = SchemaArgs {
-        db: cli.db,
+fn parse_delimiter(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (value, chars.next(), chars.next()) {
+        ("tab" | "\\t", _, _) => Ok('\t'),
+        (_, Some(c), None) if c != '"' && c != '\n' && c != '\r' => Ok(c),
+        _ => Err(format!(
+            "expected a single character or 'tab', got '{value}'"
+        )),
+    }
+}
+
+/// Everything `fetch_schema` needs from the command line
+fn schema_args(cli: Cli) -> Result<SchemaArgs> {
+    Ok(SchemaArgs {
+        db: cli
+            .db
+            .or_else(|| cli.demo.then(|| "demo".to_string()))
+            .or_else(|| cli.file.as_deref().map(file_label))
+            .or_else(|| (cli.list || cli.whoami).then(String::new))
+            .expect(
+                "clap requires --db unless --validate-file, --capabilities, --demo, --file, --list or --whoami is given",
+            ),
         server: cli.server,
-        version: cli.version,
+        version: cli.schema_version,
+        query: cli.query,
+        verbose: cli.verbose,
         cloud: cli.cloud,
         format: cli.format,
+        catalog_flavor: cli.catalog_flavor,
+        namespace: cli.namespace,
+        csharp_types: cli.csharp_types,
+        package: cli.package,
+        rust_flat: cli.rust_flat,
+        go_package: cli.go_package,
+        kotlin_package: cli.kotlin_package,
+        delimiter: cli.delimiter,
+        types_only: cli.types_only,
+        template: cli.template,
+        template_context: cli.template_context,
         table: cli.table,
         type_filter: cli.type_filter,
+        referenced_by: cli.referenced_by,
         enum_filter: cli.enum_filter,
-        search: cli.search,
-    };
+        search: cli
+            .search
+            .map(|pattern| match cli.regex {
+                true => NameMatcher::regex(&pattern, cli.ignore_case),
+                false => Ok(NameMatcher::substring(&pattern)),
+            })
+            .transpose()?,
+        automation: cli.automation,
+        unused: cli.unused,
+        stats: cli.stats,
+        index_report: cli.index_report,
+        coverage: cli.coverage,
+        wide_table_columns: cli.wide_table_columns,
+        against_db: cli.against_db,
+        diff_version: cli.diff_version,
+        annotate_units: cli.annotate_units,
+        expand_all: cli.expand_all,
+        explain: cli.explain,
+        abbreviate_types: cli.abbreviate_types,
+        enum_collapse_threshold: cli.enum_collapse_threshold,
+        variant_skip: cli.skip,
+        variant_top: cli.top,
+        color_by_category: cli.color_by_type_category,
+        legend: cli.legend,
+        header: cli.header,
+        min_fields: cli.min_fields,
+        max_fields: cli.max_fields,
+        dump_names: cli.dump_names,
+        qualified_names: cli.qualified_names,
+        probe_endpoints: cli.probe_endpoints,
+        list: cli.list,
+        selftest: cli.selftest,
+        demo: cli.demo,
+        file: cli.file,
+        token: cli
+            .token
+            .or_else(|| std::env::var("SPACETIME_TOKEN").ok())
+            .filter(|token| !token.is_empty()),
+        strict_auth: cli.strict_auth,
+        whoami: cli.whoami,
+        timeout: (cli.timeout > 0).then(|| std::time::Duration::from_secs(cli.timeout)),
+        retries: cli.retries,
+        last_seen: cli.last_seen,
+        lint: cli.lint,
+        infer_names: cli.infer_names,
+        no_metadata_footer: cli.no_metadata_footer,
+        reproducible: cli.reproducible,
+        no_warnings: cli.no_warnings,
+        extra_keywords: cli.extra_keywords,
+        reducer_filter: cli.reducer_filter,
+        show_commands: cli.show_commands,
+    })
+}
 
-    schema::fetch_schema(args).await?;
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if let Some(path) = &cli.output {
+        output::redirect_to(path, cli.force)?;
+    }
+    color::configure(if cli.no_color {
+        color::ColorChoice::Never
+    } else {
+        cli.color
+    });
+    if matches!(cli.format, OutputFormat::Pretty) {
+        ascii::configure(cli.ascii);
+    }
 
-    Ok(())
+    if cli.capabilities {
+        let manifest = capabilities::capabilities_json(&Cli::command());
+        outln!("{}", serde_json::to_string_pretty(&manifest)?);
+        return output::finish();
+    }
+
+    if let Some(path) = &cli.validate_file {
+        let result = validate::validate_file(path);
+        output::finish()?;
+        return result;
+    }
+
+    let result = match schema_args(cli) {
+        Ok(args) => schema::fetch_schema(args).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => output::finish(),
+        Err(e) => {
+            output::discard();
+            Err(e)
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,441 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use anyhow::{anyhow, Result};
+
+use crate::schema::sats_types::{
+    AlgebraicType, ProductType, SatsSchema, SumType, TableInfo, TypeDef,
+};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+    is_nullable, match_fields, table_fields, unit_label, FieldMatch, SchemaArgs,
+};
+
+/// Render the schema as Markdown for wikis and READMEs: a section per table
+/// with a field table, then a section per standalone struct and enum.
+///
+/// `--table`, `--type`, `--enum` and `--search` narrow the document the
+/// same way they narrow the pretty view, and `--annotate-units` adds the
+/// unit after Timestamp and Duration types.
+///
+/// Sections are written to `out` as they are rendered, so memory use is
+/// bounded by the largest table rather than the whole document.
+pub fn write_markdown(out: &mut impl Write, schema: &SatsSchema, args: &SchemaArgs) -> Result<()> {
+    let type_names = build_type_names(schema);
+    let table_refs: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+
+    let mut standalone: Vec<(usize, &String)> = type_names
+        .iter()
+        .filter(|(idx, _)| !table_refs.contains(idx))
+        .map(|(idx, name)| (*idx, name))
+        .collect();
+    standalone.sort_by_key(|(_, name)| name.to_lowercase());
+
+    let (tables, types): (Vec<&TableInfo>, Vec<(usize, &String)>) = if let Some(name) = &args.table
+    {
+        let table = schema
+            .tables
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+        (vec![table], Vec::new())
+    } else if let Some(name) = args.type_filter.as_ref().or(args.enum_filter.as_ref()) {
+        let entry = type_names
+            .iter()
+            .find(|(_, n)| n.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Type '{name}' not found"))?;
+        if args.enum_filter.is_some()
+            && !matches!(
+                schema.typespace.types.get(*entry.0),
+                Some(TypeDef::Sum { .. })
+            )
+        {
+            return Err(anyhow!("'{name}' is not an enum"));
+        }
+        (Vec::new(), vec![(*entry.0, entry.1)])
+    } else if let Some(matcher) = &args.search {
+        (
+            schema
+                .tables
+                .iter()
+                .filter(|t| matcher.is_match(&t.name))
+                .collect(),
+            standalone
+                .into_iter()
+                .filter(|(_, name)| matcher.is_match(name))
+                .collect(),
+        )
+    } else {
+        (schema.tables.iter().collect(), standalone)
+    };
+
+    writeln!(out, "# Schema: {}", args.db)?;
+
+    if !tables.is_empty() {
+        out.write_all(b"\n## Tables\n")?;
+    }
+    for table in tables {
+        write_table(out, schema, table, &type_names, args.annotate_units)?;
+    }
+
+    let mut wrote_types_heading = false;
+    for (idx, name) in types {
+        let Some(def) = schema.typespace.types.get(idx) else {
+            continue;
+        };
+        if !wrote_types_heading {
+            out.write_all(b"\n## Types\n")?;
+            wrote_types_heading = true;
+        }
+        match def {
+            TypeDef::Product { Product } => {
+                write_struct(out, schema, name, Product, &type_names, args.annotate_units)?;
+            }
+            TypeDef::Sum { Sum } => write_enum(
+                out,
+                schema,
+                name,
+                Sum,
+                &type_names,
+                args.enum_collapse_threshold,
+                args.annotate_units,
+            )?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn write_table(
+    out: &mut impl Write,
+    schema: &SatsSchema,
+    table: &TableInfo,
+    type_names: &HashMap<usize, String>,
+    annotate_units: bool,
+) -> io::Result<()> {
+    let type_name = type_names
+        .get(&table.product_type_ref)
+        .cloned()
+        .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
+    writeln!(out, "\n### `{}`\n", table.name)?;
+    writeln!(out, "Row type: `{type_name}`")?;
+    if let Some(schedule) = table.schedule.as_ref().and_then(|s| s.as_option()) {
+        writeln!(out, "\nScheduled by reducer `{}`", schedule.reducer_name)?;
+    }
+
+    let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+    else {
+        return Ok(());
+    };
+    out.write_all(b"\n| Field | Type | Nullable | Primary key |\n")?;
+    out.write_all(b"|-------|------|----------|-------------|\n")?;
+    for (i, element) in Product.elements.iter().enumerate() {
+        let name = element
+            .name
+            .as_option()
+            .map_or_else(|| i.to_string(), str::to_string);
+        writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            code(&name),
+            type_cell(schema, &element.algebraic_type, type_names, annotate_units),
+            yes_no(is_nullable(schema, &element.algebraic_type)),
+            yes_no(table.primary_key.contains(&i))
+        )?;
+    }
+    Ok(())
+}
+
+fn write_struct(
+    out: &mut impl Write,
+    schema: &SatsSchema,
+    name: &str,
+    product: &ProductType,
+    type_names: &HashMap<usize, String>,
+    annotate_units: bool,
+) -> io::Result<()> {
+    if let Some(special) = detect_spacetimedb_type(product) {
+        writeln!(out, "\n### `{name}`\n\nSpacetimeDB type `{special}`")?;
+        return Ok(());
+    }
+    writeln!(out, "\n### `{name}` (struct)\n")?;
+    if product.elements.is_empty() {
+        out.write_all(b"No fields\n")?;
+        return Ok(());
+    }
+    out.write_all(b"| Field | Type | Nullable |\n")?;
+    out.write_all(b"|-------|------|----------|\n")?;
+    for (i, element) in product.elements.iter().enumerate() {
+        let field = element
+            .name
+            .as_option()
+            .map_or_else(|| i.to_string(), str::to_string);
+        writeln!(
+            out,
+            "| {} | {} | {} |",
+            code(&field),
+            type_cell(schema, &element.algebraic_type, type_names, annotate_units),
+            yes_no(is_nullable(schema, &element.algebraic_type))
+        )?;
+    }
+    Ok(())
+}
+
+/// Enums over `collapse_threshold` variants (0 never collapses) go in a
+/// collapsed `<details>` block so they don't bury the rest of the page
+fn write_enum(
+    out: &mut impl Write,
+    schema: &SatsSchema,
+    name: &str,
+    sum: &SumType,
+    type_names: &HashMap<usize, String>,
+    collapse_threshold: usize,
+    annotate_units: bool,
+) -> io::Result<()> {
+    if let Some(special) = detect_spacetimedb_sum_type(sum) {
+        writeln!(out, "\n### `{name}`\n\nSpacetimeDB type `{special}`")?;
+        return Ok(());
+    }
+    writeln!(out, "\n### `{name}` (enum)\n")?;
+    let collapsed = collapse_threshold > 0 && sum.variants.len() > collapse_threshold;
+    if collapsed {
+        writeln!(
+            out,
+            "<details>\n<summary>{} variants</summary>\n",
+            sum.variants.len()
+        )?;
+    }
+    for (i, variant) in sum.variants.iter().enumerate() {
+        let variant_name = variant
+            .name
+            .as_option()
+            .map_or_else(|| i.to_string(), str::to_string);
+        match &variant.algebraic_type {
+            AlgebraicType::Product { Product } if Product.elements.is_empty() => {
+                writeln!(out, "- {}", code(&variant_name))?;
+            }
+            other => {
+                let unit = unit_label(schema, other)
+                    .filter(|_| annotate_units)
+                    .map(|label| format!(" {label}"))
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "- {}{unit}",
+                    code(&format!(
+                        "{variant_name}({})",
+                        format_type(schema, other, type_names)
+                    ))
+                )?;
+            }
+        }
+    }
+    if collapsed {
+        out.write_all(b"\n</details>\n")?;
+    }
+    Ok(())
+}
+
+/// `--against-db` as a two-column Markdown table, one database per column.
+/// Rows are matched as in the terminal view: same-named fields first, with
+/// type differences in bold, then renames, then fields on one side only.
+pub fn write_table_comparison(
+    out: &mut impl Write,
+    left: (&str, &SatsSchema),
+    right: (&str, &SatsSchema),
+    table_name: &str,
+) -> io::Result<()> {
+    let left_table = table_fields(left.1, table_name);
+    let right_table = table_fields(right.1, table_name);
+    let header = |db: &str, table: &Option<(String, Vec<(String, String)>)>| match table {
+        Some((name, _)) => format!("{}: {}", code(db), code(name)),
+        None => format!("{}: (table not found)", code(db)),
+    };
+
+    writeln!(out, "## Compare table {}\n", code(table_name))?;
+    writeln!(
+        out,
+        "| {} | {} |",
+        header(left.0, &left_table),
+        header(right.0, &right_table)
+    )?;
+    writeln!(out, "| --- | --- |")?;
+
+    let left_fields = left_table.map(|(_, fields)| fields).unwrap_or_default();
+    let right_fields = right_table.map(|(_, fields)| fields).unwrap_or_default();
+    let cell = |(name, ty): &(String, String)| format!("{}: {}", code(name), code(ty));
+    for row in match_fields(&left_fields, &right_fields) {
+        let (l, r) = match row {
+            FieldMatch::Same(l, r) if l.1 == r.1 => (cell(l), cell(r)),
+            FieldMatch::Same(l, r) => (format!("**{}**", cell(l)), format!("**{}**", cell(r))),
+            FieldMatch::Renamed(l, r) => (
+                cell(l),
+                format!("{} (renamed from {})", cell(r), code(&l.0)),
+            ),
+            FieldMatch::Only(l, r) => (
+                l.map(cell).unwrap_or_default(),
+                r.map(cell).unwrap_or_default(),
+            ),
+        };
+        writeln!(out, "| {l} | {r} |")?;
+    }
+    Ok(())
+}
+
+/// A field's type as inline code, followed by its unit with `--annotate-units`
+fn type_cell(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+    annotate_units: bool,
+) -> String {
+    let cell = code(&format_type(schema, alg_type, type_names));
+    match unit_label(schema, alg_type).filter(|_| annotate_units) {
+        Some(label) => format!("{cell} {label}"),
+        None => cell,
+    }
+}
+
+/// Inline code that is safe inside a table cell
+fn code(text: &str) -> String {
+    format!("`{}`", text.replace('|', "\\|"))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        args, big_enum_schema, demo_schema, demo_schema_with, wide_schema, CountingWriter,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn demo_schema_matches_fixture() {
+        let mut out = Vec::new();
+        write_markdown(&mut out, &demo_schema(), &args(&[])).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            include_str!("../tests/fixtures/demo.md")
+        );
+    }
+
+    #[test]
+    fn units_are_annotated_on_request() {
+        let mut out = Vec::new();
+        write_markdown(&mut out, &demo_schema(), &args(&["--table", "game_tick"])).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("µs"));
+
+        let mut out = Vec::new();
+        let flags = ["--table", "guild", "--annotate-units"];
+        write_markdown(&mut out, &demo_schema(), &args(&flags)).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+        assert!(
+            markdown.contains("| `founded` | `Timestamp` (i64 µs since epoch) | no | no |"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("| `name` | `String` | no | no |"));
+
+        let mut out = Vec::new();
+        let flags = ["--table", "game_tick", "--annotate-units"];
+        write_markdown(&mut out, &demo_schema(), &args(&flags)).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+        assert!(
+            markdown.contains("| `tick_rate` | `Duration` (i64 µs) | no | no |"),
+            "{markdown}"
+        );
+    }
+
+    #[test]
+    fn large_schema_is_written_in_small_pieces() {
+        let mut out = CountingWriter::default();
+        write_markdown(&mut out, &wide_schema(5000), &args(&[])).unwrap();
+        assert!(out.total > 1 << 20, "wrote {} bytes", out.total);
+        assert!(
+            out.largest_write <= 4096,
+            "wrote {} bytes at once",
+            out.largest_write
+        );
+    }
+
+    fn render(schema: &SatsSchema, flags: &[&str]) -> String {
+        let mut out = Vec::new();
+        write_markdown(&mut out, schema, &args(flags)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn enums_at_or_under_the_threshold_stay_open() {
+        let flags = ["--enum-collapse-threshold", "400"];
+        let markdown = render(&big_enum_schema(400), &flags);
+        assert!(!markdown.contains("<details>"));
+        assert!(markdown.contains("- `Key399`"));
+
+        let markdown = render(&big_enum_schema(401), &flags);
+        assert!(markdown.contains("<summary>401 variants</summary>"));
+    }
+
+    fn comparison(right: &SatsSchema) -> String {
+        let mut out = Vec::new();
+        write_table_comparison(
+            &mut out,
+            ("game", &demo_schema()),
+            ("game_v2", right),
+            "player",
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn comparison_lines_up_both_databases() {
+        // game_v2 retypes name, renames position and drops inventory
+        let right = demo_schema_with(|schema| {
+            let player = &mut schema["typespace"]["types"][5]["Product"]["elements"];
+            player[2]["algebraic_type"] = json!({"U32": []});
+            player[4]["name"] = json!({"some": "pos"});
+            player.as_array_mut().unwrap().pop();
+        });
+        let markdown = comparison(&right);
+        assert!(
+            markdown.starts_with(concat!(
+                "## Compare table `player`\n\n",
+                "| `game`: `player` | `game_v2`: `player` |\n",
+                "| --- | --- |\n",
+                "| `id`: `u64` | `id`: `u64` |\n",
+            )),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| **`name`: `String`** | **`name`: `u32`** |\n"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| `position`: `Vec2` | `pos`: `Vec2` (renamed from `position`) |\n"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.ends_with("| `inventory`: `Vec<ItemStack>` |  |\n"),
+            "{markdown}"
+        );
+    }
+
+    #[test]
+    fn comparison_with_a_missing_table_has_an_empty_column() {
+        let right = demo_schema_with(|schema| schema["tables"][0]["name"] = json!("players"));
+        let markdown = comparison(&right);
+        assert!(
+            markdown.contains("| `game`: `player` | `game_v2`: (table not found) |\n"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("| `id`: `u64` |  |\n"), "{markdown}");
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type,
+    is_option_type,
+};
+
+/// How many of the target a field holds, as Mermaid's right-hand cardinality
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Cardinality {
+    One,
+    ZeroOrOne,
+    Many,
+}
+
+impl Cardinality {
+    fn symbol(self) -> &'static str {
+        match self {
+            Cardinality::One => "||--||",
+            Cardinality::ZeroOrOne => "||--o|",
+            Cardinality::Many => "||--o{",
+        }
+    }
+}
+
+/// Render the schema as a Mermaid `erDiagram`. Tables are entities named
+/// after the table; other structs and enums are entities too, prefixed
+/// `struct_` / `enum_` so they stand apart. Every field that uses another
+/// entity's type becomes a relationship labeled with the field name.
+///
+/// Mermaid attribute types can't contain `<`, `>` or commas, so types are
+/// simplified: `Vec<T>` is written `T[]`, `Option<T>` as `T` with an
+/// "optional" comment, and maps as `Map`.
+pub fn generate_mermaid(schema: &SatsSchema) -> String {
+    let type_names = build_type_names(schema);
+    let entities = entity_names(schema, &type_names);
+
+    let mut out = String::from("erDiagram\n");
+    let mut relationships: Vec<String> = Vec::new();
+
+    for table in &schema.tables {
+        let entity = &entities[&table.product_type_ref];
+        let _ = writeln!(out, "    {entity} {{");
+        if let Some(TypeDef::Product { Product }) =
+            schema.typespace.types.get(table.product_type_ref)
+        {
+            for (i, element) in Product.elements.iter().enumerate() {
+                let name = field_name(element.name.as_option(), i);
+                let (ty, optional) = simple_type(schema, &type_names, &element.algebraic_type);
+                let key = if table.primary_key.contains(&i) {
+                    " PK"
+                } else {
+                    ""
+                };
+                let comment = if optional { " \"optional\"" } else { "" };
+                let _ = writeln!(out, "        {ty} {name}{key}{comment}");
+            }
+            relationships.extend(product_relationships(schema, &entities, entity, Product));
+        }
+        out.push_str("    }\n");
+    }
+
+    let mut types: Vec<(usize, &String)> = entities
+        .iter()
+        .filter(|(idx, _)| !schema.tables.iter().any(|t| t.product_type_ref == **idx))
+        .map(|(idx, name)| (*idx, name))
+        .collect();
+    types.sort_by_key(|(_, name)| name.to_lowercase());
+
+    for (idx, entity) in types {
+        let _ = writeln!(out, "    {entity} {{");
+        match schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => {
+                for (i, element) in Product.elements.iter().enumerate() {
+                    let name = field_name(element.name.as_option(), i);
+                    let (ty, optional) = simple_type(schema, &type_names, &element.algebraic_type);
+                    let comment = if optional { " \"optional\"" } else { "" };
+                    let _ = writeln!(out, "        {ty} {name}{comment}");
+                }
+                relationships.extend(product_relationships(schema, &entities, entity, Product));
+            }
+            Some(TypeDef::Sum { Sum }) => {
+                for (i, variant) in Sum.variants.iter().enumerate() {
+                    let name = field_name(variant.name.as_option(), i);
+                    let ty = match &variant.algebraic_type {
+                        AlgebraicType::Product { Product } if Product.elements.is_empty() => {
+                            "unit".to_string()
+                        }
+                        other => simple_type(schema, &type_names, other).0,
+                    };
+                    let _ = writeln!(out, "        {ty} {name}");
+                }
+                relationships.extend(sum_relationships(schema, &entities, entity, Sum));
+            }
+            _ => {}
+        }
+        out.push_str("    }\n");
+    }
+
+    for line in relationships {
+        let _ = writeln!(out, "    {line}");
+    }
+    out
+}
+
+/// Entity name for every table row type and every named struct or enum,
+/// sanitized to the characters Mermaid accepts and made unique
+fn entity_names(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+) -> HashMap<usize, String> {
+    let mut entities = HashMap::new();
+    let mut taken = HashSet::new();
+    let mut unique = |base: String| {
+        let mut name = base.clone();
+        let mut n = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        name
+    };
+
+    for table in &schema.tables {
+        entities
+            .entry(table.product_type_ref)
+            .or_insert_with(|| unique(sanitize(&table.name)));
+    }
+    let mut named: Vec<(&usize, &String)> = type_names.iter().collect();
+    named.sort_by_key(|(idx, _)| **idx);
+    for (idx, name) in named {
+        let prefix = match schema.typespace.types.get(*idx) {
+            Some(TypeDef::Product { Product }) if detect_spacetimedb_type(Product).is_none() => {
+                "struct"
+            }
+            Some(TypeDef::Sum { Sum })
+                if detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum) =>
+            {
+                "enum"
+            }
+            _ => continue,
+        };
+        entities
+            .entry(*idx)
+            .or_insert_with(|| unique(format!("{prefix}_{}", sanitize(name))));
+    }
+    entities
+}
+
+fn product_relationships(
+    schema: &SatsSchema,
+    entities: &HashMap<usize, String>,
+    from: &str,
+    product: &ProductType,
+) -> Vec<String> {
+    let members = product
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (field_name(e.name.as_option(), i), &e.algebraic_type));
+    relationships(schema, entities, from, members)
+}
+
+fn sum_relationships(
+    schema: &SatsSchema,
+    entities: &HashMap<usize, String>,
+    from: &str,
+    sum: &SumType,
+) -> Vec<String> {
+    let members = sum
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (field_name(v.name.as_option(), i), &v.algebraic_type));
+    relationships(schema, entities, from, members)
+}
+
+/// One line per (field, target entity), in field order
+fn relationships<'a>(
+    schema: &SatsSchema,
+    entities: &HashMap<usize, String>,
+    from: &str,
+    members: impl Iterator<Item = (String, &'a AlgebraicType)>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (member, alg_type) in members {
+        let mut targets = BTreeMap::new();
+        entity_refs(
+            schema,
+            entities,
+            alg_type,
+            Cardinality::One,
+            &mut targets,
+            &mut HashSet::new(),
+        );
+        for (idx, cardinality) in targets {
+            lines.push(format!(
+                "{from} {} {} : \"{member}\"",
+                cardinality.symbol(),
+                entities[&idx]
+            ));
+        }
+    }
+    lines
+}
+
+/// Entities `alg_type` points at, with the loosest cardinality on the way.
+/// Refs to non-entities are followed into the typespace; `visiting` stops
+/// cycles among those.
+fn entity_refs(
+    schema: &SatsSchema,
+    entities: &HashMap<usize, String>,
+    alg_type: &AlgebraicType,
+    cardinality: Cardinality,
+    found: &mut BTreeMap<usize, Cardinality>,
+    visiting: &mut HashSet<usize>,
+) {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => {
+            let idx = *Ref as usize;
+            if entities.contains_key(&idx) {
+                let entry = found.entry(idx).or_insert(cardinality);
+                *entry = (*entry).max(cardinality);
+            } else if visiting.insert(idx) {
+                match schema.typespace.types.get(idx) {
+                    Some(TypeDef::Product { Product }) => {
+                        for element in &Product.elements {
+                            entity_refs(
+                                schema,
+                                entities,
+                                &element.algebraic_type,
+                                cardinality,
+                                found,
+                                visiting,
+                            );
+                        }
+                    }
+                    Some(TypeDef::Sum { Sum }) => {
+                        let cardinality = if is_option_type(Sum) {
+                            cardinality.max(Cardinality::ZeroOrOne)
+                        } else {
+                            cardinality
+                        };
+                        for variant in &Sum.variants {
+                            entity_refs(
+                                schema,
+                                entities,
+                                &variant.algebraic_type,
+                                cardinality,
+                                found,
+                                visiting,
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        AlgebraicType::Array { Array } => {
+            entity_refs(schema, entities, Array, Cardinality::Many, found, visiting);
+        }
+        AlgebraicType::Map { Map } => {
+            entity_refs(
+                schema,
+                entities,
+                &Map.key_ty,
+                Cardinality::Many,
+                found,
+                visiting,
+            );
+            entity_refs(
+                schema,
+                entities,
+                &Map.ty,
+                Cardinality::Many,
+                found,
+                visiting,
+            );
+        }
+        AlgebraicType::Product { Product } => {
+            for element in &Product.elements {
+                entity_refs(
+                    schema,
+                    entities,
+                    &element.algebraic_type,
+                    cardinality,
+                    found,
+                    visiting,
+                );
+            }
+        }
+        AlgebraicType::Sum { Sum } => {
+            let cardinality = if is_option_type(Sum) {
+                cardinality.max(Cardinality::ZeroOrOne)
+            } else {
+                cardinality
+            };
+            for variant in &Sum.variants {
+                entity_refs(
+                    schema,
+                    entities,
+                    &variant.algebraic_type,
+                    cardinality,
+                    found,
+                    visiting,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A Mermaid-safe attribute type, and whether the field is optional
+fn simple_type(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    alg_type: &AlgebraicType,
+) -> (String, bool) {
+    let ty = match alg_type {
+        AlgebraicType::Bool { .. } => "bool".to_string(),
+        AlgebraicType::I8 { .. } => "i8".to_string(),
+        AlgebraicType::U8 { .. } => "u8".to_string(),
+        AlgebraicType::I16 { .. } => "i16".to_string(),
+        AlgebraicType::U16 { .. } => "u16".to_string(),
+        AlgebraicType::I32 { .. } => "i32".to_string(),
+        AlgebraicType::U32 { .. } => "u32".to_string(),
+        AlgebraicType::I64 { .. } => "i64".to_string(),
+        AlgebraicType::U64 { .. } => "u64".to_string(),
+        AlgebraicType::I128 { .. } => "i128".to_string(),
+        AlgebraicType::U128 { .. } => "u128".to_string(),
+        AlgebraicType::I256 { .. } => "i256".to_string(),
+        AlgebraicType::U256 { .. } => "u256".to_string(),
+        AlgebraicType::F32 { .. } => "f32".to_string(),
+        AlgebraicType::F64 { .. } => "f64".to_string(),
+        AlgebraicType::String { .. } => "String".to_string(),
+        AlgebraicType::Array { Array } => {
+            format!("{}[]", simple_type(schema, type_names, Array).0)
+        }
+        AlgebraicType::Map { .. } => "Map".to_string(),
+        AlgebraicType::Ref { Ref } => {
+            let idx = *Ref as usize;
+            let special = match schema.typespace.types.get(idx) {
+                Some(TypeDef::Product { Product }) => detect_spacetimedb_type(Product),
+                Some(TypeDef::Sum { Sum }) => detect_spacetimedb_sum_type(Sum),
+                _ => None,
+            };
+            special.unwrap_or_else(|| {
+                type_names
+                    .get(&idx)
+                    .map_or_else(|| format!("Type_{idx}"), |name| sanitize(name))
+            })
+        }
+        AlgebraicType::Product { Product } => {
+            detect_spacetimedb_type(Product).unwrap_or_else(|| "struct".to_string())
+        }
+        AlgebraicType::Sum { Sum } => {
+            if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                special
+            } else if is_option_type(Sum) {
+                let inner = get_option_inner_type(Sum).map_or_else(
+                    || "unit".to_string(),
+                    |t| simple_type(schema, type_names, t).0,
+                );
+                return (inner, true);
+            } else {
+                "enum".to_string()
+            }
+        }
+    };
+    (ty, false)
+}
+
+fn field_name(name: Option<&str>, index: usize) -> String {
+    match name {
+        Some(name) => sanitize(name),
+        None => format!("_{index}"),
+    }
+}
+
+/// Mermaid names: letters, digits, `_` and `-`, not starting with a digit
+fn sanitize(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.is_empty() || sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    #[test]
+    fn tables_list_their_columns_with_markers() {
+        let mermaid = generate_mermaid(&demo_schema());
+        assert!(mermaid.starts_with("erDiagram\n"), "{mermaid}");
+        assert!(
+            mermaid.contains(
+                "    guild_member {\n        u64 guild_id PK\n        u64 player_id PK\n"
+            ),
+            "{mermaid}"
+        );
+        assert!(
+            mermaid.contains("        String nickname \"optional\"\n"),
+            "{mermaid}"
+        );
+        assert!(
+            mermaid.contains("        ItemStack[] inventory\n"),
+            "{mermaid}"
+        );
+    }
+
+    #[test]
+    fn references_become_relationships_with_cardinality() {
+        let mermaid = generate_mermaid(&demo_schema());
+        for line in [
+            "    player ||--|| enum_PlayerStatus : \"status\"\n",
+            "    player ||--o{ struct_ItemStack : \"inventory\"\n",
+            "    struct_ItemStack ||--o| struct_ItemStack : \"next\"\n",
+        ] {
+            assert!(mermaid.contains(line), "no {line:?} in {mermaid}");
+        }
+        assert!(
+            mermaid.contains("    enum_PlayerStatus {\n        unit Online\n"),
+            "{mermaid}"
+        );
+    }
+
+    #[test]
+    fn recursive_types_relate_to_themselves() {
+        let mermaid = generate_mermaid(&recursive_schema());
+        assert!(
+            mermaid.ends_with("    tree ||--o{ tree : \"children\"\n"),
+            "{mermaid}"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::spacetime_commands::shell_quote;
+
+/// Where a generated document came from, written into it as comments so
+/// committed files can be traced back to their database
+pub struct SourceMetadata {
+    pub server: String,
+    pub db: String,
+    pub schema_version: String,
+    /// `--query` parameters sent with the schema request, URL-encoded
+    pub query: Option<String>,
+    pub fingerprint: String,
+    /// `None` with --reproducible, so regenerated files don't churn
+    pub fetched_at: Option<String>,
+    pub invocation: String,
+}
+
+impl SourceMetadata {
+    pub fn new(
+        server: &str,
+        db: &str,
+        schema_version: Option<&str>,
+        query: &[(String, String)],
+        schema_json: &Value,
+        reproducible: bool,
+    ) -> Self {
+        Self {
+            server: server.to_string(),
+            db: db.to_string(),
+            schema_version: schema_version.unwrap_or("9").to_string(),
+            query: (!query.is_empty()).then(|| crate::spacetime_client::encode_query(query)),
+            fingerprint: crate::history::fingerprint(schema_json),
+            fetched_at: (!reproducible)
+                .then(|| Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            invocation: redacted_invocation(std::env::args().skip(1)),
+        }
+    }
+
+    /// The metadata as line comments, one field per line
+    pub fn comment_block(&self, comment: &str) -> String {
+        self.lines()
+            .iter()
+            .map(|line| format!("{comment} {line}\n"))
+            .collect()
+    }
+
+    /// One `field: value` line per field, headed by the tool version
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Generated by spacetime-schema-tool {}",
+                env!("CARGO_PKG_VERSION")
+            ),
+            format!("server: {}", self.server),
+            format!("database: {}", self.db),
+            format!("schema version: {}", self.schema_version),
+        ];
+        if let Some(query) = &self.query {
+            lines.push(format!("query: {query}"));
+        }
+        lines.push(format!("schema fingerprint: {}", self.fingerprint));
+        if let Some(fetched_at) = &self.fetched_at {
+            lines.push(format!("fetched at: {fetched_at}"));
+        }
+        lines.push(format!(
+            "command: spacetime-schema-tool {}",
+            self.invocation
+        ));
+
+        lines
+    }
+}
+
+/// Command-line arguments with the values of credential-like flags
+/// (`--token`, `--password`, ...) replaced
+fn redacted_invocation(args: impl Iterator<Item = String>) -> String {
+    let is_secret = |flag: &str| {
+        let flag = flag.to_ascii_lowercase();
+        ["token", "password", "secret"]
+            .iter()
+            .any(|word| flag.contains(word))
+    };
+
+    let mut out = Vec::new();
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            out.push("<redacted>".to_string());
+            redact_next = false;
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((flag, _)) if flag.starts_with("--") && is_secret(flag) => {
+                out.push(format!("{flag}=<redacted>"));
+            }
+            _ => {
+                redact_next = arg.starts_with("--") && is_secret(&arg);
+                out.push(shell_quote(&arg));
+            }
+        }
+    }
+    out.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(query: &[(String, String)]) -> SourceMetadata {
+        SourceMetadata::new(
+            "http://localhost:3000",
+            "game",
+            None,
+            query,
+            &Value::Null,
+            true,
+        )
+    }
+
+    #[test]
+    fn reproducible_metadata_has_no_timestamp() {
+        let mut metadata = metadata(&[]);
+        metadata.invocation = "--db game --format kdl".to_string();
+        assert_eq!(metadata.fetched_at, None);
+        assert_eq!(
+            metadata.comment_block("//"),
+            format!(
+                "// Generated by spacetime-schema-tool {}\n\
+                 // server: http://localhost:3000\n\
+                 // database: game\n\
+                 // schema version: 9\n\
+                 // schema fingerprint: {}\n\
+                 // command: spacetime-schema-tool --db game --format kdl\n",
+                env!("CARGO_PKG_VERSION"),
+                crate::history::fingerprint(&Value::Null)
+            )
+        );
+    }
+
+    #[test]
+    fn fetched_at_is_a_utc_timestamp() {
+        let metadata = SourceMetadata::new("local", "game", Some("8"), &[], &Value::Null, false);
+        let fetched_at = metadata.fetched_at.as_deref().unwrap();
+        assert!(
+            chrono::NaiveDateTime::parse_from_str(fetched_at, "%Y-%m-%dT%H:%M:%SZ").is_ok(),
+            "{fetched_at}"
+        );
+        let block = metadata.comment_block("--");
+        assert!(block.contains("-- schema version: 8\n"), "{block}");
+        assert!(
+            block.contains(&format!("-- fetched at: {fetched_at}\n")),
+            "{block}"
+        );
+    }
+
+    #[test]
+    fn query_is_recorded_encoded() {
+        let query = vec![
+            ("expand".to_string(), "all".to_string()),
+            ("filter".to_string(), "a b&c".to_string()),
+        ];
+        let block = metadata(&query).comment_block("//");
+        assert!(
+            block.contains("// query: expand=all&filter=a+b%26c\n"),
+            "{block}"
+        );
+    }
+
+    #[test]
+    fn no_query_line_without_query() {
+        let block = metadata(&[]).comment_block("//");
+        assert!(!block.contains("query:"), "{block}");
+    }
+
+    #[test]
+    fn credentials_are_redacted_from_the_invocation() {
+        let invocation =
+            |args: &[&str]| redacted_invocation(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(
+            invocation(&["--db", "game", "--token", "abc", "--format", "kdl"]),
+            "--db game --token <redacted> --format kdl"
+        );
+        assert_eq!(
+            invocation(&["--token=abc", "--db-password", "hunter2", "--API-SECRET=x"]),
+            "--token=<redacted> --db-password <redacted> --API-SECRET=<redacted>"
+        );
+        // Values that merely look like secrets are kept, and quoted for the shell
+        assert_eq!(
+            invocation(&["--db", "token", "--query", "a=b c", "--table", "it's"]),
+            "--db token --query 'a=b c' --table 'it'\\''s'"
+        );
+    }
+}
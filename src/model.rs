@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::codegen::{resolve, Shape};
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, is_option_type,
+    primary_key_names,
+};
+
+/// The schema normalized for scripts: no typespace indexes to chase, every
+/// field type a self-describing tree, primary keys as field names. Serialized
+/// as-is by `--format model`.
+#[derive(Debug, Serialize)]
+pub struct SchemaModel {
+    pub tables: Vec<TableModel>,
+    /// Named structs, table row types included
+    pub structs: Vec<StructModel>,
+    /// Named enums; options and SpacetimeDB's own types are written in place instead
+    pub enums: Vec<EnumModel>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableModel {
+    pub name: String,
+    /// Name of the row struct, if the schema names it
+    pub row_type: Option<String>,
+    pub primary_key: Vec<String>,
+    pub fields: Vec<FieldModel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StructModel {
+    pub name: String,
+    pub fields: Vec<FieldModel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnumModel {
+    pub name: String,
+    pub variants: Vec<VariantModel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldModel {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: TypeModel,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VariantModel {
+    pub name: Option<String>,
+    /// `None` for a variant without data
+    #[serde(rename = "type")]
+    pub ty: Option<TypeModel>,
+}
+
+/// A field type, tagged by `kind`. Options and aliases are already
+/// followed, so `named` only ever points at a struct or enum in the model
+/// or at one of SpacetimeDB's own types (tagged with `wellKnown`).
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TypeModel {
+    /// `bool`, `u64`, `String`, ... as the pretty view writes them
+    Primitive {
+        name: &'static str,
+    },
+    Named {
+        name: String,
+        #[serde(rename = "wellKnown", skip_serializing_if = "Option::is_none")]
+        well_known: Option<&'static str>,
+    },
+    Option {
+        inner: Box<TypeModel>,
+    },
+    Array {
+        inner: Box<TypeModel>,
+    },
+    Map {
+        key: Box<TypeModel>,
+        value: Box<TypeModel>,
+    },
+    /// An unnamed struct or tuple written in place
+    Product {
+        fields: Vec<FieldModel>,
+    },
+    /// An unnamed enum written in place
+    Sum {
+        variants: Vec<VariantModel>,
+    },
+    /// A dangling ref or an alias cycle
+    Any,
+}
+
+impl SchemaModel {
+    pub fn new(schema: &SatsSchema) -> Self {
+        let builder = Builder {
+            schema,
+            type_names: build_type_names(schema),
+        };
+
+        let tables = schema
+            .tables
+            .iter()
+            .map(|table| TableModel {
+                name: table.name.clone(),
+                row_type: builder.type_names.get(&table.product_type_ref).cloned(),
+                primary_key: primary_key_names(schema, table),
+                fields: match schema.typespace.types.get(table.product_type_ref) {
+                    Some(TypeDef::Product { Product }) => builder.fields(Product),
+                    _ => Vec::new(),
+                },
+            })
+            .collect();
+
+        let mut structs = Vec::new();
+        let mut enums = Vec::new();
+        for named in &schema.types {
+            match schema.typespace.types.get(named.ty) {
+                Some(TypeDef::Product { Product })
+                    if detect_spacetimedb_type(Product).is_none() =>
+                {
+                    structs.push(StructModel {
+                        name: named.name.name.clone(),
+                        fields: builder.fields(Product),
+                    });
+                }
+                Some(TypeDef::Sum { Sum })
+                    if detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum) =>
+                {
+                    enums.push(EnumModel {
+                        name: named.name.name.clone(),
+                        variants: builder.variants(Sum),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            tables,
+            structs,
+            enums,
+        }
+    }
+}
+
+struct Builder<'a> {
+    schema: &'a SatsSchema,
+    type_names: HashMap<usize, String>,
+}
+
+impl Builder<'_> {
+    fn fields(&self, product: &ProductType) -> Vec<FieldModel> {
+        product
+            .elements
+            .iter()
+            .map(|e| FieldModel {
+                name: e.name.as_option().map(str::to_string),
+                ty: self.type_model(&e.algebraic_type),
+            })
+            .collect()
+    }
+
+    fn variants(&self, sum: &SumType) -> Vec<VariantModel> {
+        sum.variants
+            .iter()
+            .map(|v| VariantModel {
+                name: v.name.as_option().map(str::to_string),
+                ty: match &v.algebraic_type {
+                    AlgebraicType::Product { Product } if Product.elements.is_empty() => None,
+                    payload => Some(self.type_model(payload)),
+                },
+            })
+            .collect()
+    }
+
+    fn type_model(&self, alg_type: &AlgebraicType) -> TypeModel {
+        self.shape_model(resolve(self.schema, alg_type))
+    }
+
+    fn shape_model(&self, shape: Shape) -> TypeModel {
+        match shape {
+            Shape::Primitive(primitive) => TypeModel::Primitive {
+                name: primitive.name(),
+            },
+            Shape::Special(special) => TypeModel::Named {
+                name: special.name().to_string(),
+                well_known: Some(special.name()),
+            },
+            Shape::Option(inner) => TypeModel::Option {
+                inner: Box::new(self.shape_model(*inner)),
+            },
+            Shape::Array(element) => TypeModel::Array {
+                inner: Box::new(self.shape_model(*element)),
+            },
+            Shape::Map(key, value) => TypeModel::Map {
+                key: Box::new(self.shape_model(*key)),
+                value: Box::new(self.shape_model(*value)),
+            },
+            Shape::Named(idx) => TypeModel::Named {
+                name: self
+                    .type_names
+                    .get(&idx)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Type_{idx}")),
+                well_known: None,
+            },
+            Shape::Product(product) => TypeModel::Product {
+                fields: self.fields(product),
+            },
+            Shape::Sum(sum) => TypeModel::Sum {
+                variants: self.variants(sum),
+            },
+            Shape::Any => TypeModel::Any,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema, schema};
+    use serde_json::json;
+
+    fn serialized(schema: &SatsSchema) -> serde_json::Value {
+        serde_json::to_value(SchemaModel::new(schema)).unwrap()
+    }
+
+    #[test]
+    fn tables_list_keys_by_name_and_tag_every_type() {
+        let model = serialized(&demo_schema());
+        let player = &model["tables"][0];
+        assert_eq!(player["name"], "player");
+        assert_eq!(player["rowType"], "Player");
+        assert_eq!(player["primaryKey"], json!(["id"]));
+        assert_eq!(
+            player["fields"][1],
+            json!({"name": "identity", "type": {"kind": "named", "name": "Identity", "wellKnown": "Identity"}})
+        );
+        assert_eq!(
+            player["fields"][5],
+            json!({"name": "nickname", "type": {"kind": "option", "inner": {"kind": "primitive", "name": "String"}}})
+        );
+        assert_eq!(
+            player["fields"][7]["type"],
+            json!({"kind": "array", "inner": {"kind": "named", "name": "ItemStack"}})
+        );
+    }
+
+    #[test]
+    fn enums_leave_out_options_and_spacetimedb_types() {
+        let model = serialized(&demo_schema());
+        let names: Vec<_> = model["enums"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["PlayerStatus", "Rarity", "GuildRole"]);
+        assert_eq!(
+            model["enums"][0]["variants"][0],
+            json!({"name": "Online", "type": null})
+        );
+    }
+
+    #[test]
+    fn recursive_and_map_types_are_trees() {
+        let model = serialized(&recursive_schema());
+        assert_eq!(
+            model["structs"][0]["fields"][1]["type"],
+            json!({"kind": "array", "inner": {"kind": "named", "name": "Node"}})
+        );
+
+        let model = serialized(&schema(json!({
+            "typespace": {"types": [{"Product": {"elements": [
+                {"name": {"some": "scores"}, "algebraic_type": {"Map": {"key_ty": {"String": []}, "ty": {"U32": []}}}},
+            ]}}]},
+            "tables": [{"name": "board", "product_type_ref": 0, "primary_key": []}],
+            "types": [],
+        })));
+        assert_eq!(
+            model["tables"][0]["fields"][0]["type"],
+            json!({
+                "kind": "map",
+                "key": {"kind": "primitive", "name": "String"},
+                "value": {"kind": "primitive", "name": "u32"},
+            })
+        );
+        assert_eq!(model["tables"][0]["rowType"], serde_json::Value::Null);
+    }
+}
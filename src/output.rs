@@ -0,0 +1,151 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{bail, Context, Result};
+
+/// The `--output` file being written, when one was given; closed by `finish`
+static OUTPUT_FILE: OnceLock<Mutex<OutputFile>> = OnceLock::new();
+
+/// Where the `--output` file goes once the results are complete
+static TARGET: OnceLock<Target> = OnceLock::new();
+
+struct Target {
+    path: PathBuf,
+    /// The results as they are written, next to `path`
+    partial: PathBuf,
+    force: bool,
+}
+
+/// Send results to `path` instead of stdout. Call before anything is written.
+///
+/// The results go to a `.partial` file next to `path` and replace it in
+/// `finish`. An existing file with the same content is left untouched, and
+/// one with different content is only replaced with `force`.
+pub fn redirect_to(path: &Path, force: bool) -> Result<()> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let file = File::create(&partial)
+        .with_context(|| format!("Could not create output file {}", path.display()))?;
+    let _ = OUTPUT_FILE.set(Mutex::new(Some(BufWriter::new(file))));
+    let _ = TARGET.set(Target {
+        path: path.to_path_buf(),
+        partial,
+        force,
+    });
+    Ok(())
+}
+
+pub fn is_redirected() -> bool {
+    OUTPUT_FILE.get().is_some()
+}
+
+/// Flush the results and move the `--output` file into place; only a
+/// flush when writing to stdout
+pub fn finish() -> Result<()> {
+    Output.flush().context("Could not write output file")?;
+    let Some(target) = TARGET.get() else {
+        return Ok(());
+    };
+    if let Some(file) = OUTPUT_FILE.get() {
+        // Closed before it is compared and renamed
+        lock(file).take();
+    }
+    match std::fs::read(&target.path) {
+        Ok(existing) => {
+            let written = std::fs::read(&target.partial).context("Could not write output file")?;
+            if existing == written {
+                let _ = std::fs::remove_file(&target.partial);
+                status(format!("{} is unchanged", target.path.display()));
+                return Ok(());
+            }
+            if !target.force {
+                let _ = std::fs::remove_file(&target.partial);
+                bail!(
+                    "{} already exists with different content; pass --force to overwrite it",
+                    target.path.display()
+                );
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => {
+            let _ = std::fs::remove_file(&target.partial);
+            return Err(e).with_context(|| format!("Could not read {}", target.path.display()));
+        }
+    }
+    std::fs::rename(&target.partial, &target.path)
+        .with_context(|| format!("Could not write output file {}", target.path.display()))
+}
+
+/// Drop the unfinished `--output` file of a failed run, leaving any
+/// existing file at the path as it was
+pub fn discard() {
+    if let (Some(file), Some(target)) = (OUTPUT_FILE.get(), TARGET.get()) {
+        lock(file).take();
+        let _ = std::fs::remove_file(&target.partial);
+    }
+}
+
+/// A buffered writer to the results destination, for renderers that
+/// stream a document piece by piece. Flush it when done.
+pub fn sink() -> BufWriter<Output> {
+    BufWriter::new(Output)
+}
+
+/// Progress messages ("Fetching...") always go to stderr, so stdout holds
+/// nothing but the results and can be piped or redirected as-is
+pub fn status(line: impl Display) {
+    eprintln!("{line}");
+}
+
+/// Where results go: the `--output` file, or stdout
+pub struct Output;
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match OUTPUT_FILE.get() {
+            Some(file) => match lock(file).as_mut() {
+                Some(file) => file.write(buf),
+                None => Err(io::Error::other("the output file was already finished")),
+            },
+            None => io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match OUTPUT_FILE.get() {
+            Some(file) => match lock(file).as_mut() {
+                Some(file) => file.flush(),
+                None => Ok(()),
+            },
+            None => io::stdout().flush(),
+        }
+    }
+}
+
+type OutputFile = Option<BufWriter<File>>;
+
+fn lock(file: &Mutex<OutputFile>) -> std::sync::MutexGuard<'_, OutputFile> {
+    file.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// `print!` to the results destination
+macro_rules! out {
+    ($($arg:tt)*) => {{
+        use std::io::Write as _;
+        write!($crate::output::Output, $($arg)*).expect("failed writing output")
+    }};
+}
+
+/// `println!` to the results destination
+macro_rules! outln {
+    ($($arg:tt)*) => {{
+        use std::io::Write as _;
+        writeln!($crate::output::Output, $($arg)*).expect("failed writing output")
+    }};
+}
+
+pub(crate) use {out, outln};
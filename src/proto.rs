@@ -0,0 +1,511 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::identifiers::{pascal_case, proto_identifier};
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{
+    detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type, is_option_type,
+};
+
+/// SpacetimeDB's `ScheduleAt`, declared once when a table uses it
+const SCHEDULE_AT: &str = "message ScheduleAt {
+  oneof value {
+    google.protobuf.Duration interval = 1;
+    google.protobuf.Timestamp time = 2;
+  }
+}
+";
+
+/// Render a proto3 file for the schema: a `message` per struct, an `enum`
+/// per enum whose variants carry no data, and a message wrapping a `oneof`
+/// for the rest. Declarations are sorted by name.
+///
+/// Numbers come from the SATS element order (field `n` is number `n + 1`,
+/// variant `n` is enum value or `oneof` case `n` / `n + 1`), so
+/// regenerating after a schema change only renumbers what moved.
+/// Integers wider than 64 bits and identities become `bytes`, with a
+/// comment giving the encoding.
+pub fn generate_proto(schema: &SatsSchema, package: Option<&str>) -> String {
+    let names = type_names(schema);
+    let mut generator = ProtoGenerator {
+        schema,
+        names: &names,
+        imports: BTreeSet::new(),
+        schedule_at: false,
+        hoisted: Vec::new(),
+        inlining: HashSet::new(),
+    };
+
+    let mut entries: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| is_declared(schema, *idx))
+        .collect();
+    entries.sort_by(|a, b| names[a].cmp(&names[b]));
+
+    let mut declarations = Vec::new();
+    for idx in entries {
+        let name = &names[&idx];
+        let declaration = match &schema.typespace.types[idx] {
+            TypeDef::Product { Product } => generator.message(name, Product),
+            TypeDef::Sum { Sum } => generator.sum(name, Sum),
+            _ => continue,
+        };
+        declarations.push(declaration);
+        declarations.append(&mut generator.hoisted);
+    }
+    if generator.schedule_at {
+        generator.imports.insert("google/protobuf/duration.proto");
+        generator.imports.insert("google/protobuf/timestamp.proto");
+        declarations.push(SCHEDULE_AT.to_string());
+    }
+
+    let mut out = String::from("syntax = \"proto3\";\n");
+    if let Some(package) = package {
+        let _ = write!(out, "\npackage {package};\n");
+    }
+    if !generator.imports.is_empty() {
+        out.push('\n');
+        for import in &generator.imports {
+            let _ = writeln!(out, "import \"{import}\";");
+        }
+    }
+    for declaration in declarations {
+        out.push('\n');
+        out.push_str(&declaration);
+    }
+    out
+}
+
+/// Structs and enums get a declaration. Builtins, options and aliases of
+/// other entries are written out in place wherever they are used.
+fn is_declared(schema: &SatsSchema, idx: usize) -> bool {
+    match schema.typespace.types.get(idx) {
+        Some(TypeDef::Product { Product }) => detect_spacetimedb_type(Product).is_none(),
+        Some(TypeDef::Sum { Sum }) => {
+            detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum)
+        }
+        _ => false,
+    }
+}
+
+/// Message name for each typespace entry, unique after sanitizing;
+/// unnamed entries become `Type<index>`. `ScheduleAt` is kept free.
+fn type_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::from(["ScheduleAt".to_string()]);
+    for named in &schema.types {
+        names
+            .entry(named.ty)
+            .or_insert_with(|| unique(&mut taken, proto_identifier(&named.name.name)));
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(&mut taken, format!("Type{idx}")));
+    }
+    names
+}
+
+/// `base`, or `base2`, `base3`, ... if it is already taken
+fn unique(taken: &mut HashSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut n = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{base}{n}");
+        n += 1;
+    }
+    name
+}
+
+/// `GuildRole` -> `GUILD_ROLE`, the conventional enum value prefix
+fn screaming_snake(name: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_uppercase()
+            && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+        {
+            out.push('_');
+        }
+        out.push(if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        });
+        previous = Some(c);
+    }
+    out
+}
+
+/// A field's type and how it may be labelled
+struct Field {
+    ty: String,
+    /// `optional ` / `repeated `, or empty
+    label: &'static str,
+    /// Trailing comment explaining a lossy mapping
+    note: Option<String>,
+}
+
+impl Field {
+    fn plain(ty: &str) -> Self {
+        Self {
+            ty: ty.to_string(),
+            label: "",
+            note: None,
+        }
+    }
+
+    fn bytes(note: String) -> Self {
+        Self {
+            note: Some(note),
+            ..Self::plain("bytes")
+        }
+    }
+
+    /// `repeated T name = 1; // note`
+    fn write_line(&self, out: &mut String, indent: &str, name: &str, number: usize) {
+        let _ = write!(out, "{indent}{}{} {name} = {number};", self.label, self.ty);
+        match &self.note {
+            Some(note) => {
+                let _ = writeln!(out, " // {note}");
+            }
+            None => out.push('\n'),
+        }
+    }
+
+    /// Usable as a `repeated` element, `optional` value or `oneof` case
+    fn is_singular(&self) -> bool {
+        self.label.is_empty() && !self.ty.starts_with("map<")
+    }
+}
+
+struct ProtoGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: &'a HashMap<usize, String>,
+    /// Well-known type files the generated messages use
+    imports: BTreeSet<&'static str>,
+    /// Whether a `ScheduleAt` field was seen
+    schedule_at: bool,
+    /// Wrapper messages and anonymous types found inside the declaration
+    /// being generated, emitted right after it
+    hoisted: Vec<String>,
+    /// Typespace entries being written out in place, to stop alias cycles
+    inlining: HashSet<usize>,
+}
+
+impl ProtoGenerator<'_> {
+    fn message(&mut self, name: &str, product: &ProductType) -> String {
+        let mut out = format!("message {name} {{\n");
+        let mut taken = HashSet::new();
+        for (i, element) in product.elements.iter().enumerate() {
+            let base = element
+                .name
+                .as_option()
+                .map_or_else(|| format!("field_{i}"), proto_identifier);
+            let field_name = unique(&mut taken, base);
+            let field = self.field(&element.algebraic_type, name, &field_name);
+            field.write_line(&mut out, "  ", &field_name, i + 1);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn sum(&mut self, name: &str, sum: &SumType) -> String {
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+
+        if sum.variants.iter().all(|v| is_unit(&v.algebraic_type)) {
+            let prefix = screaming_snake(name);
+            let mut out = format!("enum {name} {{\n");
+            let mut taken = HashSet::new();
+            for (i, variant) in sum.variants.iter().enumerate() {
+                let base = variant.name.as_option().map_or_else(
+                    || format!("{prefix}_VARIANT_{i}"),
+                    |v| format!("{prefix}_{}", screaming_snake(v)),
+                );
+                let _ = writeln!(out, "  {} = {i};", unique(&mut taken, base));
+            }
+            out.push_str("}\n");
+            return out;
+        }
+
+        let mut out = format!("message {name} {{\n  oneof value {{\n");
+        let mut taken = HashSet::new();
+        for (i, variant) in sum.variants.iter().enumerate() {
+            let base = variant
+                .name
+                .as_option()
+                .map_or_else(|| format!("variant_{i}"), proto_identifier);
+            let case = unique(&mut taken, base);
+            let field = if is_unit(&variant.algebraic_type) {
+                self.imports.insert("google/protobuf/empty.proto");
+                Field::plain("google.protobuf.Empty")
+            } else {
+                let field = self.field(&variant.algebraic_type, name, &case);
+                self.singular(field, name, &case)
+            };
+            field.write_line(&mut out, "    ", &case, i + 1);
+        }
+        out.push_str("  }\n}\n");
+        out
+    }
+
+    /// `owner` and `member` name hoisted messages (`PlayerStats` for an
+    /// inline struct in `Player.stats`)
+    fn field(&mut self, alg_type: &AlgebraicType, owner: &str, member: &str) -> Field {
+        match alg_type {
+            AlgebraicType::Bool { .. } => Field::plain("bool"),
+            AlgebraicType::I8 { .. } | AlgebraicType::I16 { .. } | AlgebraicType::I32 { .. } => {
+                Field::plain("int32")
+            }
+            AlgebraicType::U8 { .. } | AlgebraicType::U16 { .. } | AlgebraicType::U32 { .. } => {
+                Field::plain("uint32")
+            }
+            AlgebraicType::I64 { .. } => Field::plain("int64"),
+            AlgebraicType::U64 { .. } => Field::plain("uint64"),
+            AlgebraicType::I128 { .. } => Field::bytes(wide_note("i128", 16)),
+            AlgebraicType::U128 { .. } => Field::bytes(wide_note("u128", 16)),
+            AlgebraicType::I256 { .. } => Field::bytes(wide_note("i256", 32)),
+            AlgebraicType::U256 { .. } => Field::bytes(wide_note("u256", 32)),
+            AlgebraicType::F32 { .. } => Field::plain("float"),
+            AlgebraicType::F64 { .. } => Field::plain("double"),
+            AlgebraicType::String { .. } => Field::plain("string"),
+            AlgebraicType::Array { Array } => self.repeated(Array, owner, member),
+            AlgebraicType::Map { Map } => self.map(&Map.key_ty, &Map.ty, owner, member),
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize, owner, member),
+            AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+                Some(special) => self.special(&special),
+                None => {
+                    let name = format!("{owner}{}", pascal_case(member));
+                    let definition = self.message(&name, Product);
+                    self.hoisted.push(definition);
+                    Field::plain(&name)
+                }
+            },
+            AlgebraicType::Sum { Sum } => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    return self.special(&special);
+                }
+                if let Some(inner) = get_option_inner_type(Sum).filter(|_| is_option_type(Sum)) {
+                    return self.optional(inner, owner, member);
+                }
+                let name = format!("{owner}{}", pascal_case(member));
+                let definition = self.sum(&name, Sum);
+                self.hoisted.push(definition);
+                Field::plain(&name)
+            }
+        }
+    }
+
+    /// `repeated T`; raw bytes are `bytes`
+    fn repeated(&mut self, element: &AlgebraicType, owner: &str, member: &str) -> Field {
+        if matches!(element, AlgebraicType::U8 { .. }) {
+            return Field::plain("bytes");
+        }
+        let element = self.field(element, owner, member);
+        let element = self.singular(element, owner, &format!("{member}_item"));
+        Field {
+            label: "repeated ",
+            ..element
+        }
+    }
+
+    fn optional(&mut self, inner: &AlgebraicType, owner: &str, member: &str) -> Field {
+        let inner = self.field(inner, owner, member);
+        let inner = self.singular(inner, owner, &format!("{member}_value"));
+        Field {
+            label: "optional ",
+            ..inner
+        }
+    }
+
+    /// `map<K, V>` when protobuf allows it (integer, bool or string keys
+    /// and a singular value), otherwise a list of key/value messages
+    fn map(
+        &mut self,
+        key: &AlgebraicType,
+        value: &AlgebraicType,
+        owner: &str,
+        member: &str,
+    ) -> Field {
+        let key_field = self.field(key, owner, &format!("{member}_key"));
+        let value_field = self.field(value, owner, &format!("{member}_value"));
+        let valid_key = key_field.note.is_none()
+            && matches!(
+                key_field.ty.as_str(),
+                "int32" | "uint32" | "int64" | "uint64" | "bool" | "string"
+            );
+        if valid_key && value_field.is_singular() && value_field.note.is_none() {
+            return Field::plain(&format!("map<{}, {}>", key_field.ty, value_field.ty));
+        }
+
+        let name = format!("{owner}{}Entry", pascal_case(member));
+        let mut entry = format!("message {name} {{\n");
+        key_field.write_line(&mut entry, "  ", "key", 1);
+        value_field.write_line(&mut entry, "  ", "value", 2);
+        entry.push_str("}\n");
+        self.hoisted.push(entry);
+        Field {
+            label: "repeated ",
+            ..Field::plain(&name)
+        }
+    }
+
+    /// Protobuf can't nest labels (`repeated optional T`, `repeated map`),
+    /// so such a field is moved into a one-field wrapper message
+    fn singular(&mut self, field: Field, owner: &str, member: &str) -> Field {
+        if field.is_singular() {
+            return field;
+        }
+        let name = format!("{owner}{}", pascal_case(member));
+        let mut wrapper = format!("message {name} {{\n");
+        field.write_line(&mut wrapper, "  ", "value", 1);
+        wrapper.push_str("}\n");
+        self.hoisted.push(wrapper);
+        Field::plain(&name)
+    }
+
+    fn reference(&mut self, idx: usize, owner: &str, member: &str) -> Field {
+        let name = self
+            .names
+            .get(&idx)
+            .cloned()
+            .unwrap_or_else(|| format!("Type{idx}"));
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => match detect_spacetimedb_type(Product) {
+                Some(special) => self.special(&special),
+                None => Field::plain(&name),
+            },
+            Some(TypeDef::Sum { Sum }) => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    return self.special(&special);
+                }
+                match get_option_inner_type(Sum) {
+                    Some(inner) if is_option_type(Sum) => {
+                        self.inline(idx, |generator| generator.optional(inner, owner, member))
+                    }
+                    _ => Field::plain(&name),
+                }
+            }
+            Some(TypeDef::Builtin { Builtin }) => {
+                self.inline(idx, |generator| generator.builtin(Builtin, owner, member))
+            }
+            Some(TypeDef::Ref { Ref }) => self.inline(idx, |generator| {
+                generator.reference(*Ref as usize, owner, member)
+            }),
+            None => Field::bytes(format!("unknown type {idx}")),
+        }
+    }
+
+    /// Write typespace entry `idx` out in place. An entry that leads back
+    /// to itself without passing through a message has no protobuf
+    /// spelling, so the cycle is cut with `bytes`.
+    fn inline(&mut self, idx: usize, render: impl FnOnce(&mut Self) -> Field) -> Field {
+        if !self.inlining.insert(idx) {
+            return Field::bytes(format!("recursive alias {idx}"));
+        }
+        let field = render(self);
+        self.inlining.remove(&idx);
+        field
+    }
+
+    fn builtin(&mut self, builtin: &BuiltinType, owner: &str, member: &str) -> Field {
+        match builtin {
+            BuiltinType::Bool { .. } => Field::plain("bool"),
+            BuiltinType::I8 { .. } | BuiltinType::I16 { .. } | BuiltinType::I32 { .. } => {
+                Field::plain("int32")
+            }
+            BuiltinType::U8 { .. } | BuiltinType::U16 { .. } | BuiltinType::U32 { .. } => {
+                Field::plain("uint32")
+            }
+            BuiltinType::I64 { .. } => Field::plain("int64"),
+            BuiltinType::U64 { .. } => Field::plain("uint64"),
+            BuiltinType::I128 { .. } => Field::bytes(wide_note("i128", 16)),
+            BuiltinType::U128 { .. } => Field::bytes(wide_note("u128", 16)),
+            BuiltinType::I256 { .. } => Field::bytes(wide_note("i256", 32)),
+            BuiltinType::U256 { .. } => Field::bytes(wide_note("u256", 32)),
+            BuiltinType::F32 { .. } => Field::plain("float"),
+            BuiltinType::F64 { .. } => Field::plain("double"),
+            BuiltinType::String { .. } => Field::plain("string"),
+            BuiltinType::Array { Array } => self.repeated(Array, owner, member),
+            BuiltinType::Map { Map } => self.map(&Map.key_ty, &Map.ty, owner, member),
+        }
+    }
+
+    fn special(&mut self, detected: &str) -> Field {
+        match detected {
+            "Identity" => Field::bytes("Identity, 32 bytes little-endian".to_string()),
+            "Timestamp" => {
+                self.imports.insert("google/protobuf/timestamp.proto");
+                Field::plain("google.protobuf.Timestamp")
+            }
+            "Duration" => {
+                self.imports.insert("google/protobuf/duration.proto");
+                Field::plain("google.protobuf.Duration")
+            }
+            "ScheduledAt" => {
+                self.schedule_at = true;
+                Field::plain("ScheduleAt")
+            }
+            other => Field::bytes(other.to_string()),
+        }
+    }
+}
+
+fn wide_note(ty: &str, bytes: usize) -> String {
+    format!("{ty} as {bytes} little-endian bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    #[test]
+    fn structs_become_numbered_messages() {
+        let proto = generate_proto(&demo_schema(), None);
+        assert!(proto.starts_with("syntax = \"proto3\";\n"), "{proto}");
+        assert!(
+            proto.contains(concat!(
+                "message ItemStack {\n",
+                "  uint32 item_id = 1;\n",
+                "  uint32 count = 2;\n",
+                "  optional ItemStack next = 3;\n",
+                "}\n",
+            )),
+            "{proto}"
+        );
+        assert!(
+            proto.contains("  repeated ItemStack inventory = 8;\n"),
+            "{proto}"
+        );
+        assert!(
+            proto.contains("  google.protobuf.Timestamp created_at = 7;\n"),
+            "{proto}"
+        );
+    }
+
+    #[test]
+    fn unit_enums_are_prefixed_and_others_become_oneofs() {
+        let proto = generate_proto(&demo_schema(), None);
+        assert!(
+            proto.contains("enum GuildRole {\n  GUILD_ROLE_MEMBER = 0;\n"),
+            "{proto}"
+        );
+        assert!(
+            proto.contains("    google.protobuf.Empty Online = 1;\n"),
+            "{proto}"
+        );
+        assert!(proto.contains("    string Banned = 3;\n"), "{proto}");
+    }
+
+    #[test]
+    fn imports_only_what_is_used() {
+        let proto = generate_proto(&recursive_schema(), None);
+        assert!(!proto.contains("import"), "{proto}");
+        assert!(proto.contains("  repeated Node children = 2;\n"), "{proto}");
+
+        let proto = generate_proto(&recursive_schema(), Some("game.v1"));
+        assert!(proto.contains("package game.v1;\n"), "{proto}");
+    }
+}
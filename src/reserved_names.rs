@@ -0,0 +1,78 @@
+/// Words that must be quoted when used as identifiers in SpacetimeDB SQL
+pub const SQL_KEYWORDS: &[&str] = &[
+    "all", "and", "as", "asc", "by", "delete", "desc", "false", "from", "group", "insert", "into",
+    "join", "limit", "not", "null", "offset", "on", "or", "order", "select", "set", "table",
+    "true", "update", "values", "where",
+];
+
+/// Names with a built-in meaning in SpacetimeDB SQL (`:sender` in row-level
+/// security filters, the `timestamp` type) that read ambiguously as columns
+pub const SPECIAL_NAMES: &[&str] = &["sender", "timestamp"];
+
+/// Table name prefixes reserved for SpacetimeDB system tables
+pub const RESERVED_TABLE_PREFIXES: &[&str] = &["st_"];
+
+/// Case-insensitive, like SpacetimeDB's SQL parser
+pub fn is_sql_keyword(name: &str, extra_keywords: &[String]) -> bool {
+    let lower = name.to_ascii_lowercase();
+    SQL_KEYWORDS.contains(&lower.as_str())
+        || extra_keywords.iter().any(|k| k.eq_ignore_ascii_case(name))
+}
+
+pub fn is_special_name(name: &str) -> bool {
+    SPECIAL_NAMES.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// The reserved prefix a table name starts with, if any
+pub fn reserved_table_prefix(name: &str) -> Option<&'static str> {
+    let lower = name.to_ascii_lowercase();
+    RESERVED_TABLE_PREFIXES
+        .iter()
+        .copied()
+        .find(|prefix| lower.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_match_in_any_case() {
+        for name in ["select", "SELECT", "Select", "sElEcT"] {
+            assert!(is_sql_keyword(name, &[]), "{name}");
+        }
+        assert!(!is_sql_keyword("selection", &[]));
+        assert!(!is_sql_keyword("player", &[]));
+    }
+
+    #[test]
+    fn keyword_list_is_lowercase_and_sorted() {
+        assert!(SQL_KEYWORDS.iter().all(|k| *k == k.to_ascii_lowercase()));
+        assert!(SQL_KEYWORDS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn extra_keywords_match_in_any_case() {
+        let extra = vec!["Level".to_string()];
+        assert!(is_sql_keyword("level", &extra));
+        assert!(is_sql_keyword("LEVEL", &extra));
+        assert!(!is_sql_keyword("levels", &extra));
+    }
+
+    #[test]
+    fn special_names_match_in_any_case() {
+        assert!(is_special_name("sender"));
+        assert!(is_special_name("Timestamp"));
+        assert!(!is_special_name("sender_id"));
+    }
+
+    #[test]
+    fn reserved_prefixes_match_at_the_start_in_any_case() {
+        assert_eq!(reserved_table_prefix("st_table"), Some("st_"));
+        assert_eq!(reserved_table_prefix("ST_Client"), Some("st_"));
+        assert_eq!(reserved_table_prefix("st_"), Some("st_"));
+        assert_eq!(reserved_table_prefix("first_table"), None);
+        assert_eq!(reserved_table_prefix("st"), None);
+        assert_eq!(reserved_table_prefix("stats"), None);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::schema::sats_types::{AlgebraicType, ProductType, SatsSchema, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+    is_option_type,
+};
+
+/// The schema as a plain object tree for `jq` and friends: tables with
+/// their fields, then the named structs and enums, every type written as
+/// `format_type` renders it (`Option<Vec<ItemStack>>`) instead of as a
+/// typespace reference.
+///
+/// Unnamed fields and variants have a `null` name; unit variants have a
+/// `null` type.
+pub fn to_resolved_json(schema: &SatsSchema) -> Value {
+    let type_names = build_type_names(schema);
+
+    let tables: Vec<_> = schema
+        .tables
+        .iter()
+        .map(|table| {
+            let product = match schema.typespace.types.get(table.product_type_ref) {
+                Some(TypeDef::Product { Product }) => Some(Product),
+                _ => None,
+            };
+            let primary_key: Vec<_> = table
+                .primary_key
+                .iter()
+                .map(|&i| {
+                    product
+                        .and_then(|p| p.elements.get(i))
+                        .and_then(|e| e.name.as_option())
+                })
+                .collect();
+            json!({
+                "name": table.name,
+                "type": type_names.get(&table.product_type_ref),
+                "primary_key": primary_key,
+                "fields": product.map(|p| fields_json(schema, p, &type_names)).unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    let mut structs = Vec::new();
+    let mut enums = Vec::new();
+    for named in &schema.types {
+        match schema.typespace.types.get(named.ty) {
+            Some(TypeDef::Product { Product }) if detect_spacetimedb_type(Product).is_none() => {
+                structs.push(json!({
+                    "name": named.name.name,
+                    "fields": fields_json(schema, Product, &type_names),
+                }));
+            }
+            Some(TypeDef::Sum { Sum })
+                if detect_spacetimedb_sum_type(Sum).is_none() && !is_option_type(Sum) =>
+            {
+                let variants: Vec<_> = Sum
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        let ty = match &v.algebraic_type {
+                            AlgebraicType::Product { Product } if Product.elements.is_empty() => {
+                                None
+                            }
+                            payload => Some(format_type(schema, payload, &type_names)),
+                        };
+                        json!({ "name": v.name.as_option(), "type": ty })
+                    })
+                    .collect();
+                enums.push(json!({
+                    "name": named.name.name,
+                    "variants": variants,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    json!({
+        "tables": tables,
+        "structs": structs,
+        "enums": enums,
+    })
+}
+
+fn fields_json(
+    schema: &SatsSchema,
+    product: &ProductType,
+    type_names: &HashMap<usize, String>,
+) -> Vec<Value> {
+    product
+        .elements
+        .iter()
+        .map(|e| {
+            json!({
+                "name": e.name.as_option(),
+                "type": format_type(schema, &e.algebraic_type, type_names),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    #[test]
+    fn tables_carry_their_fields_and_primary_key() {
+        let resolved = to_resolved_json(&demo_schema());
+        let player = &resolved["tables"][0];
+        assert_eq!(player["name"], "player");
+        assert_eq!(player["type"], "Player");
+        assert_eq!(player["primary_key"], json!(["id"]));
+        assert_eq!(
+            player["fields"][5],
+            json!({"name": "nickname", "type": "Option<String>"})
+        );
+        assert_eq!(
+            player["fields"][7],
+            json!({"name": "inventory", "type": "Vec<ItemStack>"})
+        );
+    }
+
+    #[test]
+    fn enums_list_their_variants() {
+        let resolved = to_resolved_json(&demo_schema());
+        let status = resolved["enums"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["name"] == "PlayerStatus")
+            .unwrap();
+        assert_eq!(
+            status["variants"],
+            json!([
+                {"name": "Online", "type": null},
+                {"name": "Away", "type": null},
+                {"name": "Banned", "type": "String"},
+            ])
+        );
+    }
+
+    #[test]
+    fn recursive_types_are_written_by_name() {
+        let resolved = to_resolved_json(&recursive_schema());
+        assert_eq!(resolved["structs"][0]["name"], "Node");
+        assert_eq!(
+            resolved["tables"][0]["fields"][1],
+            json!({"name": "children", "type": "Vec<Node>"})
+        );
+        assert_eq!(resolved["tables"][0]["primary_key"], json!([]));
+    }
+}
@@ -0,0 +1,724 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::identifiers::{pascal_case, rust_identifier};
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{
+    detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type, is_option_type,
+};
+
+/// Derives on every generated struct and enum
+const DERIVE: &str = "#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\n";
+
+/// Render Rust definitions for the schema's types: a struct per product,
+/// an enum per sum and an alias per named builtin. Table row types come
+/// first, each with a doc comment naming its table.
+///
+/// Structs and enums derive serde's `Serialize` and `Deserialize`, with a
+/// `#[serde(rename)]` wherever a name had to be changed to be a valid
+/// identifier. SpacetimeDB's own types (`Identity`, `Timestamp`, ...) are
+/// imported from `spacetimedb_sdk` rather than redefined, and
+/// self-referencing fields are boxed so the types have a finite size.
+///
+/// Types whose name has a scope go into nested `pub mod` blocks following
+/// it (`["game", "world"]` is `game::world`), and references between
+/// modules are written as `super::` paths. With `flat`, scopes are
+/// ignored and everything is defined at the top level.
+pub fn generate_rust_code(schema: &SatsSchema, flat: bool) -> String {
+    let modules = if flat {
+        HashMap::new()
+    } else {
+        type_modules(schema)
+    };
+    let mut generator = RustGenerator {
+        schema,
+        names: type_names(schema, &modules),
+        modules,
+        module: Vec::new(),
+        sdk_types: BTreeMap::new(),
+        hoisted: Vec::new(),
+    };
+
+    let mut tables_by_type: HashMap<usize, Vec<&str>> = HashMap::new();
+    for table in &schema.tables {
+        tables_by_type
+            .entry(table.product_type_ref)
+            .or_default()
+            .push(&table.name);
+    }
+
+    let mut order: Vec<usize> = Vec::new();
+    for table in &schema.tables {
+        if !order.contains(&table.product_type_ref) {
+            order.push(table.product_type_ref);
+        }
+    }
+    let mut rest: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| !order.contains(idx))
+        .collect();
+    rest.sort_by_key(|idx| generator.names[idx].to_lowercase());
+    order.extend(rest);
+
+    let mut bodies: BTreeMap<Vec<String>, String> = BTreeMap::new();
+    for idx in order {
+        let Some(def) = schema.typespace.types.get(idx) else {
+            continue;
+        };
+        let name = generator.names[&idx].clone();
+        let doc = tables_by_type.get(&idx).map(|tables| {
+            let tables: Vec<_> = tables.iter().map(|t| format!("`{t}`")).collect();
+            format!("/// Row type of the {} table\n", tables.join(", "))
+        });
+        generator.module = generator.module_of(idx).to_vec();
+        if let Some(definition) = generator.definition(idx, &name, def) {
+            let body = bodies.entry(generator.module.clone()).or_default();
+            body.push('\n');
+            body.push_str(doc.as_deref().unwrap_or_default());
+            body.push_str(&definition);
+            for hoisted in std::mem::take(&mut generator.hoisted) {
+                body.push('\n');
+                body.push_str(&hoisted);
+            }
+        }
+    }
+
+    let mut paths: BTreeSet<Vec<String>> = BTreeSet::new();
+    for path in bodies.keys() {
+        for depth in 0..=path.len() {
+            paths.insert(path[..depth].to_vec());
+        }
+    }
+    render_module(&[], &paths, &bodies, &generator.sdk_types)
+}
+
+/// The `use` line, definitions and submodules of the module at `path`,
+/// unindented
+fn render_module(
+    path: &[String],
+    paths: &BTreeSet<Vec<String>>,
+    bodies: &BTreeMap<Vec<String>, String>,
+    sdk_types: &BTreeMap<Vec<String>, BTreeSet<&'static str>>,
+) -> String {
+    let mut out = String::new();
+    if let Some(imports) = sdk_types.get(path) {
+        let imports: Vec<_> = imports.iter().copied().collect();
+        let _ = writeln!(out, "use spacetimedb_sdk::{{{}}};", imports.join(", "));
+    }
+    out.push_str(bodies.get(path).map_or("", String::as_str));
+    let children = paths
+        .iter()
+        .filter(|child| child.len() == path.len() + 1 && child.starts_with(path));
+    for child in children {
+        let inner = render_module(child, paths, bodies, sdk_types);
+        let _ = writeln!(out, "\npub mod {} {{", child[path.len()]);
+        for line in inner.trim_start_matches('\n').lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// The module path of each named typespace entry with a scope, each
+/// segment made a valid identifier
+fn type_modules(schema: &SatsSchema) -> HashMap<usize, Vec<String>> {
+    let mut modules = HashMap::new();
+    for named in &schema.types {
+        if !named.name.scope.is_empty() {
+            let path = named.name.scope.iter().map(|s| rust_identifier(s));
+            modules.entry(named.ty).or_insert_with(|| path.collect());
+        }
+    }
+    modules
+}
+
+/// Rust type name for each typespace entry, unique within its module
+/// after sanitizing; unnamed entries become `Type<index>`
+fn type_names(
+    schema: &SatsSchema,
+    modules: &HashMap<usize, Vec<String>>,
+) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::new();
+    let mut unique = |idx: usize, base: String| {
+        let module = modules.get(&idx).cloned().unwrap_or_default();
+        let mut name = base.clone();
+        let mut n = 2;
+        while !taken.insert((module.clone(), name.clone())) {
+            name = format!("{base}{n}");
+            n += 1;
+        }
+        name
+    };
+    for named in &schema.types {
+        names
+            .entry(named.ty)
+            .or_insert_with(|| unique(named.ty, rust_identifier(&named.name.name)));
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(idx, format!("Type{idx}")));
+    }
+    names
+}
+
+struct RustGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: HashMap<usize, String>,
+    /// Module path of each typespace entry in a scope
+    modules: HashMap<usize, Vec<String>>,
+    /// Module of the definition being generated
+    module: Vec<String>,
+    /// `spacetimedb_sdk` items the generated code uses, by module
+    sdk_types: BTreeMap<Vec<String>, BTreeSet<&'static str>>,
+    /// Definitions for anonymous structs and enums found inside the type
+    /// being generated, emitted right after it
+    hoisted: Vec<String>,
+}
+
+impl RustGenerator<'_> {
+    fn module_of(&self, idx: usize) -> &[String] {
+        self.modules.get(&idx).map_or(&[], Vec::as_slice)
+    }
+
+    fn definition(&mut self, idx: usize, name: &str, def: &TypeDef) -> Option<String> {
+        match def {
+            TypeDef::Product { Product } => {
+                if detect_spacetimedb_type(Product).is_some() {
+                    return None;
+                }
+                Some(self.product_definition(name, Product, Some(idx)))
+            }
+            TypeDef::Sum { Sum } => {
+                if detect_spacetimedb_sum_type(Sum).is_some() {
+                    return None;
+                }
+                if is_option_type(Sum) {
+                    let inner = get_option_inner_type(Sum)?;
+                    let inner = self.rust_type(inner, name, "", Some(idx), false);
+                    return Some(format!("pub type {name} = Option<{inner}>;\n"));
+                }
+                Some(self.sum_definition(name, Sum, Some(idx)))
+            }
+            TypeDef::Builtin { Builtin } => {
+                let ty = match Builtin {
+                    BuiltinType::Bool { .. } => "bool".to_string(),
+                    BuiltinType::I8 { .. } => "i8".to_string(),
+                    BuiltinType::U8 { .. } => "u8".to_string(),
+                    BuiltinType::I16 { .. } => "i16".to_string(),
+                    BuiltinType::U16 { .. } => "u16".to_string(),
+                    BuiltinType::I32 { .. } => "i32".to_string(),
+                    BuiltinType::U32 { .. } => "u32".to_string(),
+                    BuiltinType::I64 { .. } => "i64".to_string(),
+                    BuiltinType::U64 { .. } => "u64".to_string(),
+                    BuiltinType::I128 { .. } => "i128".to_string(),
+                    BuiltinType::U128 { .. } => "u128".to_string(),
+                    BuiltinType::I256 { .. } => self.sdk("i256"),
+                    BuiltinType::U256 { .. } => self.sdk("u256"),
+                    BuiltinType::F32 { .. } => "f32".to_string(),
+                    BuiltinType::F64 { .. } => "f64".to_string(),
+                    BuiltinType::String { .. } => "String".to_string(),
+                    BuiltinType::Array { Array } => {
+                        format!("Vec<{}>", self.rust_type(Array, name, "", None, true))
+                    }
+                    BuiltinType::Map { Map } => {
+                        let key = self.rust_type(&Map.key_ty, name, "key", None, true);
+                        let value = self.rust_type(&Map.ty, name, "value", None, true);
+                        format!("std::collections::HashMap<{key}, {value}>")
+                    }
+                };
+                Some(format!("pub type {name} = {ty};\n"))
+            }
+            TypeDef::Ref { Ref } => {
+                let target = self.reference(*Ref as usize, None, false);
+                Some(format!("pub type {name} = {target};\n"))
+            }
+        }
+    }
+
+    /// `self_idx` is the typespace entry being defined, for boxing fields
+    /// that lead back to it
+    fn product_definition(
+        &mut self,
+        name: &str,
+        product: &ProductType,
+        self_idx: Option<usize>,
+    ) -> String {
+        let derive = DERIVE;
+        if product.elements.is_empty() {
+            return format!("{derive}pub struct {name};\n");
+        }
+
+        if product
+            .elements
+            .iter()
+            .all(|e| e.name.as_option().is_none())
+        {
+            let fields: Vec<_> = product
+                .elements
+                .iter()
+                .enumerate()
+                .map(|(i, e)| {
+                    let ty =
+                        self.rust_type(&e.algebraic_type, name, &i.to_string(), self_idx, false);
+                    format!("pub {ty}")
+                })
+                .collect();
+            return format!("{derive}pub struct {name}({});\n", fields.join(", "));
+        }
+
+        let mut out = format!("{derive}pub struct {name} {{\n");
+        let mut taken = HashSet::new();
+        for (i, element) in product.elements.iter().enumerate() {
+            let field = unique_member(
+                &mut taken,
+                element
+                    .name
+                    .as_option()
+                    .map_or_else(|| format!("field_{i}"), rust_identifier),
+            );
+            let ty = self.rust_type(&element.algebraic_type, name, &field, self_idx, false);
+            if let Some(original) = element.name.as_option() {
+                out.push_str(&serde_rename(original, &field, "    "));
+            }
+            let _ = writeln!(out, "    pub {field}: {ty},");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn sum_definition(&mut self, name: &str, sum: &SumType, self_idx: Option<usize>) -> String {
+        let mut out = format!("{DERIVE}pub enum {name} {{\n");
+        let mut taken = HashSet::new();
+        for (i, variant) in sum.variants.iter().enumerate() {
+            let variant_name = unique_member(
+                &mut taken,
+                variant
+                    .name
+                    .as_option()
+                    .map_or_else(|| format!("Variant{i}"), rust_identifier),
+            );
+            if let Some(original) = variant.name.as_option() {
+                out.push_str(&serde_rename(original, &variant_name, "    "));
+            }
+            match &variant.algebraic_type {
+                AlgebraicType::Product { Product } if Product.elements.is_empty() => {
+                    let _ = writeln!(out, "    {variant_name},");
+                }
+                other => {
+                    let ty = self.rust_type(other, name, &variant_name, self_idx, false);
+                    let _ = writeln!(out, "    {variant_name}({ty}),");
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// `owner` and `member` name hoisted anonymous types (`PlayerStats`
+    /// for an inline struct in `Player::stats`). Refs are boxed when they
+    /// lead back to `self_idx` outside a `Vec`.
+    fn rust_type(
+        &mut self,
+        alg_type: &AlgebraicType,
+        owner: &str,
+        member: &str,
+        self_idx: Option<usize>,
+        in_vec: bool,
+    ) -> String {
+        match alg_type {
+            AlgebraicType::Bool { .. } => "bool".to_string(),
+            AlgebraicType::I8 { .. } => "i8".to_string(),
+            AlgebraicType::U8 { .. } => "u8".to_string(),
+            AlgebraicType::I16 { .. } => "i16".to_string(),
+            AlgebraicType::U16 { .. } => "u16".to_string(),
+            AlgebraicType::I32 { .. } => "i32".to_string(),
+            AlgebraicType::U32 { .. } => "u32".to_string(),
+            AlgebraicType::I64 { .. } => "i64".to_string(),
+            AlgebraicType::U64 { .. } => "u64".to_string(),
+            AlgebraicType::I128 { .. } => "i128".to_string(),
+            AlgebraicType::U128 { .. } => "u128".to_string(),
+            AlgebraicType::I256 { .. } => self.sdk("i256"),
+            AlgebraicType::U256 { .. } => self.sdk("u256"),
+            AlgebraicType::F32 { .. } => "f32".to_string(),
+            AlgebraicType::F64 { .. } => "f64".to_string(),
+            AlgebraicType::String { .. } => "String".to_string(),
+            AlgebraicType::Array { Array } => {
+                format!(
+                    "Vec<{}>",
+                    self.rust_type(Array, owner, member, self_idx, true)
+                )
+            }
+            AlgebraicType::Map { Map } => {
+                let key = self.rust_type(&Map.key_ty, owner, &format!("{member}_key"), None, true);
+                let value = self.rust_type(&Map.ty, owner, &format!("{member}_value"), None, true);
+                format!("std::collections::HashMap<{key}, {value}>")
+            }
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize, self_idx, in_vec),
+            AlgebraicType::Product { Product } => {
+                if let Some(special) = detect_spacetimedb_type(Product) {
+                    return self.special(&special);
+                }
+                if Product
+                    .elements
+                    .iter()
+                    .all(|e| e.name.as_option().is_none())
+                {
+                    let types: Vec<_> = Product
+                        .elements
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| {
+                            self.rust_type(
+                                &e.algebraic_type,
+                                owner,
+                                &format!("{member}{i}"),
+                                self_idx,
+                                in_vec,
+                            )
+                        })
+                        .collect();
+                    return match types.len() {
+                        1 => format!("({},)", types[0]),
+                        _ => format!("({})", types.join(", ")),
+                    };
+                }
+                let name = hoisted_name(owner, member);
+                let definition = self.product_definition(&name, Product, None);
+                self.hoisted.push(definition);
+                name
+            }
+            AlgebraicType::Sum { Sum } => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    return self.special(&special);
+                }
+                if is_option_type(Sum) {
+                    if let Some(inner) = get_option_inner_type(Sum) {
+                        return format!(
+                            "Option<{}>",
+                            self.rust_type(inner, owner, member, self_idx, in_vec)
+                        );
+                    }
+                }
+                let name = hoisted_name(owner, member);
+                let definition = self.sum_definition(&name, Sum, None);
+                self.hoisted.push(definition);
+                name
+            }
+        }
+    }
+
+    fn reference(&mut self, idx: usize, self_idx: Option<usize>, in_vec: bool) -> String {
+        match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => {
+                if let Some(special) = detect_spacetimedb_type(Product) {
+                    return self.special(&special);
+                }
+            }
+            Some(TypeDef::Sum { Sum }) => {
+                if let Some(special) = detect_spacetimedb_sum_type(Sum) {
+                    return self.special(&special);
+                }
+            }
+            _ => {}
+        }
+
+        let name = self
+            .names
+            .get(&idx)
+            .cloned()
+            .unwrap_or_else(|| format!("Type{idx}"));
+        let target = self.module_of(idx);
+        let common = self
+            .module
+            .iter()
+            .zip(target)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut path = "super::".repeat(self.module.len() - common);
+        for segment in &target[common..] {
+            path.push_str(segment);
+            path.push_str("::");
+        }
+        let name = format!("{path}{name}");
+        let recursive = self_idx
+            .is_some_and(|target| !in_vec && reaches(self.schema, idx, target, &mut Vec::new()));
+        if recursive {
+            format!("Box<{name}>")
+        } else {
+            name
+        }
+    }
+
+    /// SpacetimeDB types map onto their `spacetimedb_sdk` counterparts
+    fn special(&mut self, detected: &str) -> String {
+        match detected {
+            "Identity" => self.sdk("Identity"),
+            "Timestamp" => self.sdk("Timestamp"),
+            "Duration" => self.sdk("TimeDuration"),
+            "ScheduledAt" => self.sdk("ScheduleAt"),
+            other => other.to_string(),
+        }
+    }
+
+    fn sdk(&mut self, item: &'static str) -> String {
+        self.sdk_types
+            .entry(self.module.clone())
+            .or_default()
+            .insert(item);
+        item.to_string()
+    }
+}
+
+/// Whether typespace entry `from` contains `target` other than through a
+/// `Vec` (which is already heap-allocated)
+fn reaches(schema: &SatsSchema, from: usize, target: usize, visiting: &mut Vec<usize>) -> bool {
+    if from == target {
+        return true;
+    }
+    if visiting.contains(&from) {
+        return false;
+    }
+    visiting.push(from);
+    let found = match schema.typespace.types.get(from) {
+        Some(TypeDef::Product { Product }) => Product
+            .elements
+            .iter()
+            .any(|e| type_reaches(schema, &e.algebraic_type, target, visiting)),
+        Some(TypeDef::Sum { Sum }) => Sum
+            .variants
+            .iter()
+            .any(|v| type_reaches(schema, &v.algebraic_type, target, visiting)),
+        Some(TypeDef::Ref { Ref }) => reaches(schema, *Ref as usize, target, visiting),
+        _ => false,
+    };
+    visiting.pop();
+    found
+}
+
+fn type_reaches(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    target: usize,
+    visiting: &mut Vec<usize>,
+) -> bool {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => reaches(schema, *Ref as usize, target, visiting),
+        AlgebraicType::Product { Product } => Product
+            .elements
+            .iter()
+            .any(|e| type_reaches(schema, &e.algebraic_type, target, visiting)),
+        AlgebraicType::Sum { Sum } => Sum
+            .variants
+            .iter()
+            .any(|v| type_reaches(schema, &v.algebraic_type, target, visiting)),
+        _ => false,
+    }
+}
+
+/// `ident`, numbered if an earlier field or variant of the same type
+/// already took it (`user-data` and `user_data` both sanitize to
+/// `user_data`). Numbered keywords no longer need the `r#`.
+fn unique_member(taken: &mut HashSet<String>, ident: String) -> String {
+    let mut name = ident.clone();
+    let mut n = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{}{n}", ident.trim_start_matches("r#"));
+        n += 1;
+    }
+    name
+}
+
+/// A `#[serde(rename)]` line when sanitizing changed the name, so the
+/// generated types still (de)serialize under the schema's spelling
+fn serde_rename(original: &str, ident: &str, indent: &str) -> String {
+    if ident.trim_start_matches("r#") == original {
+        String::new()
+    } else {
+        format!("{indent}#[serde(rename = {original:?})]\n")
+    }
+}
+
+/// `Player` + `home_base` -> `PlayerHomeBase`
+fn hoisted_name(owner: &str, member: &str) -> String {
+    format!("{owner}{}", pascal_case(member.trim_start_matches("r#")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema, schema};
+    use serde_json::json;
+
+    #[test]
+    fn demo_schema_matches_fixture() {
+        assert_eq!(
+            generate_rust_code(&demo_schema(), false),
+            include_str!("../tests/fixtures/demo.rs")
+        );
+    }
+
+    #[test]
+    fn self_references_are_boxed_outside_vecs() {
+        let rust = generate_rust_code(&recursive_schema(), false);
+        assert!(rust.contains("pub children: Vec<Node>,"), "{rust}");
+
+        let rust = generate_rust_code(&demo_schema(), false);
+        assert!(rust.contains("pub next: Option<Box<ItemStack>>,"), "{rust}");
+    }
+
+    #[test]
+    fn unnamed_products_become_tuples() {
+        let module = schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"none": []}, "algebraic_type": {"F32": []}},
+                    {"name": {"none": []}, "algebraic_type": {"F32": []}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "corners"}, "algebraic_type": {"Product": {"elements": [
+                        {"name": {"none": []}, "algebraic_type": {"Ref": 0}},
+                        {"name": {"none": []}, "algebraic_type": {"Ref": 0}},
+                    ]}}},
+                    {"name": {"some": "style"}, "algebraic_type": {"Product": {"elements": [
+                        {"name": {"some": "fill"}, "algebraic_type": {"Bool": []}},
+                    ]}}},
+                ]}},
+            ]},
+            "tables": [{"name": "shape", "product_type_ref": 1, "primary_key": []}],
+            "types": [
+                {"name": {"scope": [], "name": "Point"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Shape"}, "ty": 1, "custom_ordering": true},
+            ],
+        }));
+        let rust = generate_rust_code(&module, false);
+        assert!(
+            rust.contains("pub struct Point(pub f32, pub f32);\n"),
+            "{rust}"
+        );
+        assert!(
+            rust.contains("    pub corners: (Point, Point),\n"),
+            "{rust}"
+        );
+        assert!(rust.contains("    pub style: ShapeStyle,\n"), "{rust}");
+        assert!(
+            rust.contains("pub struct ShapeStyle {\n    pub fill: bool,\n}\n"),
+            "{rust}"
+        );
+    }
+
+    #[test]
+    fn sanitized_names_keep_their_schema_spelling() {
+        let module = schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"some": "user-data"}, "algebraic_type": {"U8": []}},
+                    {"name": {"some": "user_data"}, "algebraic_type": {"U8": []}},
+                    {"name": {"some": "type"}, "algebraic_type": {"U8": []}},
+                ]}},
+            ]},
+            "tables": [{"name": "entry", "product_type_ref": 0, "primary_key": []}],
+            "types": [{"name": {"scope": [], "name": "Entry"}, "ty": 0, "custom_ordering": true}],
+        }));
+        let rust = generate_rust_code(&module, false);
+        assert!(
+            rust.contains(concat!(
+                "    #[serde(rename = \"user-data\")]\n",
+                "    pub user_data: u8,\n",
+                "    #[serde(rename = \"user_data\")]\n",
+                "    pub user_data2: u8,\n",
+                "    pub r#type: u8,\n",
+            )),
+            "{rust}"
+        );
+    }
+
+    /// `game::Player` with a `game::world::Vec2` position, `shop::Item`
+    /// owned by a player, and an unscoped `Config`
+    fn scoped_schema() -> SatsSchema {
+        let identity = json!({"Product": {"elements": [
+            {"name": {"some": "__identity__"}, "algebraic_type": {"U256": []}},
+        ]}});
+        schema(json!({
+            "typespace": {"types": [
+                {"Product": {"elements": [
+                    {"name": {"some": "x"}, "algebraic_type": {"F32": []}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "identity"}, "algebraic_type": identity},
+                    {"name": {"some": "position"}, "algebraic_type": {"Ref": 0}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "owner"}, "algebraic_type": {"Ref": 1}},
+                ]}},
+                {"Product": {"elements": [
+                    {"name": {"some": "spawn"}, "algebraic_type": {"Ref": 0}},
+                ]}},
+            ]},
+            "tables": [{"name": "player", "product_type_ref": 1, "primary_key": []}],
+            "types": [
+                {"name": {"scope": ["game", "world"], "name": "Vec2"}, "ty": 0, "custom_ordering": true},
+                {"name": {"scope": ["game"], "name": "Player"}, "ty": 1, "custom_ordering": true},
+                {"name": {"scope": ["shop"], "name": "Item"}, "ty": 2, "custom_ordering": true},
+                {"name": {"scope": [], "name": "Config"}, "ty": 3, "custom_ordering": true},
+            ],
+        }))
+    }
+
+    #[test]
+    fn scopes_become_nested_modules() {
+        assert_eq!(
+            generate_rust_code(&scoped_schema(), false),
+            r#"
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Config {
+    pub spawn: game::world::Vec2,
+}
+
+pub mod game {
+    use spacetimedb_sdk::{Identity};
+
+    /// Row type of the `player` table
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    pub struct Player {
+        pub identity: Identity,
+        pub position: world::Vec2,
+    }
+
+    pub mod world {
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        pub struct Vec2 {
+            pub x: f32,
+        }
+    }
+}
+
+pub mod shop {
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    pub struct Item {
+        pub owner: super::game::Player,
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn flat_ignores_scopes() {
+        let rust = generate_rust_code(&scoped_schema(), true);
+        assert!(!rust.contains("mod "), "{rust}");
+        assert!(
+            rust.starts_with("use spacetimedb_sdk::{Identity};\n"),
+            "{rust}"
+        );
+        assert!(rust.contains("    pub spawn: Vec2,\n"), "{rust}");
+        assert!(rust.contains("    pub owner: Player,\n"), "{rust}");
+    }
+}
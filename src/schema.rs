@@ -1,40 +1,167 @@
-use anyhow::Result;
-use colored::Colorize;
+use anyhow::{Context, Result};
+use colored::{ColoredString, Colorize};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
+
+use crate::ascii::glyphs;
+use crate::identifiers;
+use crate::metadata::SourceMetadata;
+use crate::output::{self, out, outln};
+use crate::reserved_names;
+use crate::spacetime_client::{Availability, EndpointProbe};
+use crate::spacetime_commands::SpacetimeCommands;
 
 // Import SATS types
-use self::sats_types::{AlgebraicType, ProductType, SatsSchema, SumType, TypeDef};
+use self::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, ReducerDef, SatsSchema, SumType, TableInfo, TypeDef,
+    TypeName,
+};
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 pub enum OutputFormat {
     Pretty,
     Json,
     Raw,
+    Kdl,
+    Zod,
+    Catalog,
+    Markdown,
+    Rust,
+    Dot,
+    Mermaid,
+    Sql,
+    Ts,
+    Csharp,
+    Jsonschema,
+    Resolved,
+    Proto,
+    Csv,
+    Html,
+    Go,
+    Kotlin,
+    Template,
+    Model,
+    Jsonl,
+    Compact,
 }
 
 pub struct SchemaArgs {
     pub db: String,
     pub server: String,
     pub version: Option<String>,
+    pub query: Vec<(String, String)>,
+    pub verbose: bool,
     pub cloud: bool,
     pub format: OutputFormat,
+    pub catalog_flavor: crate::catalog::CatalogFlavor,
+    pub namespace: String,
+    pub csharp_types: Vec<(String, String)>,
+    pub package: Option<String>,
+    pub go_package: String,
+    pub kotlin_package: Option<String>,
+    pub delimiter: char,
+    pub types_only: bool,
+    pub rust_flat: bool,
+    pub template: Option<std::path::PathBuf>,
+    pub template_context: bool,
     pub table: Option<String>,
     pub type_filter: Option<String>,
     pub enum_filter: Option<String>,
-    pub search: Option<String>,
+    pub search: Option<NameMatcher>,
+    pub referenced_by: Option<String>,
+    pub automation: bool,
+    pub unused: bool,
+    pub stats: bool,
+    pub index_report: bool,
+    pub coverage: bool,
+    pub wide_table_columns: usize,
+    pub against_db: Option<String>,
+    pub diff_version: Option<String>,
+    pub annotate_units: bool,
+    pub expand_all: bool,
+    pub color_by_category: bool,
+    pub legend: bool,
+    pub header: bool,
+    pub min_fields: Option<usize>,
+    pub max_fields: Option<usize>,
+    pub explain: bool,
+    pub abbreviate_types: bool,
+    pub enum_collapse_threshold: usize,
+    pub variant_skip: usize,
+    pub variant_top: Option<usize>,
+    pub dump_names: bool,
+    pub qualified_names: bool,
+    pub probe_endpoints: bool,
+    pub list: bool,
+    pub selftest: bool,
+    pub demo: bool,
+    pub file: Option<String>,
+    pub token: Option<String>,
+    pub strict_auth: bool,
+    pub whoami: bool,
+    /// Per-request limit; `None` waits forever
+    pub timeout: Option<std::time::Duration>,
+    pub retries: u32,
+    pub last_seen: bool,
+    pub lint: bool,
+    pub infer_names: bool,
+    pub no_metadata_footer: bool,
+    pub reproducible: bool,
+    pub no_warnings: bool,
+    pub extra_keywords: Vec<String>,
+    pub reducer_filter: Option<String>,
+    pub show_commands: bool,
+}
+
+/// Options for the pretty views
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    pub annotate_units: bool,
+    pub expand_all: bool,
+    pub color_by_category: bool,
+    pub legend: bool,
+    pub min_fields: Option<usize>,
+    pub max_fields: Option<usize>,
+    pub explain: bool,
+    pub abbreviate_types: bool,
+    pub header: bool,
+    /// Enums with more variants than this are listed on one line in the
+    /// overview (0 never collapses)
+    pub enum_collapse_threshold: usize,
+    /// Variants skipped at the start of the single-enum view
+    pub variant_skip: usize,
+    /// Variants shown in the single-enum view, after `variant_skip`
+    pub variant_top: Option<usize>,
+}
+
+/// Default for --enum-collapse-threshold
+pub const DEFAULT_ENUM_COLLAPSE_THRESHOLD: usize = 50;
+
+impl RenderOptions {
+    fn table_size_matches(&self, field_count: usize) -> bool {
+        self.min_fields.is_none_or(|min| field_count >= min)
+            && self.max_fields.is_none_or(|max| field_count <= max)
+    }
+
+    fn collapses_enum(&self, variant_count: usize) -> bool {
+        self.enum_collapse_threshold > 0 && variant_count > self.enum_collapse_threshold
+    }
 }
 
 // SATS type definitions (from the parser tool)
 // These must match the JSON format exactly
 #[allow(non_snake_case)]
-mod sats_types {
+pub mod sats_types {
     use super::{Deserialize, Serialize};
 
     #[derive(Debug, Deserialize, Serialize)]
     pub struct SatsSchema {
         pub typespace: TypeSpace,
         pub tables: Vec<TableInfo>,
+        #[serde(default)]
+        pub reducers: Vec<ReducerDef>,
         pub types: Vec<NamedType>,
     }
 
@@ -66,6 +193,8 @@ mod sats_types {
         U64 { U64: Vec<()> },
         I128 { I128: Vec<()> },
         U128 { U128: Vec<()> },
+        I256 { I256: Vec<()> },
+        U256 { U256: Vec<()> },
         F32 { F32: Vec<()> },
         F64 { F64: Vec<()> },
         String { String: Vec<()> },
@@ -117,6 +246,23 @@ mod sats_types {
         }
     }
 
+    // SATS encodes Option<T> as {"some": T} / {"none": []}
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum SatsOption<T> {
+        Some { some: T },
+        None { none: Vec<()> },
+    }
+
+    impl<T> SatsOption<T> {
+        pub fn as_option(&self) -> Option<&T> {
+            match self {
+                SatsOption::Some { some } => Some(some),
+                SatsOption::None { .. } => None,
+            }
+        }
+    }
+
     #[derive(Debug, Deserialize, Serialize)]
     #[serde(untagged)]
     pub enum AlgebraicType {
@@ -137,6 +283,7 @@ mod sats_types {
         F64 { F64: Vec<()> },
         String { String: Vec<()> },
         Array { Array: Box<AlgebraicType> },
+        Map { Map: MapType },
         Product { Product: ProductType },
         Sum { Sum: SumType },
         Ref { Ref: u32 },
@@ -147,6 +294,123 @@ mod sats_types {
         pub name: String,
         pub product_type_ref: usize,
         pub primary_key: Vec<usize>,
+        #[serde(default)]
+        pub schedule: Option<SatsOption<ScheduleDef>>,
+        #[serde(default)]
+        pub sequences: Vec<SequenceDef>,
+        #[serde(default)]
+        pub indexes: Vec<IndexDef>,
+        #[serde(default)]
+        pub constraints: Vec<ConstraintDef>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct IndexDef {
+        #[serde(default)]
+        pub name: Option<OptionalName>,
+        #[serde(default)]
+        pub accessor_name: Option<OptionalName>,
+        pub algorithm: IndexAlgorithm,
+    }
+
+    impl IndexDef {
+        pub fn name(&self) -> Option<&str> {
+            self.name.as_ref().and_then(|n| n.as_option())
+        }
+    }
+
+    // Unknown algorithms are kept as raw JSON rather than failing the parse
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum IndexAlgorithm {
+        BTree { BTree: Vec<usize> },
+        Hash { Hash: Vec<usize> },
+        Direct { Direct: usize },
+        Other(serde_json::Value),
+    }
+
+    impl IndexAlgorithm {
+        pub fn label(&self) -> &'static str {
+            match self {
+                IndexAlgorithm::BTree { .. } => "btree",
+                IndexAlgorithm::Hash { .. } => "hash",
+                IndexAlgorithm::Direct { .. } => "direct",
+                IndexAlgorithm::Other(_) => "unknown",
+            }
+        }
+
+        pub fn columns(&self) -> Vec<usize> {
+            match self {
+                IndexAlgorithm::BTree { BTree } => BTree.clone(),
+                IndexAlgorithm::Hash { Hash } => Hash.clone(),
+                IndexAlgorithm::Direct { Direct } => vec![*Direct],
+                IndexAlgorithm::Other(_) => Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct ConstraintDef {
+        #[serde(default)]
+        pub name: Option<OptionalName>,
+        pub data: ConstraintData,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum ConstraintData {
+        Unique { Unique: UniqueConstraint },
+        Other(serde_json::Value),
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct UniqueConstraint {
+        pub columns: Vec<usize>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct SequenceDef {
+        pub column: usize,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct ScheduleDef {
+        #[serde(default)]
+        pub name: Option<OptionalName>,
+        pub reducer_name: String,
+        pub scheduled_at_column: usize,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct ReducerDef {
+        pub name: String,
+        pub params: ProductType,
+        #[serde(default)]
+        pub lifecycle: Option<SatsOption<Lifecycle>>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(untagged)]
+    pub enum Lifecycle {
+        Init { Init: Vec<()> },
+        OnConnect { OnConnect: Vec<()> },
+        OnDisconnect { OnDisconnect: Vec<()> },
+    }
+
+    impl Lifecycle {
+        pub fn label(&self) -> &'static str {
+            match self {
+                Lifecycle::Init { .. } => "init",
+                Lifecycle::OnConnect { .. } => "client_connected",
+                Lifecycle::OnDisconnect { .. } => "client_disconnected",
+            }
+        }
+    }
+
+    impl ReducerDef {
+        pub fn lifecycle(&self) -> Option<&Lifecycle> {
+            self.lifecycle.as_ref().and_then(|l| l.as_option())
+        }
     }
 
     #[derive(Debug, Deserialize, Serialize)]
@@ -164,96 +428,700 @@ mod sats_types {
 }
 // Schema operations
 pub async fn fetch_schema(args: SchemaArgs) -> Result<()> {
+    if args.selftest {
+        return crate::selftest::run_selftest(&args).await;
+    }
+
     let server = if args.cloud {
         "cloud"
     } else {
         &args.server
     };
 
-    let client = crate::spacetime_client::SpacetimeClient::new(server)?;
-    println!(
-        "{} {}",
-        "🌐 Fetching schema from:".cyan(),
-        client.base_url()
-    );
+    let mut client = crate::spacetime_client::SpacetimeClient::new(server, args.timeout)?
+        .with_verbose(args.verbose)
+        .with_retries(args.retries);
+    if args.whoami {
+        return display_whoami(client.base_url(), args.token.is_some());
+    }
+    if let Some(token) = &args.token {
+        client = client.with_token(token);
+    } else if !(args.demo || args.file.is_some() || args.last_seen) {
+        client = client.with_stored_token(args.strict_auth)?;
+    }
+    if args.probe_endpoints {
+        outln!("{} {}", glyphs("🌐 Probing:").cyan(), client.base_url());
+        let probes = client.probe_endpoints(&args.db).await;
+        display_endpoint_probes(&probes);
+        return Ok(());
+    }
+    if args.list {
+        output::status(format!(
+            "{} {}",
+            glyphs("🌐 Listing databases on:").cyan(),
+            client.base_url()
+        ));
+        let names = client.list_databases().await?;
+        if names.is_empty() {
+            outln!("{}", "No databases found".yellow());
+        }
+        for name in names {
+            outln!("{name}");
+        }
+        return Ok(());
+    }
+    if args.last_seen {
+        return crate::history::display_last_seen(client.base_url(), &args.db);
+    }
+
+    if streams_document(&args) {
+        return stream_document(&client, &args).await;
+    }
 
-    let schema_json = client.fetch_schema(&args.db, args.version).await?;
+    let schema_json = if args.demo {
+        output::status(glyphs("📦 Using the bundled demo schema").cyan());
+        serde_json::from_str(DEMO_SCHEMA)?
+    } else if let Some(path) = &args.file {
+        output::status(format!(
+            "{} {}",
+            glyphs("📂 Loading from file:").cyan(),
+            path
+        ));
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?;
+        serde_json::from_str(&text).with_context(|| format!("{path} is not valid JSON"))?
+    } else {
+        output::status(format!(
+            "{} {}",
+            glyphs("🌐 Fetching schema from:").cyan(),
+            client.base_url()
+        ));
+        let schema_json = client
+            .fetch_schema(&args.db, args.version.clone(), &args.query)
+            .await?;
+        crate::history::record_fetch(client.base_url(), &args.db, &schema_json);
+        schema_json
+    };
     let schema_text = serde_json::to_string_pretty(&schema_json)?;
-    println!("{} {} bytes", "✅ Fetched".green(), schema_text.len());
+    if !args.demo && args.file.is_none() {
+        output::status(format!(
+            "{} {} bytes",
+            glyphs("✅ Fetched").green(),
+            schema_text.len()
+        ));
+    }
+    if args.verbose && !args.coverage {
+        // A document that doesn't parse is reported by whichever output runs next
+        if let Ok(schema) = serde_json::from_value::<SatsSchema>(schema_json.clone()) {
+            output::status(crate::coverage::coverage_summary(&schema_json, &schema).dimmed());
+        }
+    }
+
+    if let (Some(other_db), Some(table_name)) = (&args.against_db, &args.table) {
+        let other_json = client
+            .fetch_schema(other_db, args.version, &args.query)
+            .await?;
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let other = parse_schema(other_json, args.infer_names)?;
+        let (left, right) = ((args.db.as_str(), &schema), (other_db.as_str(), &other));
+        match args.format {
+            OutputFormat::Markdown => {
+                let mut out = output::sink();
+                crate::markdown::write_table_comparison(&mut out, left, right, table_name)?;
+                out.flush()?;
+            }
+            _ => display_table_comparison(left, right, table_name),
+        }
+        return Ok(());
+    }
+
+    if let Some(other_version) = &args.diff_version {
+        let other_json = client
+            .fetch_schema(&args.db, Some(other_version.clone()), &args.query)
+            .await?;
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let other = parse_schema(other_json, args.infer_names)?;
+        let version = format!("version {}", args.version.as_deref().unwrap_or("(default)"));
+        display_schema_diff(
+            (&version, &schema),
+            (&format!("version {other_version}"), &other),
+        );
+        return Ok(());
+    }
+
+    if args.dump_names {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let names: BTreeMap<usize, String> = schema
+            .types
+            .iter()
+            .map(|t| {
+                let name = if args.qualified_names {
+                    qualified_type_name(&t.name)
+                } else {
+                    t.name.name.clone()
+                };
+                (t.ty, name)
+            })
+            .collect();
+        outln!("{}", serde_json::to_string_pretty(&names)?);
+        return Ok(());
+    }
+
+    if args.lint {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let warnings = find_name_warnings(&schema, &args.extra_keywords);
+        match args.format {
+            OutputFormat::Pretty => display_name_warnings(&warnings),
+            _ => outln!("{}", serde_json::to_string_pretty(&warnings)?),
+        }
+        return Ok(());
+    }
+
+    if args.automation {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        match args.format {
+            OutputFormat::Pretty => display_automation(&schema),
+            _ => {
+                outln!(
+                    "{}",
+                    serde_json::to_string_pretty(&automation_json(&schema))?
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    if args.stats {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        match args.format {
+            OutputFormat::Pretty => print_summary(&schema),
+            _ => outln!(
+                "{}",
+                serde_json::to_string_pretty(&SchemaStats::new(&schema).to_json())?
+            ),
+        }
+        return Ok(());
+    }
+
+    if args.unused {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let unused = unused_types(&schema);
+        match args.format {
+            OutputFormat::Pretty => display_unused(&unused),
+            _ => outln!("{}", serde_json::to_string_pretty(&unused)?),
+        }
+        return Ok(());
+    }
+
+    if args.template_context {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        let context = crate::template::template_context(&schema, &args.db);
+        outln!("{}", serde_json::to_string_pretty(&context)?);
+        return Ok(());
+    }
+
+    if args.coverage {
+        let schema = parse_schema(schema_json.clone(), args.infer_names)?;
+        match args.format {
+            OutputFormat::Pretty => crate::coverage::display_coverage(&schema_json, &schema),
+            _ => {
+                let report = crate::coverage::coverage_json(&schema_json, &schema);
+                outln!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.index_report {
+        let schema = parse_schema(schema_json, args.infer_names)?;
+        match args.format {
+            OutputFormat::Pretty => {
+                crate::index_report::display_index_report(&schema, args.wide_table_columns);
+            }
+            _ => {
+                let report =
+                    crate::index_report::index_report_json(&schema, args.wide_table_columns);
+                outln!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        }
+        return Ok(());
+    }
+
+    // Provenance comments for the generated document formats
+    let metadata = (!args.no_metadata_footer).then(|| {
+        SourceMetadata::new(
+            match &args.file {
+                _ if args.demo => "bundled demo schema",
+                Some(path) => path,
+                None => client.base_url(),
+            },
+            &args.db,
+            args.version.as_deref(),
+            // Only a server fetch sends the query
+            if args.demo { &[] } else { &args.query },
+            &schema_json,
+            args.reproducible,
+        )
+    });
 
     match args.format {
         OutputFormat::Raw | OutputFormat::Json => {
-            println!("{schema_text}");
+            outln!("{schema_text}");
         }
-        OutputFormat::Pretty => {
-            let schema: SatsSchema = serde_json::from_value(schema_json)?;
-            display_schema_pretty(
+        OutputFormat::Kdl => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let mut out = output::sink();
+            crate::kdl::write_kdl(&mut out, &schema)?;
+            if let Some(metadata) = &metadata {
+                write!(out, "\n{}", metadata.comment_block("//"))?;
+            }
+            out.flush()?;
+        }
+        OutputFormat::Zod => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!(
+                "{}",
+                crate::zod::generate_zod(&schema, args.table.as_deref())?
+            );
+        }
+        OutputFormat::Catalog => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let catalog = crate::catalog::generate_catalog(
+                &schema,
+                client.base_url(),
+                &args.db,
+                args.table.as_deref(),
+                args.catalog_flavor,
+            )?;
+            outln!("{}", serde_json::to_string_pretty(&catalog)?);
+        }
+        OutputFormat::Csv => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let mut out = output::sink();
+            crate::csv::write_csv(
+                &mut out,
                 &schema,
-                args.table,
-                args.type_filter,
-                args.enum_filter,
-                args.search,
+                args.table.as_deref(),
+                args.delimiter,
+                args.types_only,
+            )?;
+            out.flush()?;
+        }
+        OutputFormat::Kotlin => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!(
+                "{}",
+                crate::kotlin::generate_kotlin(&schema, args.kotlin_package.as_deref())
+            );
+        }
+        OutputFormat::Go => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let code = crate::go::generate_go(&schema, &args.go_package)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!("{}", code);
+        }
+        OutputFormat::Proto => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!(
+                "{}",
+                crate::proto::generate_proto(&schema, args.package.as_deref())
+            );
+        }
+        OutputFormat::Resolved => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            outln!(
+                "{}",
+                serde_json::to_string_pretty(&crate::resolved::to_resolved_json(&schema))?
+            );
+        }
+        OutputFormat::Model => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            outln!(
+                "{}",
+                serde_json::to_string_pretty(&crate::model::SchemaModel::new(&schema))?
+            );
+        }
+        OutputFormat::Compact => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            out!("{}", crate::compact::generate_compact(&schema));
+        }
+        OutputFormat::Jsonl => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            out!("{}", crate::jsonl::generate_jsonl(&schema, &args)?);
+        }
+        OutputFormat::Jsonschema => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            outln!(
+                "{}",
+                crate::jsonschema::generate_json_schema(&schema, args.table.as_deref())?
+            );
+        }
+        OutputFormat::Rust => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!("{}", crate::rust::generate_rust_code(&schema, args.rust_flat));
+        }
+        OutputFormat::Dot => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!("{}", crate::dot::generate_dot(&schema));
+        }
+        OutputFormat::Mermaid => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("%%"));
+            }
+            out!("{}", crate::mermaid::generate_mermaid(&schema));
+        }
+        OutputFormat::Sql => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("--"));
+            }
+            out!(
+                "{}",
+                crate::sql::generate_sql(&schema, args.table.as_deref())?
             );
         }
+        OutputFormat::Ts => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!("{}", crate::typescript::generate_typescript(&schema));
+        }
+        OutputFormat::Csharp => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let code =
+                crate::csharp::generate_csharp(&schema, &args.namespace, &args.csharp_types)?;
+            if let Some(metadata) = &metadata {
+                outln!("{}", metadata.comment_block("//"));
+            }
+            out!("{}", code);
+        }
+        OutputFormat::Html => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let mut out = output::sink();
+            crate::html::write_html(
+                &mut out,
+                &schema,
+                &args.db,
+                metadata.as_ref(),
+                args.enum_collapse_threshold,
+            )?;
+            out.flush()?;
+        }
+        OutputFormat::Template => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let path = args
+                .template
+                .as_deref()
+                .expect("clap requires --template with --format template");
+            let context = crate::template::template_context(&schema, &args.db);
+            out!("{}", crate::template::render_template(path, &context)?);
+        }
+        OutputFormat::Markdown => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let mut out = output::sink();
+            crate::markdown::write_markdown(&mut out, &schema, &args)?;
+            out.flush()?;
+        }
+        OutputFormat::Pretty => {
+            let schema = parse_schema(schema_json, args.infer_names)?;
+            let options = RenderOptions {
+                annotate_units: args.annotate_units,
+                expand_all: args.expand_all,
+                color_by_category: args.color_by_category,
+                legend: args.legend,
+                min_fields: args.min_fields,
+                max_fields: args.max_fields,
+                explain: args.explain,
+                abbreviate_types: args.abbreviate_types,
+                header: args.header,
+                enum_collapse_threshold: args.enum_collapse_threshold,
+                variant_skip: args.variant_skip,
+                variant_top: args.variant_top,
+            };
+            let commands = args.show_commands.then(|| SpacetimeCommands {
+                db: args.db.clone(),
+                server: if args.cloud {
+                    "maincloud".to_string()
+                } else {
+                    args.server.clone()
+                },
+            });
+            if args.demo && !has_view_filter(&args) {
+                display_demo_walkthrough(&schema, &args, options);
+            } else {
+                display_schema_pretty(&schema, &args, options, commands.as_ref());
+            }
+        }
     }
 
     Ok(())
 }
 
+/// `--format raw` and `--format json` with nothing else to do just relay the
+/// document, so there is no need to parse it
+fn streams_document(args: &SchemaArgs) -> bool {
+    matches!(args.format, OutputFormat::Raw | OutputFormat::Json)
+        && !(args.against_db.is_some()
+            || args.diff_version.is_some()
+            || args.dump_names
+            || args.lint
+            || args.automation
+            || args.unused
+            || args.stats
+            || args.template_context
+            || args.coverage
+            || args.index_report)
+}
+
+/// Write the schema to stdout as it is read: raw passes the bytes through
+/// untouched, json re-indents them without parsing, so large integer
+/// literals survive exactly. Progress goes to stderr to keep stdout
+/// pipeable, and the fetch is not recorded for --last-seen, which needs the
+/// parsed document.
+async fn stream_document(
+    client: &crate::spacetime_client::SpacetimeClient,
+    args: &SchemaArgs,
+) -> Result<()> {
+    let mut out = output::sink();
+    let received = if matches!(args.format, OutputFormat::Raw) {
+        let received = read_document(client, args, |chunk| Ok(out.write_all(chunk)?)).await?;
+        out.flush()?;
+        received
+    } else {
+        let mut printer = crate::json_stream::PrettyPrinter::new(out);
+        let received =
+            read_document(client, args, |chunk| Ok(printer.write_chunk(chunk)?)).await?;
+        printer.finish()?;
+        received
+    };
+    if !args.demo && args.file.is_none() {
+        output::status(format!("{} {} bytes", glyphs("✅ Fetched").green(), received));
+    }
+    Ok(())
+}
+
+/// Hand the schema document to `sink` as it is read, from the bundled demo
+/// schema, a --file dump or the server, and return its size in bytes
+async fn read_document(
+    client: &crate::spacetime_client::SpacetimeClient,
+    args: &SchemaArgs,
+    mut sink: impl FnMut(&[u8]) -> Result<()>,
+) -> Result<usize> {
+    if args.demo {
+        output::status(glyphs("📦 Using the bundled demo schema").cyan());
+        sink(DEMO_SCHEMA.as_bytes())?;
+        return Ok(DEMO_SCHEMA.len());
+    }
+    if let Some(path) = &args.file {
+        output::status(format!("{} {}", glyphs("📂 Loading from file:").cyan(), path));
+        let mut file =
+            std::fs::File::open(path).with_context(|| format!("Could not read {path}"))?;
+        let mut buf = vec![0; 64 * 1024];
+        let mut received = 0;
+        loop {
+            let n = file
+                .read(&mut buf)
+                .with_context(|| format!("Could not read {path}"))?;
+            if n == 0 {
+                return Ok(received);
+            }
+            sink(&buf[..n])?;
+            received += n;
+        }
+    }
+    output::status(format!(
+        "{} {}",
+        glyphs("🌐 Fetching schema from:").cyan(),
+        client.base_url()
+    ));
+    client
+        .stream_schema(&args.db, args.version.clone(), &args.query, sink)
+        .await
+}
+
+/// A small game module's schema, bundled for --demo
+pub(crate) const DEMO_SCHEMA: &str = include_str!("demo_schema.json");
+
+fn has_view_filter(args: &SchemaArgs) -> bool {
+    args.table.is_some()
+        || args.type_filter.is_some()
+        || args.enum_filter.is_some()
+        || args.reducer_filter.is_some()
+        || args.search.is_some()
+        || args.referenced_by.is_some()
+}
+
+/// A tour of the pretty views over the demo schema, each introduced by the
+/// command that produces it
+fn display_demo_walkthrough(schema: &SatsSchema, args: &SchemaArgs, options: RenderOptions) {
+    let type_names = build_type_names(schema);
+    let caption = |text: &str, flags: &str| {
+        outln!("\n{} {}", glyphs("💡").yellow(), text.bold());
+        outln!(
+            "   {}",
+            format!("spacetime-schema-tool --db <database> {flags}")
+                .trim_end()
+                .dimmed()
+        );
+    };
+
+    caption("The whole schema: tables, then the other types", "");
+    display_schema_pretty(schema, args, options, None);
+
+    caption(
+        "One table with its field markers",
+        "--table player --legend",
+    );
+    display_single_table(schema, &type_names, "player", options, None);
+    print_marker_legend(RenderOptions {
+        legend: true,
+        ..options
+    });
+
+    caption("Everything whose name matches a pattern", "--search guild");
+    display_search_results(schema, &type_names, &NameMatcher::substring("guild"));
+
+    caption("One enum and its variants", "--enum Rarity");
+    display_single_enum(schema, &type_names, "Rarity", options);
+
+    outln!(
+        "\n{} Any other flag works with --demo too, e.g. {}",
+        glyphs("💡").yellow(),
+        "--demo --format zod".bold()
+    );
+}
+
+/// Parse the fetched schema, filling in guessed type names with --infer-names
+fn parse_schema(schema_json: serde_json::Value, infer_names: bool) -> Result<SatsSchema> {
+    let mut schema: SatsSchema = serde_json::from_value(schema_json)?;
+    if infer_names {
+        let count = crate::infer_names::infer_type_names(&mut schema);
+        output::status(format!(
+            "{} Inferred {} type names (marked with {})",
+            glyphs("🔎").cyan(),
+            count,
+            crate::infer_names::INFERRED_MARKER
+        ));
+    } else if schema.types.is_empty() && !schema.typespace.types.is_empty() {
+        output::status(format!(
+            "{} The schema has no type names; --infer-names can guess them",
+            glyphs("ℹ️").blue()
+        ));
+    }
+    Ok(schema)
+}
+
 fn display_schema_pretty(
     schema: &SatsSchema,
-    table_filter: Option<String>,
-    type_filter: Option<String>,
-    enum_filter: Option<String>,
-    search_pattern: Option<String>,
+    args: &SchemaArgs,
+    options: RenderOptions,
+    commands: Option<&SpacetimeCommands>,
 ) {
     // Extract real names
-    let mut type_names = HashMap::new();
-    for named_type in &schema.types {
-        type_names.insert(named_type.ty, named_type.name.name.clone());
+    let type_names = build_type_names(schema);
+
+    if options.legend && options.color_by_category {
+        print_color_legend();
     }
 
     // Apply filters
-    if let Some(table_name) = table_filter {
-        display_single_table(schema, &type_names, &table_name);
+    if let Some(table_name) = &args.table {
+        display_single_table(schema, &type_names, table_name, options, commands);
+        if !args.no_warnings {
+            let warnings = find_name_warnings(schema, &args.extra_keywords);
+            let table_warnings: Vec<_> = warnings
+                .iter()
+                .filter(|w| w.table.eq_ignore_ascii_case(table_name))
+                .collect();
+            if !table_warnings.is_empty() {
+                outln!("\nWarnings:");
+                for warning in table_warnings {
+                    print_name_warning(warning, "  ");
+                }
+            }
+        }
+        print_marker_legend(options);
         return;
     }
 
-    if let Some(type_name) = type_filter {
-        display_single_type(schema, &type_names, &type_name);
+    if let Some(type_name) = &args.type_filter {
+        display_single_type(schema, &type_names, type_name, options);
         return;
     }
 
-    if let Some(enum_name) = enum_filter {
-        display_single_enum(schema, &type_names, &enum_name);
+    if let Some(enum_name) = &args.enum_filter {
+        display_single_enum(schema, &type_names, enum_name, options);
         return;
     }
 
-    if let Some(pattern) = search_pattern {
-        display_search_results(schema, &type_names, &pattern);
+    if let Some(reducer_name) = &args.reducer_filter {
+        display_single_reducer(schema, &type_names, reducer_name, commands);
+        return;
+    }
+
+    if let Some(matcher) = &args.search {
+        display_search_results(schema, &type_names, matcher);
+        return;
+    }
+
+    if let Some(target) = &args.referenced_by {
+        display_referenced_by(schema, &type_names, target);
         return;
     }
 
     // Default: show everything
-    println!("\n{}", "📋 SPACETIMEDB SCHEMA".bold().cyan());
-    println!("{}", "=".repeat(60));
+    outln!("\n{}", glyphs("📋 SPACETIMEDB SCHEMA").bold().cyan());
+    outln!("{}", "=".repeat(60));
 
     // Show tables
-    println!(
+    let tables: Vec<_> = schema
+        .tables
+        .iter()
+        .filter(|t| options.table_size_matches(table_field_count(schema, t)))
+        .collect();
+    let hidden_tables = schema.tables.len() - tables.len();
+
+    outln!(
         "\n{} {}",
-        "📊 TABLES".yellow(),
-        format!("({})", schema.tables.len()).dimmed()
+        glyphs("📊 TABLES").yellow(),
+        format!("({})", tables.len()).dimmed()
     );
-    for table in &schema.tables {
+    if hidden_tables > 0 {
+        outln!(
+            "  {}\n",
+            format!("{hidden_tables} tables hidden by --min-fields/--max-fields").dimmed()
+        );
+    }
+    for table in tables {
         let type_name = type_names
             .get(&table.product_type_ref)
             .cloned()
             .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
 
-        println!(
-            "  {} {} → {}",
-            "▸".green(),
+        outln!(
+            "  {} {} {} {}",
+            glyphs("▸").green(),
             table.name.bold(),
+            glyphs("→"),
             type_name.dimmed()
         );
 
@@ -261,23 +1129,28 @@ fn display_schema_pretty(
         if let Some(TypeDef::Product { Product }) =
             schema.typespace.types.get(table.product_type_ref)
         {
-            for element in &Product.elements {
-                if let Some(field_name) = element.name.as_option() {
-                    let field_type = format_type(&element.algebraic_type, &type_names);
-                    println!("    {} {}: {}", "├".dimmed(), field_name, field_type.cyan());
-                }
-            }
+            let abbreviations = print_table_fields(
+                schema,
+                table,
+                Product,
+                &type_names,
+                options,
+                glyphs("├").dimmed(),
+                "    ",
+            );
+            abbreviations.print("    ");
         }
-        println!();
+        print_schedule_link(schema, table, "    ");
+        outln!();
     }
 
     // Show other types (enums, structs)
-    println!(
+    outln!(
         "{} {}",
-        "🔧 OTHER TYPES".yellow(),
+        glyphs("🔧 OTHER TYPES").yellow(),
         "(enums, structs)".dimmed()
     );
-    println!("{}", "-".repeat(40));
+    outln!("{}", "-".repeat(40));
 
     // Find types that aren't used as tables
     let table_type_refs: HashSet<usize> =
@@ -295,17 +1168,25 @@ fn display_schema_pretty(
                 TypeDef::Sum { Sum } => {
                     // Check for special types
                     if let Some(special_type) = detect_spacetimedb_sum_type(Sum) {
-                        println!(
+                        outln!(
                             "  {} {}: {} {}",
-                            "⚡".yellow(),
+                            glyphs("⚡").yellow(),
                             real_name.bold(),
                             special_type,
                             "(SpacetimeDB type)".dimmed()
                         );
+                    } else if options.collapses_enum(Sum.variants.len()) {
+                        outln!(
+                            "  {} {} {} {}",
+                            glyphs("🔀").cyan(),
+                            real_name.bold(),
+                            format!("(enum with {} variants)", Sum.variants.len()).dimmed(),
+                            format!("{} --enum {real_name} to list them", glyphs("→")).dimmed()
+                        );
                     } else {
-                        println!(
+                        outln!(
                             "  {} {} {}",
-                            "🔀".cyan(),
+                            glyphs("🔀").cyan(),
                             real_name.bold(),
                             format!("(enum with {} variants)", Sum.variants.len()).dimmed()
                         );
@@ -313,7 +1194,7 @@ fn display_schema_pretty(
                         // Show enum variants
                         for (i, variant) in Sum.variants.iter().enumerate() {
                             let is_last = i == Sum.variants.len() - 1;
-                            let prefix = if is_last { "└" } else { "├" };
+                            let prefix = glyphs(if is_last { "└" } else { "├" });
 
                             if let Some(variant_name) = variant.name.as_option() {
                                 // Check if variant has associated data
@@ -322,17 +1203,26 @@ fn display_schema_pretty(
                                         if Product.elements.is_empty() =>
                                     {
                                         // Unit variant
-                                        println!("    {} {}", prefix.dimmed(), variant_name);
+                                        outln!("    {} {}", prefix.dimmed(), variant_name);
                                     }
                                     _ => {
                                         // Variant with data
-                                        let variant_type =
-                                            format_type(&variant.algebraic_type, &type_names);
-                                        println!(
+                                        let variant_type = render_field_type(
+                                            schema,
+                                            &variant.algebraic_type,
+                                            &type_names,
+                                            options,
+                                        );
+                                        outln!(
                                             "    {} {}({})",
                                             prefix.dimmed(),
                                             variant_name,
-                                            variant_type.cyan()
+                                            color_type(
+                                                schema,
+                                                &variant.algebraic_type,
+                                                &variant_type,
+                                                options
+                                            )
                                         );
                                     }
                                 }
@@ -343,17 +1233,17 @@ fn display_schema_pretty(
                 TypeDef::Product { Product } => {
                     // Check for special types
                     if let Some(special_type) = detect_spacetimedb_type(Product) {
-                        println!(
+                        outln!(
                             "  {} {}: {} {}",
-                            "⚡".yellow(),
+                            glyphs("⚡").yellow(),
                             real_name.bold(),
                             special_type,
                             "(SpacetimeDB type)".dimmed()
                         );
                     } else {
-                        println!(
+                        outln!(
                             "  {} {} {}",
-                            "📦".blue(),
+                            glyphs("📦").blue(),
                             real_name.bold(),
                             format!("(struct with {} fields)", Product.elements.len()).dimmed()
                         );
@@ -361,20 +1251,49 @@ fn display_schema_pretty(
                         // Show struct fields
                         for (i, element) in Product.elements.iter().enumerate() {
                             let is_last = i == Product.elements.len() - 1;
-                            let prefix = if is_last { "└" } else { "├" };
+                            let prefix = glyphs(if is_last { "└" } else { "├" });
 
                             if let Some(field_name) = element.name.as_option() {
-                                let field_type = format_type(&element.algebraic_type, &type_names);
-                                println!(
-                                    "    {} {}: {}",
+                                let field_type = render_field_type(
+                                    schema,
+                                    &element.algebraic_type,
+                                    &type_names,
+                                    options,
+                                );
+                                outln!(
+                                    "    {} {}: {}{}",
                                     prefix.dimmed(),
                                     field_name,
-                                    field_type.cyan()
+                                    color_type(
+                                        schema,
+                                        &element.algebraic_type,
+                                        &field_type,
+                                        options
+                                    ),
+                                    unit_note(schema, &element.algebraic_type, options)
                                 );
+                                print_explanation(schema, &element.algebraic_type, options, "    ");
                             } else {
                                 // Unnamed field (tuple struct)
-                                let field_type = format_type(&element.algebraic_type, &type_names);
-                                println!("    {} {}: {}", prefix.dimmed(), i, field_type.cyan());
+                                let field_type = render_field_type(
+                                    schema,
+                                    &element.algebraic_type,
+                                    &type_names,
+                                    options,
+                                );
+                                outln!(
+                                    "    {} {}: {}{}",
+                                    prefix.dimmed(),
+                                    i,
+                                    color_type(
+                                        schema,
+                                        &element.algebraic_type,
+                                        &field_type,
+                                        options
+                                    ),
+                                    unit_note(schema, &element.algebraic_type, options)
+                                );
+                                print_explanation(schema, &element.algebraic_type, options, "    ");
                             }
                         }
                     }
@@ -389,94 +1308,660 @@ fn display_schema_pretty(
         }
     }
 
-    println!();
+    outln!();
 
-    // Summary
-    let enum_count = schema
-        .typespace
-        .types
-        .iter()
-        .filter(|t| matches!(t, TypeDef::Sum { .. }))
-        .count();
+    print_summary(schema);
+
+    if !args.no_warnings {
+        let warnings = find_name_warnings(schema, &args.extra_keywords);
+        if !warnings.is_empty() {
+            outln!(
+                "  {} {} naming warnings {}",
+                glyphs("⚠️").yellow(),
+                warnings.len(),
+                "(--lint lists them)".dimmed()
+            );
+        }
+    }
 
-    println!("{}", "📈 SUMMARY".yellow());
-    println!("  {} tables", schema.tables.len());
-    println!("  {} types total", schema.typespace.types.len());
-    println!("  {} enums", enum_count);
+    print_marker_legend(options);
 }
 
-fn format_type(alg_type: &AlgebraicType, type_names: &HashMap<usize, String>) -> String {
-    match alg_type {
-        AlgebraicType::Bool { .. } => "bool".to_string(),
-        AlgebraicType::I8 { .. } => "i8".to_string(),
-        AlgebraicType::U8 { .. } => "u8".to_string(),
-        AlgebraicType::I16 { .. } => "i16".to_string(),
-        AlgebraicType::U16 { .. } => "u16".to_string(),
-        AlgebraicType::I32 { .. } => "i32".to_string(),
-        AlgebraicType::U32 { .. } => "u32".to_string(),
-        AlgebraicType::I64 { .. } => "i64".to_string(),
-        AlgebraicType::U64 { .. } => "u64".to_string(),
-        AlgebraicType::I128 { .. } => "i128".to_string(),
-        AlgebraicType::U128 { .. } => "u128".to_string(),
-        AlgebraicType::I256 { .. } => "i256".to_string(),
-        AlgebraicType::U256 { .. } => "u256".to_string(),
-        AlgebraicType::F32 { .. } => "f32".to_string(),
-        AlgebraicType::F64 { .. } => "f64".to_string(),
-        AlgebraicType::String { .. } => "String".to_string(),
-        AlgebraicType::Array { Array } => {
-            format!("Vec<{}>", format_type(Array, type_names))
-        }
-        AlgebraicType::Ref { Ref } => type_names
-            .get(&(*Ref as usize))
-            .cloned()
-            .unwrap_or_else(|| format!("Type_{}", Ref)),
-        AlgebraicType::Sum { Sum } => {
-            // Check if this is a SpacetimeDB ScheduledAt pattern
-            if let Some(stdb_type) = detect_spacetimedb_sum_type(Sum) {
-                return stdb_type;
-            }
+/// The numbers behind the summary block and --stats
+pub struct SchemaStats {
+    pub tables: usize,
+    /// Struct entries in the typespace, table rows included
+    pub structs: usize,
+    /// Enum entries in the typespace, options excluded
+    pub enums: usize,
+    /// SpacetimeDB well-known types used anywhere, inline or as entries
+    pub well_known: BTreeSet<String>,
+    pub typespace_entries: usize,
+}
 
-            // Check if this is an Option<T> pattern
-            if is_option_type(Sum) {
-                if let Some(inner_type) = get_option_inner_type(Sum) {
-                    return format!("Option<{}>", format_type(inner_type, type_names));
-                }
-                return "Option<?>".to_string();
+impl SchemaStats {
+    pub fn new(schema: &SatsSchema) -> Self {
+        let mut structs = 0;
+        let mut enums = 0;
+        let mut well_known = BTreeSet::new();
+        for type_def in &schema.typespace.types {
+            match type_def {
+                TypeDef::Product { Product } => match detect_spacetimedb_type(Product) {
+                    Some(special) => {
+                        well_known.insert(special);
+                    }
+                    None => structs += 1,
+                },
+                TypeDef::Sum { Sum } => match detect_spacetimedb_sum_type(Sum) {
+                    Some(special) => {
+                        well_known.insert(special);
+                    }
+                    None if !is_option_type(Sum) => enums += 1,
+                    None => {}
+                },
+                _ => {}
             }
+            match type_def {
+                TypeDef::Product { Product } => {
+                    for element in &Product.elements {
+                        collect_well_known(&element.algebraic_type, &mut well_known);
+                    }
+                }
+                TypeDef::Sum { Sum } => {
+                    for variant in &Sum.variants {
+                        collect_well_known(&variant.algebraic_type, &mut well_known);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for reducer in &schema.reducers {
+            for element in &reducer.params.elements {
+                collect_well_known(&element.algebraic_type, &mut well_known);
+            }
+        }
 
-            format!("Sum({} variants)", Sum.variants.len())
+        Self {
+            tables: schema.tables.len(),
+            structs,
+            enums,
+            well_known,
+            typespace_entries: schema.typespace.types.len(),
         }
-        AlgebraicType::Product { Product } => {
-            // Check for SpacetimeDB well-known types
-            if let Some(stdb_type) = detect_spacetimedb_type(Product) {
-                return stdb_type;
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "tables": self.tables,
+            "structs": self.structs,
+            "enums": self.enums,
+            "well_known_types": self.well_known.len(),
+            "typespace_entries": self.typespace_entries,
+        })
+    }
+}
+
+/// Well-known types written inline, e.g. a field whose type is the
+/// Identity product itself rather than a ref to it
+fn collect_well_known(alg_type: &AlgebraicType, found: &mut BTreeSet<String>) {
+    match alg_type {
+        AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+            Some(special) => {
+                found.insert(special);
+            }
+            None => {
+                for element in &Product.elements {
+                    collect_well_known(&element.algebraic_type, found);
+                }
+            }
+        },
+        AlgebraicType::Sum { Sum } => match detect_spacetimedb_sum_type(Sum) {
+            Some(special) => {
+                found.insert(special);
+            }
+            None => {
+                for variant in &Sum.variants {
+                    collect_well_known(&variant.algebraic_type, found);
+                }
             }
+        },
+        AlgebraicType::Array { Array } => collect_well_known(Array, found),
+        AlgebraicType::Map { Map } => {
+            collect_well_known(&Map.key_ty, found);
+            collect_well_known(&Map.ty, found);
+        }
+        _ => {}
+    }
+}
 
-            // Handle tuples and named structs
-            if Product.elements.is_empty() {
-                "()".to_string()
-            } else if Product
-                .elements
-                .iter()
-                .all(|e| e.name.as_option().is_none())
-            {
-                // This is a tuple
-                let types: Vec<_> = Product
-                    .elements
+fn print_summary(schema: &SatsSchema) {
+    let stats = SchemaStats::new(schema);
+    outln!("{}", glyphs("📈 SUMMARY").yellow());
+    outln!("  {} tables", stats.tables);
+    outln!("  {} structs", stats.structs);
+    outln!("  {} enums", stats.enums);
+    if stats.well_known.is_empty() {
+        outln!("  0 SpacetimeDB types");
+    } else {
+        outln!(
+            "  {} SpacetimeDB types {}",
+            stats.well_known.len(),
+            format!(
+                "({})",
+                stats
+                    .well_known
                     .iter()
-                    .map(|e| format_type(&e.algebraic_type, type_names))
-                    .collect();
-                format!("({})", types.join(", "))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .dimmed()
+        );
+    }
+    outln!("  {} types total", stats.typespace_entries);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+/// A table or column name that collides with a reserved SpacetimeDB name
+/// or needs quoting or renaming in SQL and generated code
+#[derive(Serialize)]
+struct NameWarning {
+    severity: Severity,
+    table: String,
+    column: Option<String>,
+    message: String,
+    suggestion: String,
+}
+
+fn find_name_warnings(schema: &SatsSchema, extra_keywords: &[String]) -> Vec<NameWarning> {
+    let mut warnings = Vec::new();
+    for table in &schema.tables {
+        if let Some(prefix) = reserved_names::reserved_table_prefix(&table.name) {
+            warnings.push(NameWarning {
+                severity: Severity::Error,
+                table: table.name.clone(),
+                column: None,
+                message: format!("`{prefix}` is the prefix of SpacetimeDB system tables"),
+                suggestion: format!("rename table `{}`", table.name),
+            });
+        }
+        if reserved_names::is_sql_keyword(&table.name, extra_keywords) {
+            warnings.push(NameWarning {
+                severity: Severity::Warning,
+                table: table.name.clone(),
+                column: None,
+                message: format!("`{}` is an SQL keyword", table.name),
+                suggestion: format!("rename table `{}` or always quote it in SQL", table.name),
+            });
+        } else if !identifiers::is_plain_identifier(&table.name) {
+            warnings.push(NameWarning {
+                severity: Severity::Warning,
+                table: table.name.clone(),
+                column: None,
+                message: format!("`{}` is not a plain identifier", table.name),
+                suggestion: format!("rename table `{}` or always quote it in SQL", table.name),
+            });
+        }
+
+        let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+        else {
+            continue;
+        };
+        for column in Product.elements.iter().filter_map(|e| e.name.as_option()) {
+            let message = if reserved_names::is_sql_keyword(column, extra_keywords) {
+                format!("`{column}` is an SQL keyword")
+            } else if reserved_names::is_special_name(column) {
+                format!("`{column}` has a built-in meaning in SpacetimeDB SQL")
+            } else if !identifiers::is_plain_identifier(column) {
+                format!("`{column}` is not a plain identifier")
             } else {
-                // This is a named struct
-                format!("Product({} fields)", Product.elements.len())
+                continue;
+            };
+            warnings.push(NameWarning {
+                severity: Severity::Warning,
+                table: table.name.clone(),
+                column: Some(column.to_string()),
+                message,
+                suggestion: format!("rename column `{column}` or always quote it in SQL"),
+            });
+        }
+    }
+    warnings
+}
+
+fn display_name_warnings(warnings: &[NameWarning]) {
+    outln!("\n{}", glyphs("⚠️  NAMING WARNINGS").bold().cyan());
+    outln!("{}", "=".repeat(60));
+
+    if warnings.is_empty() {
+        outln!("\n{} No table or column names need attention", glyphs("✅").green());
+        return;
+    }
+    outln!();
+    for warning in warnings {
+        print_name_warning(warning, "  ");
+    }
+}
+
+fn print_name_warning(warning: &NameWarning, indent: &str) {
+    let severity = match warning.severity {
+        Severity::Error => "error".red(),
+        Severity::Warning => "warning".yellow(),
+    };
+    let subject = match &warning.column {
+        Some(column) => format!("{}.{}", warning.table, column),
+        None => warning.table.clone(),
+    };
+    outln!(
+        "{indent}{severity} {}: {} ({})",
+        subject.bold(),
+        warning.message,
+        warning.suggestion.dimmed()
+    );
+}
+
+/// Print a table's named fields with their marker flags. With --header the
+/// names, types and flags are aligned under a header row.
+fn print_table_fields(
+    schema: &SatsSchema,
+    table: &TableInfo,
+    product: &ProductType,
+    type_names: &HashMap<usize, String>,
+    options: RenderOptions,
+    marker: ColoredString,
+    indent: &str,
+) -> TypeAbbreviations {
+    let mut abbreviations = TypeAbbreviations::default();
+    let rows: Vec<_> = product
+        .elements
+        .iter()
+        .enumerate()
+        .filter_map(|(i, element)| {
+            let name = element.name.as_option()?;
+            let field_type =
+                render_field_type(schema, &element.algebraic_type, type_names, options);
+            let field_type = abbreviations.shorten(field_type, options);
+            let flags = column_flags(schema, table, i, &element.algebraic_type).join(" ");
+            Some((name, &element.algebraic_type, field_type, flags))
+        })
+        .collect();
+
+    let name_width = rows
+        .iter()
+        .map(|(name, ..)| name.chars().count())
+        .chain(["field".len()])
+        .max()
+        .unwrap_or(0);
+    let type_width = rows
+        .iter()
+        .map(|(_, _, field_type, _)| field_type.chars().count())
+        .chain(["type".len()])
+        .max()
+        .unwrap_or(0);
+
+    if options.header {
+        // The marker is two characters wide in ASCII mode (`|-`)
+        let marker_width = marker.chars().count() + 1;
+        outln!(
+            "{indent}{:marker_width$}{}",
+            "",
+            format!("{:<name_width$}  {:<type_width$}  flags", "field", "type").dimmed()
+        );
+    }
+    for (name, alg_type, field_type, flags) in rows {
+        if options.header {
+            let tail = format!("{flags}{}", unit_note(schema, alg_type, options));
+            let field_type = if tail.is_empty() {
+                field_type
+            } else {
+                format!("{field_type:<type_width$}  ")
+            };
+            outln!(
+                "{indent}{marker} {name:<name_width$}  {}{tail}",
+                color_type(schema, alg_type, &field_type, options)
+            );
+        } else {
+            let flags = if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" {flags}")
+            };
+            outln!(
+                "{indent}{marker} {name}: {}{}{}",
+                color_type(schema, alg_type, &field_type, options),
+                unit_note(schema, alg_type, options),
+                flags
+            );
+        }
+        print_explanation(schema, alg_type, options, indent);
+    }
+    abbreviations
+}
+
+/// The table's primary key columns by name; a column without a name, or
+/// an index past the end of the row type, is shown as its index
+pub fn primary_key_names(schema: &SatsSchema, table: &TableInfo) -> Vec<String> {
+    let elements = match schema.typespace.types.get(table.product_type_ref) {
+        Some(TypeDef::Product { Product }) => Product.elements.as_slice(),
+        _ => &[],
+    };
+    table
+        .primary_key
+        .iter()
+        .map(|&i| {
+            elements
+                .get(i)
+                .and_then(|e| e.name.as_option())
+                .map_or_else(|| i.to_string(), str::to_string)
+        })
+        .collect()
+}
+
+/// Markers shown after a table column: 🔑 primary key, `auto_inc` for a
+/// sequence, `?` for an Option, ⚡ for a SpacetimeDB well-known type
+fn column_flags(
+    schema: &SatsSchema,
+    table: &TableInfo,
+    column: usize,
+    alg_type: &AlgebraicType,
+) -> Vec<Cow<'static, str>> {
+    let mut flags = Vec::new();
+    if table.primary_key.contains(&column) {
+        flags.push(glyphs("🔑"));
+    }
+    if table.sequences.iter().any(|s| s.column == column) {
+        flags.push("auto_inc".into());
+    }
+
+    let (product, sum) = match alg_type {
+        AlgebraicType::Ref { Ref } => match schema.typespace.types.get(*Ref as usize) {
+            Some(TypeDef::Product { Product }) => (Some(Product), None),
+            Some(TypeDef::Sum { Sum }) => (None, Some(Sum)),
+            _ => (None, None),
+        },
+        AlgebraicType::Product { Product } => (Some(Product), None),
+        AlgebraicType::Sum { Sum } => (None, Some(Sum)),
+        _ => (None, None),
+    };
+    if product.is_some_and(|p| detect_spacetimedb_type(p).is_some())
+        || sum.is_some_and(|s| detect_spacetimedb_sum_type(s).is_some())
+    {
+        flags.push(glyphs("⚡"));
+    } else if sum.is_some_and(is_option_type) {
+        flags.push("?".into());
+    }
+    flags
+}
+
+/// One-line explanation of the field markers: with --legend, or once on the
+/// very first run
+fn print_marker_legend(options: RenderOptions) {
+    if !options.legend && !take_first_run_legend() {
+        return;
+    }
+    let hint = if options.legend {
+        ""
+    } else {
+        "  (--legend shows this again)"
+    };
+    outln!(
+        "\n{} {}{}",
+        "Markers:".dimmed(),
+        glyphs("🔑 primary key  auto_inc sequence  ? nullable (Option)  ⚡ SpacetimeDB type"),
+        hint.dimmed()
+    );
+}
+
+/// True the first time this is called on this machine. The state file is
+/// best effort: if it can't be written, the legend is just shown again.
+fn take_first_run_legend() -> bool {
+    let Some(path) = crate::history::state_dir().map(|dir| dir.join("legend-shown")) else {
+        return false;
+    };
+    if path.exists() {
+        return false;
+    }
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(&path, "");
+    true
+}
+
+fn table_field_count(schema: &SatsSchema, table: &TableInfo) -> usize {
+    match schema.typespace.types.get(table.product_type_ref) {
+        Some(TypeDef::Product { Product }) => Product.elements.len(),
+        _ => 0,
+    }
+}
+
+pub fn build_type_names(schema: &SatsSchema) -> HashMap<usize, String> {
+    let mut type_names = HashMap::new();
+    for named_type in &schema.types {
+        type_names.insert(named_type.ty, named_type.name.name.clone());
+    }
+    type_names
+}
+
+/// Push every typespace index `alg_type` refers to, looking inside
+/// arrays, maps, options and inline products and sums. Refs are not
+/// followed, so each index is a direct reference.
+pub fn collect_refs(alg_type: &AlgebraicType, refs: &mut Vec<usize>) {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => refs.push(*Ref as usize),
+        AlgebraicType::Array { Array } => collect_refs(Array, refs),
+        AlgebraicType::Map { Map } => {
+            collect_refs(&Map.key_ty, refs);
+            collect_refs(&Map.ty, refs);
+        }
+        AlgebraicType::Product { Product } => {
+            for element in &Product.elements {
+                collect_refs(&element.algebraic_type, refs);
             }
         }
+        AlgebraicType::Sum { Sum } => {
+            for variant in &Sum.variants {
+                collect_refs(&variant.algebraic_type, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `collect_refs` over a typespace entry
+pub fn collect_def_refs(type_def: &TypeDef, refs: &mut Vec<usize>) {
+    match type_def {
+        TypeDef::Product { Product } => {
+            for element in &Product.elements {
+                collect_refs(&element.algebraic_type, refs);
+            }
+        }
+        TypeDef::Sum { Sum } => {
+            for variant in &Sum.variants {
+                collect_refs(&variant.algebraic_type, refs);
+            }
+        }
+        TypeDef::Builtin {
+            Builtin: BuiltinType::Array { Array },
+        } => collect_refs(Array, refs),
+        TypeDef::Builtin {
+            Builtin: BuiltinType::Map { Map },
+        } => {
+            collect_refs(&Map.key_ty, refs);
+            collect_refs(&Map.ty, refs);
+        }
+        TypeDef::Builtin { .. } => {}
+        TypeDef::Ref { Ref } => refs.push(*Ref as usize),
+    }
+}
+
+/// Name including its module scope, e.g. `math::Vec2`
+pub fn qualified_type_name(name: &TypeName) -> String {
+    name.scope
+        .iter()
+        .chain(std::iter::once(&name.name))
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+pub fn format_type(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+) -> String {
+    TypeFormatter {
+        schema,
+        type_names,
+        expanding: Vec::new(),
+    }
+    .format(alg_type)
+}
+
+/// `format_type`'s state: the unnamed typespace entries being written out
+/// in place, so a ref cycle through them ends in `Type_N`
+struct TypeFormatter<'a> {
+    schema: &'a SatsSchema,
+    type_names: &'a HashMap<usize, String>,
+    expanding: Vec<usize>,
+}
+
+impl TypeFormatter<'_> {
+    fn format(&mut self, alg_type: &AlgebraicType) -> String {
+        match alg_type {
+            AlgebraicType::Bool { .. } => "bool".to_string(),
+            AlgebraicType::I8 { .. } => "i8".to_string(),
+            AlgebraicType::U8 { .. } => "u8".to_string(),
+            AlgebraicType::I16 { .. } => "i16".to_string(),
+            AlgebraicType::U16 { .. } => "u16".to_string(),
+            AlgebraicType::I32 { .. } => "i32".to_string(),
+            AlgebraicType::U32 { .. } => "u32".to_string(),
+            AlgebraicType::I64 { .. } => "i64".to_string(),
+            AlgebraicType::U64 { .. } => "u64".to_string(),
+            AlgebraicType::I128 { .. } => "i128".to_string(),
+            AlgebraicType::U128 { .. } => "u128".to_string(),
+            AlgebraicType::I256 { .. } => "i256".to_string(),
+            AlgebraicType::U256 { .. } => "u256".to_string(),
+            AlgebraicType::F32 { .. } => "f32".to_string(),
+            AlgebraicType::F64 { .. } => "f64".to_string(),
+            AlgebraicType::String { .. } => "String".to_string(),
+            AlgebraicType::Array { Array } => format!("Vec<{}>", self.format(Array)),
+            AlgebraicType::Map { Map } => {
+                format!(
+                    "HashMap<{}, {}>",
+                    self.format(&Map.key_ty),
+                    self.format(&Map.ty)
+                )
+            }
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize),
+            AlgebraicType::Sum { Sum } => self.sum(Sum),
+            AlgebraicType::Product { Product } => self.product(Product),
+        }
+    }
+
+    /// The name of a named entry; an unnamed one is written out in place
+    /// (`(i32, String)`, `Vec<u8>`, ...)
+    fn reference(&mut self, idx: usize) -> String {
+        if let Some(name) = self.type_names.get(&idx) {
+            return name.clone();
+        }
+        let schema = self.schema;
+        let Some(type_def) = schema.typespace.types.get(idx) else {
+            return format!("Type_{idx}");
+        };
+        if self.expanding.contains(&idx) {
+            return format!("Type_{idx}");
+        }
+
+        self.expanding.push(idx);
+        let formatted = match type_def {
+            TypeDef::Product { Product } => self.product(Product),
+            TypeDef::Sum { Sum } => self.sum(Sum),
+            TypeDef::Builtin { Builtin } => match Builtin {
+                BuiltinType::Bool { .. } => "bool".to_string(),
+                BuiltinType::I8 { .. } => "i8".to_string(),
+                BuiltinType::U8 { .. } => "u8".to_string(),
+                BuiltinType::I16 { .. } => "i16".to_string(),
+                BuiltinType::U16 { .. } => "u16".to_string(),
+                BuiltinType::I32 { .. } => "i32".to_string(),
+                BuiltinType::U32 { .. } => "u32".to_string(),
+                BuiltinType::I64 { .. } => "i64".to_string(),
+                BuiltinType::U64 { .. } => "u64".to_string(),
+                BuiltinType::I128 { .. } => "i128".to_string(),
+                BuiltinType::U128 { .. } => "u128".to_string(),
+                BuiltinType::I256 { .. } => "i256".to_string(),
+                BuiltinType::U256 { .. } => "u256".to_string(),
+                BuiltinType::F32 { .. } => "f32".to_string(),
+                BuiltinType::F64 { .. } => "f64".to_string(),
+                BuiltinType::String { .. } => "String".to_string(),
+                BuiltinType::Array { Array } => format!("Vec<{}>", self.format(Array)),
+                BuiltinType::Map { Map } => {
+                    format!(
+                        "HashMap<{}, {}>",
+                        self.format(&Map.key_ty),
+                        self.format(&Map.ty)
+                    )
+                }
+            },
+            TypeDef::Ref { Ref } => self.reference(*Ref as usize),
+        };
+        self.expanding.pop();
+        formatted
+    }
+
+    fn sum(&mut self, sum: &SumType) -> String {
+        // Check if this is a SpacetimeDB ScheduledAt pattern
+        if let Some(stdb_type) = detect_spacetimedb_sum_type(sum) {
+            return stdb_type;
+        }
+
+        // Check if this is an Option<T> pattern
+        if is_option_type(sum) {
+            if let Some(inner_type) = get_option_inner_type(sum) {
+                return format!("Option<{}>", self.format(inner_type));
+            }
+            return "Option<?>".to_string();
+        }
+
+        format!("Sum({} variants)", sum.variants.len())
+    }
+
+    fn product(&mut self, product: &ProductType) -> String {
+        // Check for SpacetimeDB well-known types
+        if let Some(stdb_type) = detect_spacetimedb_type(product) {
+            return stdb_type;
+        }
+
+        // Handle tuples and named structs
+        if product.elements.is_empty() {
+            "()".to_string()
+        } else if product
+            .elements
+            .iter()
+            .all(|e| e.name.as_option().is_none())
+        {
+            // This is a tuple
+            let types: Vec<_> = product
+                .elements
+                .iter()
+                .map(|e| self.format(&e.algebraic_type))
+                .collect();
+            format!("({})", types.join(", "))
+        } else {
+            // This is a named struct
+            format!("Product({} fields)", product.elements.len())
+        }
     }
 }
 
 // Helper functions for type detection
-fn detect_spacetimedb_type(product: &ProductType) -> Option<String> {
+pub fn detect_spacetimedb_type(product: &ProductType) -> Option<String> {
     // Check for single-field products with special names (SpacetimeDB well-known types)
     if product.elements.len() == 1 {
         let element = &product.elements[0];
@@ -508,7 +1993,7 @@ fn detect_spacetimedb_type(product: &ProductType) -> Option<String> {
     None
 }
 
-fn detect_spacetimedb_sum_type(sum: &SumType) -> Option<String> {
+pub fn detect_spacetimedb_sum_type(sum: &SumType) -> Option<String> {
     // Check for SpacetimeDB ScheduledAt pattern
     if sum.variants.len() == 2 {
         let variant_names: Vec<_> = sum
@@ -526,7 +2011,509 @@ fn detect_spacetimedb_sum_type(sum: &SumType) -> Option<String> {
     None
 }
 
-fn is_option_type(sum: &SumType) -> bool {
+/// Type of a field or variant payload as shown in the pretty views
+fn render_field_type(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+    options: RenderOptions,
+) -> String {
+    if options.expand_all {
+        expand_type(schema, alg_type, type_names)
+    } else {
+        format_type(schema, alg_type, type_names)
+    }
+}
+
+/// Like `format_type`, but with every referenced struct and enum written out
+/// inline. A ref back to a type that is already being expanded (a
+/// self-referencing type like `Node { next: Option<Node> }`) is rendered by
+/// name only, so recursive types terminate.
+pub fn expand_type(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+) -> String {
+    let mut expander = TypeExpander {
+        schema,
+        type_names,
+        expanding: HashSet::new(),
+        nodes: 0,
+    };
+    let expanded = expander.expand(alg_type);
+    if expander.nodes > EXPAND_NODE_LIMIT {
+        format!(
+            "{expanded} {} (expansion stopped after {EXPAND_NODE_LIMIT} types)",
+            glyphs("…")
+        )
+    } else {
+        expanded
+    }
+}
+
+/// Upper bound on types inlined into a single field by --expand-all
+const EXPAND_NODE_LIMIT: usize = 500;
+
+/// Inlines every referenced type for `expand_type`
+struct TypeExpander<'a> {
+    schema: &'a SatsSchema,
+    type_names: &'a HashMap<usize, String>,
+    /// Refs being expanded further up; meeting one again means a cycle
+    expanding: HashSet<usize>,
+    nodes: usize,
+}
+
+impl TypeExpander<'_> {
+    fn expand(&mut self, alg_type: &AlgebraicType) -> String {
+        self.nodes += 1;
+        if self.nodes > EXPAND_NODE_LIMIT {
+            return format_type(self.schema, alg_type, self.type_names);
+        }
+
+        match alg_type {
+            AlgebraicType::Array { Array } => format!("Vec<{}>", self.expand(Array)),
+            AlgebraicType::Map { Map } => {
+                format!(
+                    "HashMap<{}, {}>",
+                    self.expand(&Map.key_ty),
+                    self.expand(&Map.ty)
+                )
+            }
+            AlgebraicType::Ref { Ref } => self.expand_ref(*Ref as usize),
+            AlgebraicType::Product { Product } => self.expand_product(Product),
+            AlgebraicType::Sum { Sum } => self.expand_sum(Sum),
+            _ => format_type(self.schema, alg_type, self.type_names),
+        }
+    }
+
+    fn expand_ref(&mut self, idx: usize) -> String {
+        let name = self.type_names.get(&idx);
+        let display_name = name.cloned().unwrap_or_else(|| format!("Type_{idx}"));
+        if !self.expanding.insert(idx) {
+            return display_name;
+        }
+
+        let body = match self.schema.typespace.types.get(idx) {
+            Some(TypeDef::Product { Product }) => self.expand_product(Product),
+            Some(TypeDef::Sum { Sum }) => self.expand_sum(Sum),
+            _ => display_name.clone(),
+        };
+        self.expanding.remove(&idx);
+
+        match name {
+            // Well-known types and options already read as their own name
+            Some(name) if body.starts_with(['{', '(']) => format!("{name} {body}"),
+            _ => body,
+        }
+    }
+
+    fn expand_product(&mut self, product: &ProductType) -> String {
+        if let Some(special) = detect_spacetimedb_type(product) {
+            return special;
+        }
+        if product.elements.is_empty() {
+            return "()".to_string();
+        }
+
+        let fields: Vec<_> = product
+            .elements
+            .iter()
+            .map(|e| {
+                let ty = self.expand(&e.algebraic_type);
+                match e.name.as_option() {
+                    Some(name) => format!("{name}: {ty}"),
+                    None => ty,
+                }
+            })
+            .collect();
+
+        if product
+            .elements
+            .iter()
+            .all(|e| e.name.as_option().is_none())
+        {
+            format!("({})", fields.join(", "))
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        }
+    }
+
+    fn expand_sum(&mut self, sum: &SumType) -> String {
+        if let Some(special) = detect_spacetimedb_sum_type(sum) {
+            return special;
+        }
+        if is_option_type(sum) {
+            return match get_option_inner_type(sum) {
+                Some(inner) => format!("Option<{}>", self.expand(inner)),
+                None => "Option<?>".to_string(),
+            };
+        }
+
+        let variants: Vec<_> = sum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = v
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                match &v.algebraic_type {
+                    AlgebraicType::Product { Product } if Product.elements.is_empty() => name,
+                    payload => format!("{name}({})", self.expand(payload)),
+                }
+            })
+            .collect();
+        format!("{{ {} }}", variants.join(" | "))
+    }
+}
+
+/// Field types at least this long are replaced by a placeholder with --abbreviate-types
+const ABBREVIATE_MIN_LEN: usize = 32;
+
+/// Placeholders (`T1`, `T2`, ...) for long field types within one table,
+/// printed as a legend after the table's fields
+#[derive(Default)]
+struct TypeAbbreviations {
+    expressions: Vec<String>,
+}
+
+impl TypeAbbreviations {
+    fn shorten(&mut self, rendered: String, options: RenderOptions) -> String {
+        if !options.abbreviate_types || rendered.chars().count() < ABBREVIATE_MIN_LEN {
+            return rendered;
+        }
+
+        let index = match self.expressions.iter().position(|e| *e == rendered) {
+            Some(index) => index,
+            None => {
+                self.expressions.push(rendered);
+                self.expressions.len() - 1
+            }
+        };
+        format!("T{}", index + 1)
+    }
+
+    fn print(&self, indent: &str) {
+        for (i, expression) in self.expressions.iter().enumerate() {
+            outln!(
+                "{indent}{} {}",
+                format!("T{} =", i + 1).dimmed(),
+                expression.cyan()
+            );
+        }
+    }
+}
+
+/// Broad kind of a field type, used to pick its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeCategory {
+    Primitive,
+    Collection,
+    Option,
+    UserType,
+    WellKnown,
+}
+
+impl TypeCategory {
+    const ALL: [TypeCategory; 5] = [
+        TypeCategory::Primitive,
+        TypeCategory::Collection,
+        TypeCategory::Option,
+        TypeCategory::UserType,
+        TypeCategory::WellKnown,
+    ];
+
+    fn of(schema: &SatsSchema, alg_type: &AlgebraicType) -> Self {
+        match alg_type {
+            AlgebraicType::Array { .. } | AlgebraicType::Map { .. } => TypeCategory::Collection,
+            AlgebraicType::Product { Product } if detect_spacetimedb_type(Product).is_some() => {
+                TypeCategory::WellKnown
+            }
+            AlgebraicType::Sum { Sum } if detect_spacetimedb_sum_type(Sum).is_some() => {
+                TypeCategory::WellKnown
+            }
+            AlgebraicType::Sum { Sum } if is_option_type(Sum) => TypeCategory::Option,
+            AlgebraicType::Product { .. } | AlgebraicType::Sum { .. } => TypeCategory::UserType,
+            AlgebraicType::Ref { Ref } => match schema.typespace.types.get(*Ref as usize) {
+                Some(TypeDef::Product { Product })
+                    if detect_spacetimedb_type(Product).is_some() =>
+                {
+                    TypeCategory::WellKnown
+                }
+                Some(TypeDef::Sum { Sum }) if detect_spacetimedb_sum_type(Sum).is_some() => {
+                    TypeCategory::WellKnown
+                }
+                _ => TypeCategory::UserType,
+            },
+            _ => TypeCategory::Primitive,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TypeCategory::Primitive => "primitive",
+            TypeCategory::Collection => "collection",
+            TypeCategory::Option => "option",
+            TypeCategory::UserType => "user type",
+            TypeCategory::WellKnown => "SpacetimeDB type",
+        }
+    }
+
+    fn paint(self, text: &str) -> ColoredString {
+        match self {
+            TypeCategory::Primitive => text.green(),
+            TypeCategory::Collection => text.blue(),
+            TypeCategory::Option => text.yellow(),
+            TypeCategory::UserType => text.magenta(),
+            TypeCategory::WellKnown => text.cyan(),
+        }
+    }
+}
+
+/// Color a rendered type: by category with --color-by-type-category,
+/// otherwise the default cyan
+fn color_type(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    rendered: &str,
+    options: RenderOptions,
+) -> ColoredString {
+    if options.color_by_category {
+        TypeCategory::of(schema, alg_type).paint(rendered)
+    } else {
+        rendered.cyan()
+    }
+}
+
+fn print_color_legend() {
+    let entries: Vec<_> = TypeCategory::ALL
+        .iter()
+        .map(|c| c.paint(c.label()).to_string())
+        .collect();
+    outln!("\n{} {}", "Legend:".dimmed(), entries.join("  "));
+}
+
+/// With --explain, print why a field's type was labeled the way it was
+fn print_explanation(
+    schema: &SatsSchema,
+    alg_type: &AlgebraicType,
+    options: RenderOptions,
+    indent: &str,
+) {
+    if !options.explain {
+        return;
+    }
+    if let Some(reason) = explain_type(schema, alg_type) {
+        outln!("{indent}  {}", format!("{} {reason}", glyphs("↳")).dimmed());
+    }
+}
+
+/// Describe the heuristic behind a type's label, for the interesting cases
+fn explain_type(schema: &SatsSchema, alg_type: &AlgebraicType) -> Option<String> {
+    match alg_type {
+        AlgebraicType::Array { Array } => explain_type(schema, Array)
+            .map(|inner| format!("Array shown as Vec<T>; element {inner}")),
+        AlgebraicType::Product { Product } => explain_product(Product),
+        AlgebraicType::Sum { Sum } => explain_sum(Sum),
+        AlgebraicType::Ref { Ref } => {
+            let idx = *Ref as usize;
+            let named = schema.types.iter().find(|t| t.ty == idx);
+            let detail = match schema.typespace.types.get(idx) {
+                Some(TypeDef::Product { Product }) => explain_product(Product),
+                Some(TypeDef::Sum { Sum }) => explain_sum(Sum),
+                _ => None,
+            };
+            match (named, detail) {
+                (_, Some(detail)) => Some(format!("Ref {idx}: {detail}")),
+                (Some(named), None) => Some(format!(
+                    "Ref {idx} resolved to '{}' via the types list",
+                    named.name.name
+                )),
+                (None, None) => Some(format!(
+                    "Ref {idx} has no entry in the types list, so it is shown as Type_{idx}"
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn explain_product(product: &ProductType) -> Option<String> {
+    if let Some(special) = detect_spacetimedb_type(product) {
+        let field = product.elements[0].name.as_option().unwrap_or_default();
+        let inner = match special.as_str() {
+            "Identity" => "U256",
+            _ => "I64",
+        };
+        return Some(format!(
+            "detected as {special}: single {inner} field named {field}"
+        ));
+    }
+    if product.elements.is_empty() {
+        return Some("product with no elements, shown as the unit type ()".to_string());
+    }
+    if product
+        .elements
+        .iter()
+        .all(|e| e.name.as_option().is_none())
+    {
+        return Some("product with only unnamed elements, shown as a tuple".to_string());
+    }
+    None
+}
+
+fn explain_sum(sum: &SumType) -> Option<String> {
+    if detect_spacetimedb_sum_type(sum).is_some() {
+        return Some(
+            "detected as ScheduledAt: 2-variant sum with variants named Interval and Time"
+                .to_string(),
+        );
+    }
+    if is_option_type(sum) {
+        let names: Vec<_> = sum
+            .variants
+            .iter()
+            .filter_map(|v| v.name.as_option())
+            .collect();
+        let reason = if names.contains(&"Some") && names.contains(&"None") {
+            "2-variant sum with variants named Some and None"
+        } else {
+            "2-variant sum with one unit variant and one variant carrying data"
+        };
+        return Some(format!("detected as Option<T>: {reason}"));
+    }
+    None
+}
+
+/// Clarifying note for the well-known temporal types, e.g. " (i64 µs since epoch)"
+fn unit_note(schema: &SatsSchema, alg_type: &AlgebraicType, options: RenderOptions) -> String {
+    if !options.annotate_units {
+        return String::new();
+    }
+    match unit_label(schema, alg_type) {
+        Some(label) => format!(" {}", glyphs(label).dimmed()),
+        None => String::new(),
+    }
+}
+
+/// The unit of a Timestamp or Duration field, `(i64 µs since epoch)` or
+/// `(i64 µs)`, for `--annotate-units`
+pub fn unit_label(schema: &SatsSchema, alg_type: &AlgebraicType) -> Option<&'static str> {
+    let special = match alg_type {
+        AlgebraicType::Product { Product } => detect_spacetimedb_type(Product),
+        AlgebraicType::Ref { Ref } => match schema.typespace.types.get(*Ref as usize) {
+            Some(TypeDef::Product { Product }) => detect_spacetimedb_type(Product),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match special.as_deref() {
+        Some("Timestamp") => Some("(i64 µs since epoch)"),
+        Some("Duration") => Some("(i64 µs)"),
+        _ => None,
+    }
+}
+
+// Scheduled tables and the reducers that process them
+struct ScheduleLink<'a> {
+    /// Reducer named by the table's schedule metadata, if any
+    declared: Option<&'a str>,
+    /// Matching reducer found in the module's reducer list
+    reducer: Option<&'a ReducerDef>,
+}
+
+fn find_schedule_link<'a>(
+    schema: &'a SatsSchema,
+    table: &'a TableInfo,
+) -> Option<ScheduleLink<'a>> {
+    let declared = table
+        .schedule
+        .as_ref()
+        .and_then(|s| s.as_option())
+        .map(|s| s.reducer_name.as_str());
+
+    if declared.is_none() && find_scheduled_at_column(schema, table).is_none() {
+        return None;
+    }
+
+    let reducer = match declared {
+        Some(name) => schema.reducers.iter().find(|r| r.name == name),
+        // Without metadata, fall back to the convention that a scheduled
+        // reducer takes the table's row type as its argument
+        None => schema.reducers.iter().find(|r| {
+            r.params.elements.iter().any(|p| {
+                matches!(p.algebraic_type, AlgebraicType::Ref { Ref } if Ref as usize == table.product_type_ref)
+            })
+        }),
+    };
+
+    Some(ScheduleLink { declared, reducer })
+}
+
+fn find_scheduled_at_column(schema: &SatsSchema, table: &TableInfo) -> Option<usize> {
+    let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+    else {
+        return None;
+    };
+
+    // Prefer the column named by the schedule metadata
+    if let Some(schedule) = table.schedule.as_ref().and_then(|s| s.as_option()) {
+        if schedule.scheduled_at_column < Product.elements.len() {
+            return Some(schedule.scheduled_at_column);
+        }
+    }
+
+    Product
+        .elements
+        .iter()
+        .position(|e| match &e.algebraic_type {
+            AlgebraicType::Sum { Sum } => detect_spacetimedb_sum_type(Sum).is_some(),
+            AlgebraicType::Ref { Ref } => matches!(
+                schema.typespace.types.get(*Ref as usize),
+                Some(TypeDef::Sum { Sum }) if detect_spacetimedb_sum_type(Sum).is_some()
+            ),
+            _ => false,
+        })
+}
+
+fn print_schedule_link(schema: &SatsSchema, table: &TableInfo, indent: &str) {
+    let Some(link) = find_schedule_link(schema, table) else {
+        return;
+    };
+
+    match (link.reducer, link.declared) {
+        (Some(reducer), _) => outln!(
+            "{indent}{} scheduled by reducer: {}",
+            glyphs("⏰").yellow(),
+            reducer.name.bold()
+        ),
+        (None, Some(name)) => outln!(
+            "{indent}{} scheduled by reducer '{}', but no such reducer exists",
+            glyphs("⚠️").yellow(),
+            name
+        ),
+        (None, None) => outln!(
+            "{indent}{} scheduled table has no matching reducer",
+            glyphs("⚠️").yellow()
+        ),
+    }
+}
+
+/// Whether a field of this type is an `Option`, directly or through a ref
+pub fn is_nullable(schema: &SatsSchema, alg_type: &AlgebraicType) -> bool {
+    match alg_type {
+        AlgebraicType::Sum { Sum } => is_option_type(Sum),
+        AlgebraicType::Ref { Ref } => matches!(
+            schema.typespace.types.get(*Ref as usize),
+            Some(TypeDef::Sum { Sum }) if is_option_type(Sum)
+        ),
+        _ => false,
+    }
+}
+
+pub fn is_option_type(sum: &SumType) -> bool {
     if sum.variants.len() != 2 {
         return false;
     }
@@ -565,7 +2552,7 @@ fn is_option_type(sum: &SumType) -> bool {
     has_unit_variant && has_data_variant
 }
 
-fn get_option_inner_type(sum: &SumType) -> Option<&AlgebraicType> {
+pub fn get_option_inner_type(sum: &SumType) -> Option<&AlgebraicType> {
     for variant in &sum.variants {
         if let Some(name) = variant.name.as_option() {
             if name == "Some" {
@@ -602,6 +2589,8 @@ fn display_single_table(
     schema: &SatsSchema,
     type_names: &HashMap<usize, String>,
     table_name: &str,
+    options: RenderOptions,
+    commands: Option<&SpacetimeCommands>,
 ) {
     let table = schema
         .tables
@@ -609,40 +2598,438 @@ fn display_single_table(
         .find(|t| t.name.eq_ignore_ascii_case(table_name));
 
     if let Some(table) = table {
-        println!("\n{} {}", "📊 TABLE:".yellow(), table.name.bold());
-        println!("{}", "-".repeat(40));
+        outln!("\n{} {}", glyphs("📊 TABLE:").yellow(), table.name.bold());
+        outln!("{}", "-".repeat(40));
 
         let type_name = type_names
             .get(&table.product_type_ref)
             .cloned()
             .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
-        println!("Type: {}", type_name.dimmed());
+        outln!("Type: {}", type_name.dimmed());
 
         if let Some(TypeDef::Product { Product }) =
             schema.typespace.types.get(table.product_type_ref)
         {
-            println!("\nFields ({}):", Product.elements.len());
-            for element in &Product.elements {
-                if let Some(field_name) = element.name.as_option() {
-                    let field_type = format_type(&element.algebraic_type, type_names);
-                    println!("  {} {}: {}", "▸".green(), field_name, field_type.cyan());
-                }
+            outln!("\nFields ({}):", Product.elements.len());
+            let abbreviations = print_table_fields(
+                schema,
+                table,
+                Product,
+                type_names,
+                options,
+                glyphs("▸").green(),
+                "  ",
+            );
+            if !abbreviations.expressions.is_empty() {
+                outln!();
+                abbreviations.print("  ");
             }
         }
 
         if !table.primary_key.is_empty() {
-            println!("\nPrimary Key: {:?}", table.primary_key);
+            outln!(
+                "\nPrimary Key: {}",
+                primary_key_names(schema, table).join(", ")
+            );
+        }
+
+        if find_schedule_link(schema, table).is_some() {
+            outln!();
+            print_schedule_link(schema, table, "");
+        }
+
+        if let Some(commands) = commands {
+            let columns: Vec<_> = match schema.typespace.types.get(table.product_type_ref) {
+                Some(TypeDef::Product { Product }) => Product
+                    .elements
+                    .iter()
+                    .filter_map(|e| e.name.as_option())
+                    .collect(),
+                _ => Vec::new(),
+            };
+            outln!("\nCommands:");
+            outln!("  $ {}", commands.sql_sample(&table.name, &columns));
+            outln!("  $ {}", commands.subscribe(&table.name));
+        }
+    } else {
+        outln!("{} Table '{}' not found", glyphs("❌").red(), table_name);
+        outln!("\nAvailable tables:");
+        for t in &schema.tables {
+            outln!("  - {}", t.name);
         }
+    }
+}
+
+fn display_single_reducer(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    reducer_name: &str,
+    commands: Option<&SpacetimeCommands>,
+) {
+    let Some(reducer) = schema
+        .reducers
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(reducer_name))
+    else {
+        outln!(
+            "{} Reducer '{}' not found",
+            glyphs("❌").red(),
+            reducer_name
+        );
+        outln!("\nAvailable reducers:");
+        for r in &schema.reducers {
+            outln!("  - {}", r.name);
+        }
+        return;
+    };
+
+    outln!(
+        "\n{} {}",
+        glyphs("⚡ REDUCER:").yellow(),
+        reducer.name.bold()
+    );
+    outln!("{}", "-".repeat(40));
+
+    if let Some(lifecycle) = reducer.lifecycle() {
+        outln!("Lifecycle: {}", lifecycle.label().yellow());
+    }
+
+    outln!("\nParameters ({}):", reducer.params.elements.len());
+    for (i, param) in reducer.params.elements.iter().enumerate() {
+        let param_name = param
+            .name
+            .as_option()
+            .map_or_else(|| i.to_string(), str::to_string);
+        let param_type = format_type(schema, &param.algebraic_type, type_names);
+        outln!(
+            "  {} {}: {}",
+            glyphs("▸").green(),
+            param_name,
+            param_type.cyan()
+        );
+    }
+
+    let scheduled_tables: Vec<_> = schema
+        .tables
+        .iter()
+        .filter(|t| {
+            find_schedule_link(schema, t)
+                .and_then(|link| link.reducer)
+                .is_some_and(|r| std::ptr::eq(r, reducer))
+        })
+        .map(|t| t.name.as_str())
+        .collect();
+    if !scheduled_tables.is_empty() {
+        outln!("\nScheduled by: {}", scheduled_tables.join(", "));
+    }
+
+    if let Some(commands) = commands {
+        outln!("\nCommands:");
+        outln!("  $ {}", commands.call(schema, reducer));
+    }
+}
+
+/// Which token requests to `url` use: the one given, the one `spacetime
+/// login` saved when it is for this server, or none
+fn display_whoami(url: &str, token_given: bool) -> Result<()> {
+    use crate::spacetime_client::{same_host, stored_token, token_mismatch};
+
+    outln!("Server: {url}");
+    if token_given {
+        outln!("Token:  from --token or SPACETIME_TOKEN");
+        return Ok(());
+    }
+    match stored_token()? {
+        Some(stored) if same_host(&stored.url, url) => outln!(
+            "Token:  saved by spacetime login for {} ({})",
+            stored.server,
+            stored.url
+        ),
+        Some(stored) => {
+            outln!("Token:  none; requests are anonymous");
+            outln!("{} {}", glyphs("⚠️").yellow(), token_mismatch(&stored, url));
+        }
+        None => outln!("Token:  none; requests are anonymous"),
+    }
+    Ok(())
+}
+
+// Side-by-side view of one table in two databases
+fn display_table_comparison(
+    left: (&str, &SatsSchema),
+    right: (&str, &SatsSchema),
+    table_name: &str,
+) {
+    let width = terminal_width().max(40);
+    let column_width = (width - 3) / 2;
+
+    let left_table = table_fields(left.1, table_name);
+    let right_table = table_fields(right.1, table_name);
+
+    let print_row = |l: String, r: String| outln!("{l} {} {r}", glyphs("│").dimmed());
+    let header = |db: &str, table: &Option<(String, Vec<(String, String)>)>| match table {
+        Some((name, _)) => fit_column(&format!("{db}: {name}"), column_width)
+            .bold()
+            .to_string(),
+        None => fit_column(&format!("{db}: (table not found)"), column_width)
+            .red()
+            .to_string(),
+    };
+
+    outln!(
+        "\n{} {}",
+        glyphs("📊 COMPARE TABLE:").yellow(),
+        table_name.bold()
+    );
+    print_row(header(left.0, &left_table), header(right.0, &right_table));
+    outln!("{}", "-".repeat(column_width * 2 + 3));
+
+    let left_fields = left_table.map(|(_, fields)| fields).unwrap_or_default();
+    let right_fields = right_table.map(|(_, fields)| fields).unwrap_or_default();
+    let cell = |(name, ty): &(String, String)| fit_column(&format!("{name}: {ty}"), column_width);
+    let blank = || " ".repeat(column_width);
+
+    for row in match_fields(&left_fields, &right_fields) {
+        match row {
+            FieldMatch::Same(l, r) if l.1 == r.1 => print_row(cell(l), cell(r)),
+            FieldMatch::Same(l, r) => {
+                print_row(cell(l).red().to_string(), cell(r).red().to_string())
+            }
+            FieldMatch::Renamed(l, r) => {
+                let renamed = fit_column(
+                    &format!("{}: {} (renamed from {})", r.0, r.1, l.0),
+                    column_width,
+                );
+                print_row(cell(l).cyan().to_string(), renamed.cyan().to_string());
+            }
+            FieldMatch::Only(Some(l), Some(r)) => {
+                print_row(cell(l).yellow().to_string(), cell(r).yellow().to_string())
+            }
+            FieldMatch::Only(Some(l), None) => print_row(cell(l).yellow().to_string(), blank()),
+            FieldMatch::Only(None, Some(r)) => print_row(blank(), cell(r).yellow().to_string()),
+            FieldMatch::Only(None, None) => {}
+        }
+    }
+}
+
+/// One row of a field-by-field table comparison
+#[derive(Debug, PartialEq)]
+pub enum FieldMatch<'a> {
+    /// The same field name on both sides, possibly with different types
+    Same(&'a (String, String), &'a (String, String)),
+    /// A field missing on the right paired with one missing on the left,
+    /// of the same type and with a similar name or position
+    Renamed(&'a (String, String), &'a (String, String)),
+    /// Fields present on one side only, listed side by side so each
+    /// column keeps its own order
+    Only(Option<&'a (String, String)>, Option<&'a (String, String)>),
+}
+
+/// Line up two tables' `(name, type)` fields: fields with the same name in
+/// the left table's order, then likely renames, then what is left on
+/// either side
+pub fn match_fields<'a>(
+    left: &'a [(String, String)],
+    right: &'a [(String, String)],
+) -> Vec<FieldMatch<'a>> {
+    let mut rows = Vec::new();
+    let mut left_only = Vec::new();
+    for (i, l) in left.iter().enumerate() {
+        match right.iter().find(|r| r.0 == l.0) {
+            Some(r) => rows.push(FieldMatch::Same(l, r)),
+            None => left_only.push((i, l)),
+        }
+    }
+    let mut right_only: Vec<_> = right
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| !left.iter().any(|l| l.0 == r.0))
+        .collect();
+
+    // Renames keep the type; prefer a similar name, then the same position
+    let mut unpaired = Vec::new();
+    for (i, l) in left_only {
+        let candidate = right_only
+            .iter()
+            .position(|(_, r)| r.1 == l.1 && similar_field_names(&l.0, &r.0))
+            .or_else(|| right_only.iter().position(|(j, r)| r.1 == l.1 && *j == i));
+        match candidate {
+            Some(k) => rows.push(FieldMatch::Renamed(l, right_only.remove(k).1)),
+            None => unpaired.push(l),
+        }
+    }
+
+    for i in 0..unpaired.len().max(right_only.len()) {
+        rows.push(FieldMatch::Only(
+            unpaired.get(i).copied(),
+            right_only.get(i).map(|(_, r)| *r),
+        ));
+    }
+    rows
+}
+
+/// Whether two field names differ only in case and underscores, or one
+/// contains the other (`pos` and `position`, `name` and `display_name`)
+fn similar_field_names(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| -> String {
+        name.chars()
+            .filter(|c| *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    !a.is_empty() && !b.is_empty() && (a.contains(&b) || b.contains(&a))
+}
+
+/// Width of the terminal: `COLUMNS` when set, otherwise the size of the
+/// terminal on stdout, and 100 when stdout is not a terminal
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .or_else(stdout_terminal_width)
+        .unwrap_or(100)
+}
+
+#[cfg(unix)]
+fn stdout_terminal_width() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes a `winsize` through the pointer
+    let status = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (status == 0 && size.ws_col > 0).then_some(usize::from(size.ws_col))
+}
+
+#[cfg(not(unix))]
+fn stdout_terminal_width() -> Option<usize> {
+    None
+}
+
+/// What changed from `old` to `new`: tables added (`+`) or removed (`-`),
+/// and in tables on both sides, fields added, removed or changed type
+/// (`~`). Fields are matched by name and their types compared as
+/// `format_type` renders them, so e.g. a field becoming optional shows up.
+fn display_schema_diff(old: (&str, &SatsSchema), new: (&str, &SatsSchema)) {
+    outln!(
+        "\n{} {} {} {}",
+        glyphs("🔀 SCHEMA DIFF:").yellow(),
+        old.0.bold(),
+        glyphs("→"),
+        new.0.bold()
+    );
+    let has_table = |schema: &SatsSchema, name: &str| schema.tables.iter().any(|t| t.name == name);
+    let mut changes = 0;
+
+    for table in &old.1.tables {
+        if !has_table(new.1, &table.name) {
+            outln!("{} table {}", "-".red(), table.name.red());
+            changes += 1;
+        }
+    }
+    for table in &new.1.tables {
+        if !has_table(old.1, &table.name) {
+            outln!("{} table {}", "+".green(), table.name.green());
+            changes += 1;
+        }
+    }
+
+    for table in &old.1.tables {
+        if !has_table(new.1, &table.name) {
+            continue;
+        }
+        let old_fields = table_fields(old.1, &table.name)
+            .map(|(_, fields)| fields)
+            .unwrap_or_default();
+        let new_fields = table_fields(new.1, &table.name)
+            .map(|(_, fields)| fields)
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for (name, old_type) in &old_fields {
+            match new_fields.iter().find(|(n, _)| n == name) {
+                None => lines.push(format!("- {name}: {old_type}").red().to_string()),
+                Some((_, new_type)) if new_type != old_type => lines.push(
+                    format!("~ {name}: {old_type} {} {new_type}", glyphs("→"))
+                        .yellow()
+                        .to_string(),
+                ),
+                Some(_) => {}
+            }
+        }
+        for (name, new_type) in &new_fields {
+            if !old_fields.iter().any(|(n, _)| n == name) {
+                lines.push(format!("+ {name}: {new_type}").green().to_string());
+            }
+        }
+
+        if !lines.is_empty() {
+            outln!("{} table {}", "~".yellow(), table.name.bold());
+            for line in &lines {
+                outln!("    {line}");
+            }
+            changes += lines.len();
+        }
+    }
+
+    if changes == 0 {
+        outln!(
+            "{}",
+            glyphs("✅ No differences in tables or fields").green()
+        );
+    }
+}
+
+/// The named table (matched ignoring case) and its fields as `(name, type)`
+pub fn table_fields(
+    schema: &SatsSchema,
+    table_name: &str,
+) -> Option<(String, Vec<(String, String)>)> {
+    let type_names = build_type_names(schema);
+    let table = schema
+        .tables
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(table_name))?;
+
+    let fields = match schema.typespace.types.get(table.product_type_ref) {
+        Some(TypeDef::Product { Product }) => Product
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let name = e
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                (name, format_type(schema, &e.algebraic_type, &type_names))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some((table.name.clone(), fields))
+}
+
+/// Pad or truncate `text` to exactly `width` characters
+fn fit_column(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        format!("{text}{}", " ".repeat(width - len))
     } else {
-        println!("{} Table '{}' not found", "❌".red(), table_name);
-        println!("\nAvailable tables:");
-        for t in &schema.tables {
-            println!("  - {}", t.name);
-        }
+        let truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+        format!("{truncated}{}", glyphs("…"))
     }
 }
 
-fn display_single_type(schema: &SatsSchema, type_names: &HashMap<usize, String>, type_name: &str) {
+fn display_single_type(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    type_name: &str,
+    options: RenderOptions,
+) {
     let type_entry = type_names
         .iter()
         .find(|(_, name)| name.eq_ignore_ascii_case(type_name));
@@ -651,111 +3038,204 @@ fn display_single_type(schema: &SatsSchema, type_names: &HashMap<usize, String>,
         if let Some(type_def) = schema.typespace.types.get(*type_idx) {
             match type_def {
                 TypeDef::Product { Product } => {
-                    println!("\n{} {}", "📦 STRUCT:".blue(), real_name.bold());
-                    println!("{}", "-".repeat(40));
+                    outln!("\n{} {}", glyphs("📦 STRUCT:").blue(), real_name.bold());
+                    outln!("{}", "-".repeat(40));
 
                     if let Some(special) = detect_spacetimedb_type(Product) {
-                        println!("SpacetimeDB Type: {}", special.yellow());
+                        outln!("SpacetimeDB Type: {}", special.yellow());
                     }
 
-                    println!("\nFields ({}):", Product.elements.len());
+                    outln!("\nFields ({}):", Product.elements.len());
                     for element in &Product.elements {
                         if let Some(field_name) = element.name.as_option() {
-                            let field_type = format_type(&element.algebraic_type, type_names);
-                            println!("  {} {}: {}", "▸".green(), field_name, field_type.cyan());
+                            let field_type = render_field_type(
+                                schema,
+                                &element.algebraic_type,
+                                type_names,
+                                options,
+                            );
+                            outln!(
+                                "  {} {}: {}{}",
+                                glyphs("▸").green(),
+                                field_name,
+                                color_type(schema, &element.algebraic_type, &field_type, options),
+                                unit_note(schema, &element.algebraic_type, options)
+                            );
+                            print_explanation(schema, &element.algebraic_type, options, "  ");
                         }
                     }
                 }
                 TypeDef::Sum { Sum } => {
-                    display_single_enum_by_ref(schema, type_names, real_name, Sum);
+                    display_single_enum_by_ref(schema, type_names, real_name, Sum, options);
                 }
                 _ => {
-                    println!("{} '{}' is not a struct or enum", "❌".red(), type_name);
+                    outln!(
+                        "{} '{}' is not a struct or enum",
+                        glyphs("❌").red(),
+                        type_name
+                    );
                 }
             }
         }
     } else {
-        println!("{} Type '{}' not found", "❌".red(), type_name);
+        outln!("{} Type '{}' not found", glyphs("❌").red(), type_name);
         suggest_similar_types(type_names, type_name);
     }
 }
 
-fn display_single_enum(schema: &SatsSchema, type_names: &HashMap<usize, String>, enum_name: &str) {
+fn display_single_enum(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    enum_name: &str,
+    options: RenderOptions,
+) {
     let type_entry = type_names
         .iter()
         .find(|(_, name)| name.eq_ignore_ascii_case(enum_name));
 
     if let Some((type_idx, real_name)) = type_entry {
         if let Some(TypeDef::Sum { Sum }) = schema.typespace.types.get(*type_idx) {
-            display_single_enum_by_ref(schema, type_names, real_name, Sum);
+            display_single_enum_by_ref(schema, type_names, real_name, Sum, options);
         } else {
-            println!("{} '{}' is not an enum", "❌".red(), enum_name);
+            outln!("{} '{}' is not an enum", glyphs("❌").red(), enum_name);
             suggest_enum_types(schema, type_names);
         }
     } else {
-        println!("{} Enum '{}' not found", "❌".red(), enum_name);
+        outln!("{} Enum '{}' not found", glyphs("❌").red(), enum_name);
         suggest_enum_types(schema, type_names);
     }
 }
 
 fn display_single_enum_by_ref(
-    _schema: &SatsSchema,
+    schema: &SatsSchema,
     type_names: &HashMap<usize, String>,
     real_name: &str,
     sum: &SumType,
+    options: RenderOptions,
 ) {
-    println!("\n{} {}", "🔀 ENUM:".cyan(), real_name.bold());
-    println!("{}", "-".repeat(40));
+    outln!("\n{} {}", glyphs("🔀 ENUM:").cyan(), real_name.bold());
+    outln!("{}", "-".repeat(40));
 
     if let Some(special) = detect_spacetimedb_sum_type(sum) {
-        println!("SpacetimeDB Type: {}", special.yellow());
+        outln!("SpacetimeDB Type: {}", special.yellow());
     }
 
-    println!("\nVariants ({}):", sum.variants.len());
-    for variant in &sum.variants {
+    outln!("\nVariants ({}):", sum.variants.len());
+    let total = sum.variants.len();
+    let start = options.variant_skip.min(total);
+    let end = options
+        .variant_top
+        .map_or(total, |top| start.saturating_add(top).min(total));
+    if start >= end && total > 0 {
+        outln!("  {}", format!("no variants after --skip {start}").dimmed());
+    } else if start > 0 || end < total {
+        outln!(
+            "  {}",
+            format!("showing {}-{} of {}", start + 1, end, total).dimmed()
+        );
+    }
+    for variant in &sum.variants[start..end] {
         if let Some(variant_name) = variant.name.as_option() {
             match &variant.algebraic_type {
                 AlgebraicType::Product { Product } if Product.elements.is_empty() => {
-                    println!("  {} {}", "▸".green(), variant_name);
+                    outln!("  {} {}", glyphs("▸").green(), variant_name);
                 }
                 _ => {
-                    let variant_type = format_type(&variant.algebraic_type, type_names);
-                    println!(
-                        "  {} {}({})",
-                        "▸".green(),
+                    let variant_type =
+                        render_field_type(schema, &variant.algebraic_type, type_names, options);
+                    outln!(
+                        "  {} {}({}){}",
+                        glyphs("▸").green(),
                         variant_name,
-                        variant_type.cyan()
+                        color_type(schema, &variant.algebraic_type, &variant_type, options),
+                        unit_note(schema, &variant.algebraic_type, options)
                     );
+                    print_explanation(schema, &variant.algebraic_type, options, "  ");
                 }
             }
         }
     }
+    if end < total {
+        outln!(
+            "  {}",
+            format!("{} {} more (--skip {})", glyphs("…"), total - end, end).dimmed()
+        );
+    }
+}
+
+/// How --search compares names: a case-insensitive substring by default,
+/// or a regular expression with --regex
+pub enum NameMatcher {
+    Substring { pattern: String, lower: String },
+    Regex(regex::Regex),
+}
+
+impl NameMatcher {
+    pub fn substring(pattern: &str) -> Self {
+        Self::Substring {
+            pattern: pattern.to_string(),
+            lower: pattern.to_lowercase(),
+        }
+    }
+
+    /// Regexes are case-sensitive unless `ignore_case` is set or the
+    /// pattern starts with `(?i)`
+    pub fn regex(pattern: &str, ignore_case: bool) -> Result<Self> {
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .with_context(|| format!("Invalid --search regex '{pattern}'"))?;
+        Ok(Self::Regex(regex))
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Substring { lower, .. } => name.to_lowercase().contains(lower),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Substring { pattern, .. } => pattern,
+            Self::Regex(regex) => regex.as_str(),
+        }
+    }
 }
 
-fn display_search_results(schema: &SatsSchema, type_names: &HashMap<usize, String>, pattern: &str) {
-    let pattern_lower = pattern.to_lowercase();
+fn display_search_results(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    matcher: &NameMatcher,
+) {
+    let pattern = matcher.pattern();
 
-    println!("\n{} '{}'", "🔍 SEARCH RESULTS FOR:".yellow(), pattern);
-    println!("{}", "=".repeat(60));
+    outln!(
+        "\n{} '{}'",
+        glyphs("🔍 SEARCH RESULTS FOR:").yellow(),
+        pattern
+    );
+    outln!("{}", "=".repeat(60));
 
     // Search tables
     let matching_tables: Vec<_> = schema
         .tables
         .iter()
-        .filter(|t| t.name.to_lowercase().contains(&pattern_lower))
+        .filter(|t| matcher.is_match(&t.name))
         .collect();
 
     if !matching_tables.is_empty() {
-        println!("\n{}", "📊 TABLES:".bold());
+        outln!("\n{}", glyphs("📊 TABLES:").bold());
         for table in &matching_tables {
             let type_name = type_names
                 .get(&table.product_type_ref)
                 .cloned()
                 .unwrap_or_else(|| format!("Type_{}", table.product_type_ref));
-            println!(
-                "  {} {} → {}",
-                "▸".green(),
+            outln!(
+                "  {} {} {} {}",
+                glyphs("▸").green(),
                 table.name.bold(),
+                glyphs("→"),
                 type_name.dimmed()
             );
         }
@@ -765,7 +3245,7 @@ fn display_search_results(schema: &SatsSchema, type_names: &HashMap<usize, Strin
     let matching_types: Vec<_> = type_names
         .iter()
         .filter(|(type_idx, name)| {
-            name.to_lowercase().contains(&pattern_lower)
+            matcher.is_match(name)
                 && !schema
                     .tables
                     .iter()
@@ -774,22 +3254,22 @@ fn display_search_results(schema: &SatsSchema, type_names: &HashMap<usize, Strin
         .collect();
 
     if !matching_types.is_empty() {
-        println!("\n{}", "🔧 OTHER TYPES:".bold());
+        outln!("\n{}", glyphs("🔧 OTHER TYPES:").bold());
         for (type_idx, name) in &matching_types {
             if let Some(type_def) = schema.typespace.types.get(**type_idx) {
                 match type_def {
                     TypeDef::Sum { Sum } => {
-                        println!(
+                        outln!(
                             "  {} {} {}",
-                            "🔀".cyan(),
+                            glyphs("🔀").cyan(),
                             name.bold(),
                             format!("(enum with {} variants)", Sum.variants.len()).dimmed()
                         );
                     }
                     TypeDef::Product { Product } => {
-                        println!(
+                        outln!(
                             "  {} {} {}",
-                            "📦".blue(),
+                            glyphs("📦").blue(),
                             name.bold(),
                             format!("(struct with {} fields)", Product.elements.len()).dimmed()
                         );
@@ -801,33 +3281,366 @@ fn display_search_results(schema: &SatsSchema, type_names: &HashMap<usize, Strin
     }
 
     if matching_tables.is_empty() && matching_types.is_empty() {
-        println!("{} No matches found for '{}'", "❌".red(), pattern);
+        outln!("{} No matches found for '{}'", glyphs("❌").red(), pattern);
+    }
+}
+
+/// Reverse dependencies: the tables and types whose definitions mention
+/// `target` directly
+fn display_referenced_by(schema: &SatsSchema, type_names: &HashMap<usize, String>, target: &str) {
+    let Some((&target_idx, target_name)) = type_names
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(target))
+    else {
+        outln!("{} Type '{}' not found", glyphs("❌").red(), target);
+        return;
+    };
+
+    let references = |idx: usize| {
+        let mut refs = Vec::new();
+        if let Some(type_def) = schema.typespace.types.get(idx) {
+            collect_def_refs(type_def, &mut refs);
+        }
+        refs.contains(&target_idx)
+    };
+
+    outln!(
+        "\n{} {}",
+        glyphs("🔗 REFERENCED BY:").yellow(),
+        target_name.bold()
+    );
+    outln!("{}", "=".repeat(60));
+
+    let tables: Vec<_> = schema
+        .tables
+        .iter()
+        .filter(|t| references(t.product_type_ref))
+        .collect();
+    if !tables.is_empty() {
+        outln!("\n{}", glyphs("📊 TABLES:").bold());
+        for table in &tables {
+            outln!("  {} {}", glyphs("▸").green(), table.name.bold());
+        }
+    }
+
+    let table_refs: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+    let mut types: Vec<_> = type_names
+        .iter()
+        .filter(|(idx, _)| !table_refs.contains(idx) && references(**idx))
+        .collect();
+    types.sort_by(|a, b| a.1.cmp(b.1));
+    if !types.is_empty() {
+        outln!("\n{}", glyphs("🔧 OTHER TYPES:").bold());
+        for (idx, name) in &types {
+            match schema.typespace.types.get(**idx) {
+                Some(TypeDef::Sum { .. }) => {
+                    outln!(
+                        "  {} {} {}",
+                        glyphs("🔀").cyan(),
+                        name.bold(),
+                        "(enum)".dimmed()
+                    )
+                }
+                Some(TypeDef::Product { .. }) => {
+                    outln!(
+                        "  {} {} {}",
+                        glyphs("📦").blue(),
+                        name.bold(),
+                        "(struct)".dimmed()
+                    )
+                }
+                _ => outln!("  {} {}", glyphs("▸").green(), name.bold()),
+            }
+        }
+    }
+
+    if tables.is_empty() && types.is_empty() {
+        outln!("Nothing references '{}'", target_name);
+    }
+}
+
+/// Named types nothing uses: no table's row type, no reducer parameter
+/// and no other typespace entry refers to them. A type referring only to
+/// itself still counts as unused. Sorted by name.
+pub fn unused_types(schema: &SatsSchema) -> Vec<&str> {
+    let mut referenced: HashSet<usize> = schema.tables.iter().map(|t| t.product_type_ref).collect();
+    for (idx, type_def) in schema.typespace.types.iter().enumerate() {
+        let mut refs = Vec::new();
+        collect_def_refs(type_def, &mut refs);
+        referenced.extend(refs.into_iter().filter(|&r| r != idx));
+    }
+    for reducer in &schema.reducers {
+        let mut refs = Vec::new();
+        for element in &reducer.params.elements {
+            collect_refs(&element.algebraic_type, &mut refs);
+        }
+        referenced.extend(refs);
+    }
+
+    let mut unused: Vec<_> = schema
+        .types
+        .iter()
+        .filter(|t| !referenced.contains(&t.ty))
+        .map(|t| t.name.name.as_str())
+        .collect();
+    unused.sort_unstable();
+    unused
+}
+
+fn display_unused(unused: &[&str]) {
+    outln!("\n{}", glyphs("🧹 UNUSED TYPES").bold().cyan());
+    outln!("{}", "=".repeat(60));
+
+    if unused.is_empty() {
+        outln!("\n{} Every named type is used", glyphs("✅").green());
+        return;
+    }
+    outln!();
+    for name in unused {
+        outln!("  {} {}", glyphs("▸").yellow(), name.bold());
+    }
+    outln!(
+        "\n{}",
+        format!(
+            "{} type(s) not referenced by any table, reducer or other type",
+            unused.len()
+        )
+        .dimmed()
+    );
+}
+
+// Operational view: everything that runs without a client calling it
+fn display_automation(schema: &SatsSchema) {
+    let type_names = build_type_names(schema);
+    let lifecycle: Vec<_> = schema
+        .reducers
+        .iter()
+        .filter_map(|r| r.lifecycle().map(|l| (r, l)))
+        .collect();
+    let scheduled: Vec<_> = schema
+        .tables
+        .iter()
+        .filter_map(|t| find_schedule_link(schema, t).map(|link| (t, link)))
+        .collect();
+
+    outln!("\n{}", glyphs("⚙️  AUTOMATION").bold().cyan());
+    outln!("{}", "=".repeat(60));
+
+    if lifecycle.is_empty() && scheduled.is_empty() {
+        outln!("\nNo lifecycle reducers or scheduled tables in this database.");
+        return;
+    }
+
+    outln!(
+        "\n{} {}",
+        glyphs("🔁 LIFECYCLE REDUCERS").yellow(),
+        format!("({})", lifecycle.len()).dimmed()
+    );
+    if lifecycle.is_empty() {
+        outln!("  {}", "none".dimmed());
+    }
+    for (reducer, kind) in &lifecycle {
+        outln!(
+            "  {} {:<20} {}",
+            glyphs("▸").green(),
+            kind.label(),
+            format_reducer_signature(schema, reducer, &type_names).cyan()
+        );
+    }
+
+    outln!(
+        "\n{} {}",
+        glyphs("⏰ SCHEDULED TABLES").yellow(),
+        format!("({})", scheduled.len()).dimmed()
+    );
+    if scheduled.is_empty() {
+        outln!("  {}", "none".dimmed());
+    }
+    for (table, link) in &scheduled {
+        outln!("  {} {}", glyphs("▸").green(), table.name.bold());
+
+        let column = find_scheduled_at_column(schema, table)
+            .and_then(|idx| scheduled_at_column_name(schema, table, idx));
+        match column {
+            Some(column) => outln!(
+                "    {} schedule column: {} {}",
+                glyphs("├").dimmed(),
+                column,
+                "(Interval or Time, chosen per row)".dimmed()
+            ),
+            None => outln!(
+                "    {} schedule column: {}",
+                glyphs("├").dimmed(),
+                "unknown".dimmed()
+            ),
+        }
+
+        match (link.reducer, link.declared) {
+            (Some(reducer), _) => outln!(
+                "    {} runs reducer: {}",
+                glyphs("└").dimmed(),
+                format_reducer_signature(schema, reducer, &type_names).cyan()
+            ),
+            (None, Some(name)) => outln!(
+                "    {} runs reducer: {} {}",
+                glyphs("└").dimmed(),
+                name,
+                glyphs("⚠️ not defined in this module").yellow()
+            ),
+            (None, None) => outln!(
+                "    {} runs reducer: {}",
+                glyphs("└").dimmed(),
+                glyphs("⚠️ no matching reducer").yellow()
+            ),
+        }
+    }
+}
+
+fn automation_json(schema: &SatsSchema) -> serde_json::Value {
+    let type_names = build_type_names(schema);
+
+    let lifecycle: Vec<_> = schema
+        .reducers
+        .iter()
+        .filter_map(|r| {
+            r.lifecycle().map(|kind| {
+                serde_json::json!({
+                    "reducer": r.name,
+                    "lifecycle": kind.label(),
+                    "params": reducer_params_json(schema, r, &type_names),
+                })
+            })
+        })
+        .collect();
+
+    let scheduled: Vec<_> = schema
+        .tables
+        .iter()
+        .filter_map(|table| {
+            let link = find_schedule_link(schema, table)?;
+            let column = find_scheduled_at_column(schema, table)
+                .and_then(|idx| scheduled_at_column_name(schema, table, idx));
+            Some(serde_json::json!({
+                "table": table.name,
+                "scheduled_at_column": column,
+                "reducer": link.reducer.map(|r| r.name.as_str()).or(link.declared),
+                "reducer_found": link.reducer.is_some(),
+            }))
+        })
+        .collect();
+
+    serde_json::json!({
+        "lifecycle_reducers": lifecycle,
+        "scheduled_tables": scheduled,
+    })
+}
+
+fn scheduled_at_column_name<'a>(
+    schema: &'a SatsSchema,
+    table: &TableInfo,
+    column: usize,
+) -> Option<&'a str> {
+    match schema.typespace.types.get(table.product_type_ref) {
+        Some(TypeDef::Product { Product }) => Product
+            .elements
+            .get(column)
+            .and_then(|e| e.name.as_option()),
+        _ => None,
+    }
+}
+
+fn format_reducer_signature(
+    schema: &SatsSchema,
+    reducer: &ReducerDef,
+    type_names: &HashMap<usize, String>,
+) -> String {
+    let params: Vec<_> = reducer
+        .params
+        .elements
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let name = p
+                .name
+                .as_option()
+                .map_or_else(|| i.to_string(), str::to_string);
+            format!(
+                "{}: {}",
+                name,
+                format_type(schema, &p.algebraic_type, type_names)
+            )
+        })
+        .collect();
+    format!("{}({})", reducer.name, params.join(", "))
+}
+
+fn reducer_params_json(
+    schema: &SatsSchema,
+    reducer: &ReducerDef,
+    type_names: &HashMap<usize, String>,
+) -> Vec<serde_json::Value> {
+    reducer
+        .params
+        .elements
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "name": p.name.as_option(),
+                "type": format_type(schema, &p.algebraic_type, type_names),
+            })
+        })
+        .collect()
+}
+
+fn display_endpoint_probes(probes: &[EndpointProbe]) {
+    outln!("\n{}", glyphs("🔎 ENDPOINT PROBE").bold().cyan());
+    outln!("{}", "=".repeat(60));
+
+    for probe in probes {
+        let (marker, label) = match probe.availability {
+            Availability::Available => (glyphs("✅").green(), "available".green()),
+            Availability::RequiresAuth => {
+                (glyphs("🔒").yellow(), "requires authentication".yellow())
+            }
+            Availability::Unavailable => (glyphs("❌").red(), "unavailable".red()),
+            Availability::Unreachable => (glyphs("❌").red(), "unreachable".red()),
+        };
+        let status = probe
+            .status
+            .map_or_else(|| "no response".to_string(), |s| s.to_string());
+        outln!(
+            "  {} {:<14} {} {}",
+            marker,
+            probe.name,
+            label,
+            format!("({} {} {})", probe.path, glyphs("→"), status).dimmed()
+        );
     }
 }
 
+/// The five type names closest to `search` by edit distance, ignoring case
 fn suggest_similar_types(type_names: &HashMap<usize, String>, search: &str) {
-    println!("\nDid you mean one of these?");
+    outln!("\nDid you mean one of these?");
     let search_lower = search.to_lowercase();
 
     let mut suggestions: Vec<_> = type_names
         .values()
-        .filter(|name| {
-            let name_lower = name.to_lowercase();
-            name_lower.contains(&search_lower)
-                || search_lower.contains(&name_lower)
-                || name_lower.starts_with(&search_lower.chars().take(3).collect::<String>())
+        .map(|name| {
+            (
+                strsim::levenshtein(&search_lower, &name.to_lowercase()),
+                name,
+            )
         })
-        .take(5)
         .collect();
-
     suggestions.sort();
-    for name in suggestions {
-        println!("  - {}", name);
+
+    for (_, name) in suggestions.into_iter().take(5) {
+        outln!("  - {}", name);
     }
 }
 
 fn suggest_enum_types(schema: &SatsSchema, type_names: &HashMap<usize, String>) {
-    println!("\nAvailable enums:");
+    outln!("\nAvailable enums:");
     let mut enums: Vec<_> = type_names
         .iter()
         .filter(|(idx, _)| matches!(schema.typespace.types.get(**idx), Some(TypeDef::Sum { .. })))
@@ -836,6 +3649,553 @@ fn suggest_enum_types(schema: &SatsSchema, type_names: &HashMap<usize, String>)
 
     enums.sort();
     for name in enums.iter().take(10) {
-        println!("  - {}", name);
+        outln!("  - {}", name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        big_enum_schema, demo_schema, demo_schema_with, recursive_schema, schema,
+        wide_table_schema,
+    };
+
+    fn reducer_mut<'a>(
+        document: &'a mut serde_json::Value,
+        name: &str,
+    ) -> &'a mut serde_json::Value {
+        document["reducers"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|r| r["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn automation_lists_the_game_tick_loop() {
+        let schema = demo_schema();
+        assert_eq!(
+            automation_json(&schema),
+            serde_json::json!({
+                "lifecycle_reducers": [
+                    {"reducer": "init", "lifecycle": "init", "params": []},
+                    {"reducer": "client_connected", "lifecycle": "client_connected", "params": []},
+                    {"reducer": "client_disconnected", "lifecycle": "client_disconnected", "params": []},
+                ],
+                "scheduled_tables": [{
+                    "table": "game_tick",
+                    "scheduled_at_column": "scheduled_at",
+                    "reducer": "tick",
+                    "reducer_found": true,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn automation_is_empty_without_lifecycle_or_schedules() {
+        let schema = demo_schema_with(|document| {
+            document["reducers"] = serde_json::json!([]);
+            document["tables"].as_array_mut().unwrap().truncate(5);
+        });
+        assert_eq!(
+            automation_json(&schema),
+            serde_json::json!({"lifecycle_reducers": [], "scheduled_tables": []})
+        );
+    }
+
+    #[test]
+    fn lifecycle_reducers_keep_their_parameters() {
+        let schema = demo_schema_with(|document| {
+            let params = reducer_mut(document, "tick")["params"].clone();
+            reducer_mut(document, "init")["params"] = params;
+        });
+        let report = automation_json(&schema);
+        let init = &report["lifecycle_reducers"][0];
+        assert_eq!(init["reducer"], "init");
+        assert_eq!(init["params"].as_array().unwrap().len(), 1);
+        assert_eq!(init["params"][0]["name"], "tick");
+    }
+
+    #[test]
+    fn declared_reducer_that_does_not_exist_is_flagged() {
+        let schema = demo_schema_with(|document| {
+            document["tables"][5]["schedule"]["some"]["reducer_name"] = "tick_v2".into();
+        });
+        let scheduled = &automation_json(&schema)["scheduled_tables"][0];
+        assert_eq!(scheduled["reducer"], "tick_v2");
+        assert_eq!(scheduled["reducer_found"], false);
+    }
+
+    #[test]
+    fn schedule_without_metadata_falls_back_to_the_row_type_convention() {
+        let schema = demo_schema_with(|document| {
+            document["tables"][5]["schedule"] = serde_json::json!({"none": []});
+        });
+        let scheduled = &automation_json(&schema)["scheduled_tables"][0];
+        assert_eq!(scheduled["table"], "game_tick");
+        assert_eq!(scheduled["scheduled_at_column"], "scheduled_at");
+        assert_eq!(scheduled["reducer"], "tick");
+        assert_eq!(scheduled["reducer_found"], true);
+
+        let orphan = demo_schema_with(|document| {
+            document["tables"][5]["schedule"] = serde_json::json!({"none": []});
+            reducer_mut(document, "tick")["params"] = serde_json::json!({"elements": []});
+        });
+        let scheduled = &automation_json(&orphan)["scheduled_tables"][0];
+        assert_eq!(scheduled["reducer"], serde_json::Value::Null);
+        assert_eq!(scheduled["reducer_found"], false);
+    }
+
+    fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn compared_fields_align_by_name_then_by_rename() {
+        let old = fields(&[
+            ("id", "u64"),
+            ("hp", "u32"),
+            ("pos", "Vec2"),
+            ("gold", "u64"),
+        ]);
+        let new = fields(&[
+            ("id", "u128"),
+            ("position", "Vec2"),
+            ("health", "u32"),
+            ("xp", "u32"),
+        ]);
+        assert_eq!(
+            match_fields(&old, &new),
+            vec![
+                FieldMatch::Same(&old[0], &new[0]),
+                FieldMatch::Renamed(&old[2], &new[1]),
+                FieldMatch::Only(Some(&old[1]), Some(&new[2])),
+                FieldMatch::Only(Some(&old[3]), Some(&new[3])),
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_by_position_needs_the_same_type() {
+        let old = fields(&[("id", "u64"), ("hp", "u32")]);
+        let renamed = fields(&[("id", "u64"), ("health", "u32")]);
+        assert_eq!(
+            match_fields(&old, &renamed)[1],
+            FieldMatch::Renamed(&old[1], &renamed[1])
+        );
+
+        let retyped = fields(&[("id", "u64"), ("health", "f32")]);
+        assert_eq!(
+            match_fields(&old, &retyped)[1],
+            FieldMatch::Only(Some(&old[1]), Some(&retyped[1]))
+        );
+    }
+
+    #[test]
+    fn missing_table_leaves_one_column_empty() {
+        let new = fields(&[("id", "u64"), ("name", "String")]);
+        assert_eq!(
+            match_fields(&[], &new),
+            vec![
+                FieldMatch::Only(None, Some(&new[0])),
+                FieldMatch::Only(None, Some(&new[1])),
+            ]
+        );
+    }
+
+    #[test]
+    fn similar_field_names_ignore_case_and_underscores() {
+        assert!(similar_field_names("player_id", "PlayerId"));
+        assert!(similar_field_names("name", "display_name"));
+        assert!(!similar_field_names("hp", "health"));
+        assert!(!similar_field_names("_", "id"));
+    }
+
+    #[test]
+    fn fit_column_pads_and_truncates_to_the_width() {
+        assert_eq!(fit_column("id: u64", 10), "id: u64   ");
+        assert_eq!(fit_column("inventory: Vec<ItemStack>", 10), "inventory…");
+        assert_eq!(fit_column("inventory…", 10).chars().count(), 10);
+    }
+
+    fn expanded(schema: &SatsSchema, alg_type: &AlgebraicType) -> String {
+        let options = RenderOptions {
+            expand_all: true,
+            ..RenderOptions::default()
+        };
+        render_field_type(schema, alg_type, &build_type_names(schema), options)
+    }
+
+    #[test]
+    fn expand_all_stops_at_a_type_that_refers_to_itself() {
+        let schema = recursive_schema();
+        assert_eq!(
+            expanded(&schema, &AlgebraicType::Ref { Ref: 0 }),
+            "Node { value: i32, children: Vec<Node> }"
+        );
+    }
+
+    #[test]
+    fn expand_all_lists_every_variant_of_a_big_enum() {
+        let schema = big_enum_schema(1000);
+        let key = expanded(&schema, &AlgebraicType::Ref { Ref: 1 });
+        assert!(key.starts_with("LocalizationKey { Key0 | Key1 | "), "{key}");
+        assert!(key.ends_with(" | Key999 }"), "{key}");
+        assert_eq!(key.matches(" | ").count(), 999);
+    }
+
+    #[test]
+    fn wide_tables_keep_every_field_in_order() {
+        let schema = wide_table_schema(300);
+        let (name, fields) = table_fields(&schema, "WIDE").unwrap();
+        assert_eq!(name, "wide");
+        assert_eq!(fields.len(), 300);
+        assert_eq!(fields[299], ("f299".to_string(), "u32".to_string()));
+
+        let options = RenderOptions {
+            min_fields: Some(300),
+            ..RenderOptions::default()
+        };
+        assert!(options.table_size_matches(fields.len()));
+        assert!(!options.table_size_matches(299));
+    }
+
+    #[test]
+    fn name_warnings_cover_prefixes_keywords_and_special_names() {
+        let mut schema = demo_schema();
+        schema.tables[0].name = "ST_Player".to_string();
+        schema.tables[1].name = "Order".to_string();
+        let warnings: Vec<_> = find_name_warnings(&schema, &["channel".to_string()])
+            .into_iter()
+            .map(|w| {
+                let subject = match &w.column {
+                    Some(column) => format!("{}.{column}", w.table),
+                    None => w.table.clone(),
+                };
+                (w.severity, subject)
+            })
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (Severity::Error, "ST_Player".to_string()),
+                (Severity::Warning, "Order".to_string()),
+                (Severity::Warning, "chat_message.sender".to_string()),
+                (Severity::Warning, "chat_message.channel".to_string()),
+            ]
+        );
+    }
+
+    fn field_types(schema: &SatsSchema, table: &str) -> Vec<String> {
+        let type_names = build_type_names(schema);
+        let table = schema.tables.iter().find(|t| t.name == table).unwrap();
+        let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+        else {
+            panic!("row type is not a product");
+        };
+        Product
+            .elements
+            .iter()
+            .map(|e| format_type(schema, &e.algebraic_type, &type_names))
+            .collect()
+    }
+
+    #[test]
+    fn map_fields_are_formatted() {
+        let schema =
+            schema(serde_json::from_str(include_str!("../tests/fixtures/map_table.json")).unwrap());
+        assert_eq!(
+            field_types(&schema, "inventory"),
+            ["String", "HashMap<String, u32>", "HashMap<u8, ItemStack>"]
+        );
+    }
+
+    /// Product with the given `(field, type)` pairs, for typespace fixtures
+    fn product(fields: &[(&str, serde_json::Value)]) -> serde_json::Value {
+        let elements: Vec<_> = fields
+            .iter()
+            .map(|(name, ty)| serde_json::json!({"name": {"some": name}, "algebraic_type": ty}))
+            .collect();
+        serde_json::json!({"Product": {"elements": elements}})
+    }
+
+    fn option(ty: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({"Sum": {"variants": [
+            {"name": {"some": "some"}, "algebraic_type": ty},
+            {"name": {"some": "none"}, "algebraic_type": {"Product": {"elements": []}}},
+        ]}})
+    }
+
+    /// A schema whose typespace is `types`, named in order by `names`
+    fn typespace(types: Vec<serde_json::Value>, names: &[&str]) -> SatsSchema {
+        let named: Vec<_> = names
+            .iter()
+            .enumerate()
+            .map(|(ty, name)| {
+                serde_json::json!({"name": {"scope": [], "name": name}, "ty": ty, "custom_ordering": true})
+            })
+            .collect();
+        schema(serde_json::json!({
+            "typespace": {"types": types},
+            "tables": [],
+            "reducers": [],
+            "types": named,
+        }))
+    }
+
+    fn expand_ref(schema: &SatsSchema, idx: u32) -> String {
+        expand_type(
+            schema,
+            &AlgebraicType::Ref { Ref: idx },
+            &build_type_names(schema),
+        )
+    }
+
+    #[test]
+    fn self_referencing_type_expands_once() {
+        let node = product(&[
+            ("value", serde_json::json!({"U32": []})),
+            ("next", option(serde_json::json!({"Ref": 0}))),
+        ]);
+        let schema = typespace(vec![node], &["Node"]);
+        assert_eq!(
+            expand_ref(&schema, 0),
+            "Node { value: u32, next: Option<Node> }"
+        );
+        assert_eq!(
+            format_type(
+                &schema,
+                &AlgebraicType::Ref { Ref: 0 },
+                &build_type_names(&schema)
+            ),
+            "Node"
+        );
+    }
+
+    #[test]
+    fn mutually_recursive_types_terminate() {
+        let a = product(&[("b", serde_json::json!({"Ref": 1}))]);
+        let b = product(&[("a", option(serde_json::json!({"Ref": 0})))]);
+        let schema = typespace(vec![a, b], &["A", "B"]);
+        assert_eq!(expand_ref(&schema, 0), "A { b: B { a: Option<A> } }");
+        assert_eq!(expand_ref(&schema, 1), "B { a: Option<A { b: B }> }");
+    }
+
+    #[test]
+    fn repeated_siblings_are_not_cycles() {
+        let vec2 = product(&[("x", serde_json::json!({"F32": []}))]);
+        let line = product(&[
+            ("from", serde_json::json!({"Ref": 0})),
+            ("to", serde_json::json!({"Ref": 0})),
+        ]);
+        let schema = typespace(vec![vec2, line], &["Vec2", "Line"]);
+        assert_eq!(
+            expand_ref(&schema, 1),
+            "Line { from: Vec2 { x: f32 }, to: Vec2 { x: f32 } }"
+        );
+    }
+
+    #[test]
+    fn unnamed_refs_fall_back_to_the_index() {
+        let node = product(&[("next", option(serde_json::json!({"Ref": 0})))]);
+        let schema = typespace(vec![node], &[]);
+        assert_eq!(expand_ref(&schema, 0), "{ next: Option<Type_0> }");
+    }
+
+    #[test]
+    fn wide_expansions_stop_at_the_node_limit() {
+        // Each level refers to the next twice: 2^12 leaves without any cycle
+        let levels = 12;
+        let mut types: Vec<_> = (0..levels)
+            .map(|i| {
+                let next = serde_json::json!({"Ref": i + 1});
+                product(&[("left", next.clone()), ("right", next)])
+            })
+            .collect();
+        types.push(product(&[("leaf", serde_json::json!({"Bool": []}))]));
+        let schema = typespace(types, &[]);
+        let expanded = expand_ref(&schema, 0);
+        assert!(
+            expanded.ends_with(&format!(
+                "(expansion stopped after {EXPAND_NODE_LIMIT} types)"
+            )),
+            "{expanded}"
+        );
+    }
+
+    #[test]
+    fn demo_item_stack_refers_back_to_itself() {
+        let schema = crate::test_support::demo_schema();
+        assert_eq!(field_types(&schema, "player")[7], "Vec<ItemStack>");
+        assert_eq!(
+            expand_ref(&schema, 2),
+            "ItemStack { item_id: u32, count: u16, next: Option<ItemStack> }"
+        );
+    }
+
+    #[test]
+    fn search_matches_substrings_or_regexes() {
+        let substring = NameMatcher::substring("Guild");
+        assert!(substring.is_match("guild_member"));
+        assert!(substring.is_match("GuildRole"));
+
+        let regex = NameMatcher::regex("^guild_", false).unwrap();
+        assert!(regex.is_match("guild_member"));
+        assert!(!regex.is_match("guild"));
+        assert!(!regex.is_match("Guild_member"));
+        assert!(NameMatcher::regex("^guild_", true)
+            .unwrap()
+            .is_match("Guild_member"));
+        assert_eq!(regex.pattern(), "^guild_");
+
+        let error = NameMatcher::regex("(unclosed", false).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("Invalid --search regex '(unclosed'"));
+    }
+
+    #[test]
+    fn refs_are_collected_through_wrappers() {
+        let schema = demo_schema();
+        let player = schema.tables[0].product_type_ref;
+        let mut refs = Vec::new();
+        collect_def_refs(&schema.typespace.types[player], &mut refs);
+        let type_names = build_type_names(&schema);
+        let mut names: Vec<_> = refs.iter().map(|idx| type_names[idx].as_str()).collect();
+        names.sort_unstable();
+        // `inventory: Vec<ItemStack>` is found inside the array
+        assert_eq!(names, ["ItemStack", "PlayerStatus", "Vec2"]);
+
+        let mut refs = Vec::new();
+        collect_refs(
+            &serde_json::from_value(serde_json::json!({"Array": {"Sum": {"variants": [
+                {"name": {"some": "some"}, "algebraic_type": {"Ref": 3}},
+                {"name": {"some": "none"}, "algebraic_type": {"Product": {"elements": []}}},
+            ]}}}))
+            .unwrap(),
+            &mut refs,
+        );
+        assert_eq!(refs, [3]);
+    }
+
+    #[test]
+    fn unused_types_are_named_ones_nothing_refers_to() {
+        assert!(unused_types(&demo_schema()).is_empty());
+
+        let schema = demo_schema_with(|document| {
+            let types = document["typespace"]["types"].as_array_mut().unwrap();
+            let orphan = types.len();
+            types.push(serde_json::json!({"Product": {"elements": []}}));
+            types.push(serde_json::json!({"Product": {"elements": [
+                {"name": {"some": "next"}, "algebraic_type": {"Array": {"Ref": orphan + 1}}},
+            ]}}));
+            let names = document["types"].as_array_mut().unwrap();
+            for (ty, name) in [(orphan, "Orphan"), (orphan + 1, "Loop")] {
+                names.push(serde_json::json!({"name": {"scope": [], "name": name}, "ty": ty, "custom_ordering": true}));
+            }
+        });
+        // A type that only refers to itself is still unused
+        assert_eq!(unused_types(&schema), ["Loop", "Orphan"]);
+    }
+
+    #[test]
+    fn primary_keys_are_shown_by_column_name() {
+        let schema = demo_schema();
+        let table = schema
+            .tables
+            .iter()
+            .find(|t| t.name == "guild_member")
+            .unwrap();
+        assert_eq!(primary_key_names(&schema, table), ["guild_id", "player_id"]);
+
+        // An index past the end of the row falls back to the number
+        let schema = demo_schema_with(|document| {
+            for table in document["tables"].as_array_mut().unwrap() {
+                if table["name"] == "guild_member" {
+                    table["primary_key"] = serde_json::json!([1, 9]);
+                }
+            }
+        });
+        let table = schema
+            .tables
+            .iter()
+            .find(|t| t.name == "guild_member")
+            .unwrap();
+        assert_eq!(primary_key_names(&schema, table), ["player_id", "9"]);
+    }
+
+    #[test]
+    fn stats_count_each_kind_of_entry() {
+        let stats = SchemaStats::new(&demo_schema());
+        assert_eq!(stats.tables, 6);
+        assert_eq!(stats.structs, 8);
+        assert_eq!(stats.enums, 3);
+        assert_eq!(
+            stats.well_known.iter().collect::<Vec<_>>(),
+            ["Duration", "Identity", "ScheduledAt", "Timestamp"]
+        );
+        assert_eq!(stats.to_json()["well_known_types"], 4);
+        assert_eq!(stats.to_json()["typespace_entries"], 11);
+    }
+
+    #[test]
+    fn unnamed_entries_are_written_in_place() {
+        use serde_json::json;
+        let tuple = json!({"Product": {"elements": [
+            {"name": {"none": []}, "algebraic_type": {"I32": []}},
+            {"name": {"none": []}, "algebraic_type": {"String": []}},
+        ]}});
+        let schema = typespace(
+            vec![
+                json!({"Product": {"elements": []}}),
+                tuple,
+                json!({"Builtin": {"Array": {"U8": []}}}),
+                json!({"Ref": 1}),
+                product(&[("again", json!({"Ref": 4}))]),
+                json!({"Ref": 6}),
+                json!({"Ref": 5}),
+            ],
+            &["Root"],
+        );
+        let type_names = build_type_names(&schema);
+        let format = |idx: u32| format_type(&schema, &AlgebraicType::Ref { Ref: idx }, &type_names);
+        assert_eq!(format(0), "Root");
+        assert_eq!(format(1), "(i32, String)");
+        assert_eq!(format(2), "Vec<u8>");
+        // Aliases are followed to what they name
+        assert_eq!(format(3), "(i32, String)");
+        assert_eq!(format(4), "Product(1 fields)");
+        // An alias cycle, and a ref past the end, fall back to the index
+        assert_eq!(format(5), "Type_5");
+        assert_eq!(format(9), "Type_9");
+    }
+
+    #[test]
+    fn builtin_256_bit_typespace_entries_deserialize() {
+        let schema = schema(
+            serde_json::from_str(include_str!("../tests/fixtures/builtin_256.json")).unwrap(),
+        );
+        assert!(matches!(
+            schema.typespace.types[1],
+            TypeDef::Builtin {
+                Builtin: BuiltinType::U256 { .. }
+            }
+        ));
+        assert!(matches!(
+            schema.typespace.types[2],
+            TypeDef::Builtin {
+                Builtin: BuiltinType::I256 { .. }
+            }
+        ));
+        assert_eq!(
+            field_types(&schema, "account"),
+            ["u64", "Balance", "i256", "Vec<Balance>"]
+        );
     }
 }
\ No newline at end of file
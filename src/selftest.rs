@@ -0,0 +1,250 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ascii::glyphs;
+use crate::output::outln;
+use crate::schema::sats_types::SatsSchema;
+use crate::schema::{OutputFormat, SchemaArgs};
+use crate::spacetime_client::SpacetimeClient;
+
+/// Outcome of one self-test step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize)]
+struct Step {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    duration_ms: Option<u128>,
+}
+
+/// Runs the steps in order; once one fails, the rest are recorded as skipped
+#[derive(Default)]
+struct SelfTest {
+    steps: Vec<Step>,
+}
+
+impl SelfTest {
+    fn failed(&self) -> bool {
+        self.steps.iter().any(|s| s.status == Status::Failed)
+    }
+
+    fn record(&mut self, name: &'static str, started: Instant, result: Result<String>) {
+        let (status, detail) = match result {
+            Ok(detail) => (Status::Passed, detail),
+            Err(e) => (Status::Failed, e.to_string()),
+        };
+        self.steps.push(Step {
+            name,
+            status,
+            detail,
+            duration_ms: Some(started.elapsed().as_millis()),
+        });
+    }
+
+    fn skip(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.steps.push(Step {
+            name,
+            status: Status::Skipped,
+            detail: detail.into(),
+            duration_ms: None,
+        });
+    }
+}
+
+const STEPS: &[&str] = &[
+    "resolve server",
+    "ping",
+    "authenticate",
+    "fetch schema",
+    "parse schema",
+    "validate schema",
+    "fingerprint",
+    "sql query",
+];
+
+/// Run every diagnostic step against the server and database, reusing the
+/// client, validator and fingerprinting the other commands use. Prints a
+/// report (JSON with `--format json`) and fails if any step failed.
+pub async fn run_selftest(args: &SchemaArgs) -> Result<()> {
+    let mut test = SelfTest::default();
+    let server = if args.cloud { "cloud" } else { &args.server };
+
+    let started = Instant::now();
+    let client = SpacetimeClient::new(server, args.timeout).and_then(|client| {
+        let client = client.with_verbose(args.verbose);
+        match &args.token {
+            Some(token) => Ok(client.with_token(token)),
+            None => client.with_stored_token(args.strict_auth),
+        }
+    });
+    test.record(
+        "resolve server",
+        started,
+        match &client {
+            Ok(client) => Ok(format!("{} ({})", client.base_url(), server_source(args))),
+            Err(e) => Err(anyhow!("{e}")),
+        },
+    );
+
+    if let Ok(client) = &client {
+        run_steps(&mut test, client, args).await;
+    }
+    for name in &STEPS[test.steps.len()..] {
+        test.skip(name, "skipped after an earlier failure");
+    }
+
+    match args.format {
+        OutputFormat::Json => outln!(
+            "{}",
+            serde_json::to_string_pretty(&report_json(args, &test))?
+        ),
+        _ => print_report(args, &test),
+    }
+
+    if test.failed() {
+        return Err(anyhow!("Self-test failed"));
+    }
+    Ok(())
+}
+
+async fn run_steps(test: &mut SelfTest, client: &SpacetimeClient, args: &SchemaArgs) {
+    let started = Instant::now();
+    test.record(
+        "ping",
+        started,
+        client.ping().await.map(|()| "server is up".to_string()),
+    );
+    if test.failed() {
+        return;
+    }
+
+    test.skip(
+        "authenticate",
+        if args.token.is_some() {
+            "a bearer token is sent with every request; the schema fetch checks it"
+        } else {
+            "no token is configured; requests are made anonymously"
+        },
+    );
+
+    let started = Instant::now();
+    let fetched = client
+        .fetch_schema(&args.db, args.version.clone(), &args.query)
+        .await;
+    let schema_json = match fetched {
+        Ok(json) => {
+            let size = json.to_string().len();
+            test.record("fetch schema", started, Ok(format!("{size} bytes")));
+            json
+        }
+        Err(e) => return test.record("fetch schema", started, Err(e)),
+    };
+
+    let started = Instant::now();
+    let parsed = serde_json::from_value::<SatsSchema>(schema_json.clone())
+        .map(|schema| {
+            format!(
+                "{} tables, {} reducers, {} types",
+                schema.tables.len(),
+                schema.reducers.len(),
+                schema.typespace.types.len()
+            )
+        })
+        .map_err(Into::into);
+    test.record("parse schema", started, parsed);
+    if test.failed() {
+        return;
+    }
+
+    let started = Instant::now();
+    let problems = crate::validate::find_problems(&schema_json);
+    let validated = match problems.first() {
+        None => Ok("no problems found".to_string()),
+        Some(first) => Err(anyhow!(
+            "{} problem(s), first at {}: {}",
+            problems.len(),
+            if first.pointer.is_empty() {
+                "/"
+            } else {
+                &first.pointer
+            },
+            first.message
+        )),
+    };
+    test.record("validate schema", started, validated);
+    if test.failed() {
+        return;
+    }
+
+    let started = Instant::now();
+    test.record(
+        "fingerprint",
+        started,
+        Ok(format!(
+            "{} ({})",
+            crate::history::fingerprint(&schema_json),
+            crate::history::FINGERPRINT_ALGORITHM
+        )),
+    );
+
+    test.skip("sql query", "this tool does not run SQL queries");
+}
+
+/// Where the server URL came from
+fn server_source(args: &SchemaArgs) -> String {
+    if args.cloud {
+        "--cloud".to_string()
+    } else if args.server.starts_with("http://") || args.server.starts_with("https://") {
+        format!("--server {}", args.server)
+    } else {
+        format!("nickname '{}'", args.server)
+    }
+}
+
+fn print_report(args: &SchemaArgs, test: &SelfTest) {
+    outln!("\n{} {}", glyphs("🩺 SELF-TEST:").cyan(), args.db.bold());
+    outln!("{}", "-".repeat(40));
+    for step in &test.steps {
+        let mark = match step.status {
+            Status::Passed => glyphs("✔").green(),
+            Status::Failed => glyphs("✘").red(),
+            Status::Skipped => "-".dimmed(),
+        };
+        let timing = step
+            .duration_ms
+            .map(|ms| format!(" ({ms} ms)"))
+            .unwrap_or_default();
+        let detail = match step.status {
+            Status::Failed => step.detail.red(),
+            Status::Passed => step.detail.normal(),
+            Status::Skipped => step.detail.dimmed(),
+        };
+        outln!("  {} {:<16} {}{}", mark, step.name, detail, timing.dimmed());
+    }
+
+    if test.failed() {
+        outln!("\n{} Self-test failed", glyphs("❌").red());
+    } else {
+        outln!("\n{} Self-test passed", glyphs("✅").green());
+    }
+}
+
+fn report_json(args: &SchemaArgs, test: &SelfTest) -> Value {
+    serde_json::json!({
+        "tool_version": env!("CARGO_PKG_VERSION"),
+        "database": args.db,
+        "passed": !test.failed(),
+        "steps": test.steps,
+    })
+}
@@ -1,38 +1,355 @@
-use anyhow::{anyhow, Result};
-use reqwest::Client;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde_json::Value;
 
+/// How long a request may take when `--timeout` isn't given
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a failed schema request is retried when `--retries` isn't given
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// Wait before the first retry; doubled for each one after it
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
 pub struct SpacetimeClient {
     client: Client,
     base_url: String,
+    verbose: bool,
+    token: Option<String>,
+    timeout: Option<Duration>,
+    retries: u32,
 }
 
 impl SpacetimeClient {
-    pub fn new(server: &str) -> Result<Self> {
+    /// `timeout` bounds each request, body included; `None` waits forever
+    pub fn new(server: &str, timeout: Option<Duration>) -> Result<Self> {
         let base_url = get_server_url(server)?;
 
+        let mut builder = Client::builder();
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder
+            .build()
+            .context("Could not create the HTTP client")?;
+
         Ok(Self {
-            client: Client::new(),
+            client,
             base_url,
+            verbose: false,
+            token: None,
+            timeout,
+            retries: 0,
         })
     }
 
-    pub async fn fetch_schema(&self, database: &str, version: Option<String>) -> Result<Value> {
-        let version = version.unwrap_or_else(|| "9".to_string());
-        let url = format!(
-            "{}/v1/database/{}/schema?version={}",
-            self.base_url, database, version
-        );
+    /// Log each schema request URL, query string included, to stderr
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Send `token` as a bearer token with every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
 
-        let response = self.client.get(&url).send().await?;
+    /// Send the token `spacetime login` saved, for when no --token was
+    /// given. It is only sent to the server it was saved for: for any
+    /// other server there is a warning naming both and requests stay
+    /// anonymous, or with `strict` an error.
+    pub fn with_stored_token(self, strict: bool) -> Result<Self> {
+        let Some(stored) = stored_token()? else {
+            return Ok(self);
+        };
+        if same_host(&stored.url, &self.base_url) {
+            return Ok(self.with_token(stored.token));
+        }
+        let mismatch = token_mismatch(&stored, &self.base_url);
+        if strict {
+            return Err(anyhow!("{mismatch}"));
+        }
+        crate::output::status(format!("{} {mismatch}", crate::ascii::glyphs("⚠️")));
+        Ok(self)
+    }
+
+    /// Retry schema requests that fail with a connection error or a 5xx
+    /// response up to `retries` times
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Fetch the module definition. `extra_query` parameters are passed
+    /// through to the server as given (URL-encoded).
+    pub async fn fetch_schema(
+        &self,
+        database: &str,
+        version: Option<String>,
+        extra_query: &[(String, String)],
+    ) -> Result<Value> {
+        let response = self.schema_response(database, version, extra_query).await?;
+        let schema_text = response
+            .text()
+            .await
+            .map_err(|e| self.request_error(e, &self.schema_url(database)))?;
+        Ok(serde_json::from_str(&schema_text)?)
+    }
+
+    /// Fetch the module definition and hand the body to `sink` chunk by
+    /// chunk as it arrives, without buffering or parsing it. Returns the
+    /// number of bytes received.
+    pub async fn stream_schema(
+        &self,
+        database: &str,
+        version: Option<String>,
+        extra_query: &[(String, String)],
+        mut sink: impl FnMut(&[u8]) -> Result<()>,
+    ) -> Result<usize> {
+        let mut response = self.schema_response(database, version, extra_query).await?;
+        let mut received = 0;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| self.request_error(e, &self.schema_url(database)))?
+        {
+            received += chunk.len();
+            sink(&chunk)?;
+        }
+        Ok(received)
+    }
+
+    async fn schema_response(
+        &self,
+        database: &str,
+        version: Option<String>,
+        extra_query: &[(String, String)],
+    ) -> Result<Response> {
+        let url = self.schema_url(database);
+        let request = self.schema_request(database, version, extra_query)?;
+        if self.verbose {
+            if let Some(built) = request.try_clone().and_then(|r| r.build().ok()) {
+                crate::output::status(format!("GET {}", built.url()));
+            }
+        }
+        let response = self.send_with_retries(request, &url).await?;
 
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(self.unauthorized());
+        }
         if !response.status().is_success() {
             let error_text = response.text().await?;
             return Err(anyhow!("Schema fetch failed: {}", error_text));
         }
+        Ok(response)
+    }
 
-        let schema_text = response.text().await?;
-        Ok(serde_json::from_str(&schema_text)?)
+    /// The schema request: `version` first, then `extra_query` in order
+    fn schema_request(
+        &self,
+        database: &str,
+        version: Option<String>,
+        extra_query: &[(String, String)],
+    ) -> Result<RequestBuilder> {
+        if extra_query.iter().any(|(key, _)| key == "version") {
+            return Err(anyhow!(
+                "--query cannot set 'version'; use --schema-version instead"
+            ));
+        }
+
+        let version = version.unwrap_or_else(|| "9".to_string());
+        let url = self.schema_url(database);
+        Ok(self
+            .get(&url)
+            .query(&[("version", version)])
+            .query(extra_query))
+    }
+
+    /// Send `request`, retrying connection errors and 5xx responses with
+    /// exponential backoff. Client errors (4xx) and timeouts are returned
+    /// right away: asking again won't change the answer, or would only
+    /// multiply the wait.
+    async fn send_with_retries(&self, request: RequestBuilder, url: &str) -> Result<Response> {
+        let mut retry = 0;
+        loop {
+            let result = request
+                .try_clone()
+                .expect("GET requests have no streaming body")
+                .send()
+                .await;
+            let failure = match &result {
+                Ok(response) if response.status().is_server_error() => {
+                    Some(format!("server returned {}", response.status()))
+                }
+                Err(e) if !e.is_timeout() && !e.is_builder() => {
+                    // The innermost cause, e.g. "Connection refused"; the
+                    // outer ones only repeat the URL
+                    let mut cause: &dyn std::error::Error = e;
+                    while let Some(source) = cause.source() {
+                        cause = source;
+                    }
+                    Some(cause.to_string())
+                }
+                _ => None,
+            };
+            match failure {
+                Some(failure) if retry < self.retries => {
+                    let delay = RETRY_BACKOFF * 2u32.pow(retry.min(4));
+                    retry += 1;
+                    crate::output::status(format!(
+                        "{}  Request to {url} failed ({failure}); attempt {} of {} in {:.1}s",
+                        crate::ascii::glyphs("⚠️"),
+                        retry + 1,
+                        self.retries + 1,
+                        delay.as_secs_f32()
+                    ));
+                    tokio::time::sleep(delay).await;
+                }
+                _ => return result.map_err(|e| self.request_error(e, url)),
+            }
+        }
+    }
+
+    fn schema_url(&self, database: &str) -> String {
+        format!("{}/v1/database/{}/schema", self.base_url, database)
+    }
+
+    fn unauthorized(&self) -> anyhow::Error {
+        if self.token.is_some() {
+            anyhow!("Authentication failed: the server rejected the token")
+        } else {
+            anyhow!("Authentication required: pass --token or set SPACETIME_TOKEN")
+        }
+    }
+
+    /// Timeouts name the URL and the limit, so it's clear what to raise
+    fn request_error(&self, error: reqwest::Error, url: &str) -> anyhow::Error {
+        match self.timeout {
+            Some(timeout) if error.is_timeout() => anyhow!(
+                "Request to {url} timed out after {}s; raise the limit with --timeout (0 disables it)",
+                timeout.as_secs()
+            ),
+            _ => error.into(),
+        }
+    }
+
+    /// Names of the databases the server lists at `/v1/database`. Entries
+    /// may be plain strings or objects with a `name`, either as the whole
+    /// response or under a `databases` key.
+    pub async fn list_databases(&self) -> Result<Vec<String>> {
+        let url = format!("{}/v1/database", self.base_url);
+        let response = self
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| self.request_error(e, &url))?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED => return Err(self.unauthorized()),
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+                return Err(anyhow!(
+                    "This server does not offer a database listing ({url} returned {})",
+                    response.status()
+                ))
+            }
+            status if !status.is_success() => {
+                let error_text = response.text().await?;
+                return Err(anyhow!("Database listing failed: {}", error_text));
+            }
+            _ => {}
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .with_context(|| format!("{url} did not return JSON"))?;
+        let entries = match &body {
+            Value::Array(entries) => entries,
+            Value::Object(object) => match object.get("databases") {
+                Some(Value::Array(entries)) => entries,
+                _ => return Err(anyhow!("Unexpected database listing from {url}")),
+            },
+            _ => return Err(anyhow!("Unexpected database listing from {url}")),
+        };
+        Ok(entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Value::String(name) => Some(name.clone()),
+                Value::Object(object) => object
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Check that the server is up
+    pub async fn ping(&self) -> Result<()> {
+        let url = format!("{}/v1/ping", self.base_url);
+        let response = self
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| self.request_error(e, &url))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Ping failed with status {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Check which server endpoints this tool relies on are reachable.
+    ///
+    /// Endpoints with side effects (SQL, reducer calls) are probed with a
+    /// GET: a 405 response shows the route exists without running anything.
+    pub async fn probe_endpoints(&self, database: &str) -> Vec<EndpointProbe> {
+        let checks = [
+            ("ping", "/v1/ping".to_string(), false),
+            ("database info", format!("/v1/database/{database}"), false),
+            (
+                "schema",
+                format!("/v1/database/{database}/schema?version=9"),
+                false,
+            ),
+            ("sql", format!("/v1/database/{database}/sql"), true),
+            (
+                "reducer calls",
+                format!("/v1/database/{database}/call/__probe__"),
+                true,
+            ),
+            ("logs", format!("/v1/database/{database}/logs"), false),
+        ];
+
+        let mut probes = Vec::new();
+        for (name, path, route_only) in checks {
+            let url = format!("{}{}", self.base_url, path);
+            let result = self.get(&url).send().await;
+            let (status, availability) = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    (Some(status.as_u16()), classify_probe(status, route_only))
+                }
+                Err(_) => (None, Availability::Unreachable),
+            };
+            probes.push(EndpointProbe {
+                name,
+                path,
+                status,
+                availability,
+            });
+        }
+        probes
     }
 
     pub fn base_url(&self) -> &str {
@@ -40,6 +357,98 @@ impl SpacetimeClient {
     }
 }
 
+pub struct EndpointProbe {
+    pub name: &'static str,
+    pub path: String,
+    pub status: Option<u16>,
+    pub availability: Availability,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    Available,
+    RequiresAuth,
+    Unavailable,
+    Unreachable,
+}
+
+/// `pairs` as a query string, encoded the way the schema request sends them
+pub fn encode_query(pairs: &[(String, String)]) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").expect("static URL");
+    url.query_pairs_mut().extend_pairs(pairs);
+    url.query().unwrap_or_default().to_string()
+}
+
+fn classify_probe(status: StatusCode, route_only: bool) -> Availability {
+    match status {
+        s if s.is_success() => Availability::Available,
+        StatusCode::METHOD_NOT_ALLOWED if route_only => Availability::Available,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Availability::RequiresAuth,
+        _ => Availability::Unavailable,
+    }
+}
+
+/// The token `spacetime login` saved in the CLI config, and the server
+/// it belongs to: the config's `default_server`
+pub struct StoredToken {
+    pub token: String,
+    /// `default_server` as written, usually a nickname
+    pub server: String,
+    /// `server` resolved like --server
+    pub url: String,
+}
+
+/// The CLI config's `spacetimedb_token`, when there is one
+pub fn stored_token() -> Result<Option<StoredToken>> {
+    let Some(config) = read_cli_config()? else {
+        return Ok(None);
+    };
+    let Some(token) = config
+        .get("spacetimedb_token")
+        .and_then(|v| v.as_str())
+        .filter(|token| !token.is_empty())
+    else {
+        return Ok(None);
+    };
+    // The spacetime CLI's own default when none is configured
+    let server = config
+        .get("default_server")
+        .and_then(|v| v.as_str())
+        .unwrap_or("maincloud");
+    Ok(Some(StoredToken {
+        token: token.to_string(),
+        server: server.to_string(),
+        url: get_server_url(server)?,
+    }))
+}
+
+/// What to tell the user when the stored token is for another server
+pub fn token_mismatch(stored: &StoredToken, url: &str) -> String {
+    format!(
+        "The spacetime CLI token is for {} ({}), not {}, so it is not sent; \
+         pass --server {} to use it, or --token for this server",
+        stored.server,
+        host(&stored.url).unwrap_or_default(),
+        host(url).unwrap_or_default(),
+        stored.server
+    )
+}
+
+/// Whether two server URLs name the same host, ignoring case, scheme and port
+pub fn same_host(a: &str, b: &str) -> bool {
+    matches!((host(a), host(b)), (Some(a), Some(b)) if a == b)
+}
+
+fn host(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    // Local servers are reached under either name
+    Some(match host.as_str() {
+        "127.0.0.1" | "[::1]" => "localhost".to_string(),
+        _ => host,
+    })
+}
+
 /// Get server URL for a nickname (e.g., "local" -> <http://127.0.0.1:3000>)
 fn get_server_url(server: &str) -> Result<String> {
     // Handle full URLs
@@ -48,11 +457,7 @@ fn get_server_url(server: &str) -> Result<String> {
     }
 
     // Check SpacetimeDB CLI config for server nicknames
-    let cli_config_path = get_spacetime_cli_config_path()?;
-    if cli_config_path.exists() {
-        let content = std::fs::read_to_string(&cli_config_path)?;
-        let config: toml::Value = toml::from_str(&content)?;
-
+    if let Some(config) = read_cli_config()? {
         if let Some(server_configs) = config.get("server_configs").and_then(|v| v.as_array()) {
             for server_config in server_configs {
                 if let Some(nickname) = server_config.get("nickname").and_then(|v| v.as_str()) {
@@ -77,7 +482,89 @@ fn get_server_url(server: &str) -> Result<String> {
     }
 }
 
+/// The spacetime CLI's cli.toml, when it exists
+fn read_cli_config() -> Result<Option<toml::Value>> {
+    let cli_config_path = get_spacetime_cli_config_path()?;
+    if !cli_config_path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&cli_config_path)?;
+    Ok(Some(toml::from_str(&content)?))
+}
+
 fn get_spacetime_cli_config_path() -> Result<std::path::PathBuf> {
     let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
     Ok(home.join(".config").join("spacetime").join("cli.toml"))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> SpacetimeClient {
+        SpacetimeClient::new("http://localhost:3000", None).unwrap()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn extra_query_is_encoded_after_version() {
+        let query = pairs(&[("expand", "all"), ("filter", "a b&c=d"), ("name", "café/ü")]);
+        let request = client()
+            .schema_request("my_db", None, &query)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(request.url().path(), "/v1/database/my_db/schema");
+        assert_eq!(
+            request.url().query(),
+            Some("version=9&expand=all&filter=a+b%26c%3Dd&name=caf%C3%A9%2F%C3%BC")
+        );
+        let decoded: Vec<(String, String)> = request.url().query_pairs().into_owned().collect();
+        assert_eq!(decoded[1..], query[..]);
+    }
+
+    #[test]
+    fn encode_query_matches_the_request() {
+        let query = pairs(&[("filter", "a b&c=d"), ("name", "café")]);
+        let request = client()
+            .schema_request("my_db", Some("8".to_string()), &query)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().query().unwrap(),
+            format!("version=8&{}", encode_query(&query))
+        );
+    }
+
+    #[test]
+    fn query_cannot_set_version() {
+        let query = pairs(&[("expand", "all"), ("version", "8")]);
+        let error = client()
+            .schema_request("my_db", None, &query)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("--schema-version"), "{error}");
+    }
+
+    #[test]
+    fn hosts_compare_without_case_scheme_or_port() {
+        assert!(same_host(
+            "https://maincloud.spacetimedb.com",
+            "http://MainCloud.SpacetimeDB.com:443/"
+        ));
+        assert!(same_host("http://127.0.0.1:3000", "http://localhost:4000"));
+        assert!(!same_host(
+            "https://maincloud.spacetimedb.com",
+            "https://staging.spacetimedb.com"
+        ));
+        assert!(!same_host("not a url", "not a url"));
+    }
 }
\ No newline at end of file
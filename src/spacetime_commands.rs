@@ -0,0 +1,300 @@
+use serde_json::{json, Value};
+
+use crate::identifiers::sql_identifier;
+use crate::schema::sats_types::{
+    AlgebraicType, ProductType, ReducerDef, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{detect_spacetimedb_sum_type, detect_spacetimedb_type, is_option_type};
+
+/// Builds copy-pasteable `spacetime` CLI invocations for the database and
+/// server the current command was pointed at
+pub struct SpacetimeCommands {
+    pub db: String,
+    pub server: String,
+}
+
+impl SpacetimeCommands {
+    pub fn sql_sample(&self, table: &str, columns: &[&str]) -> String {
+        let columns = if columns.is_empty() {
+            "*".to_string()
+        } else {
+            columns
+                .iter()
+                .map(|c| sql_identifier(c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let query = format!("SELECT {columns} FROM {} LIMIT 10", sql_identifier(table));
+        format!("{} {}", self.prefix("sql"), shell_quote(&query))
+    }
+
+    pub fn subscribe(&self, table: &str) -> String {
+        let query = format!("SELECT * FROM {}", sql_identifier(table));
+        format!("{} {}", self.prefix("subscribe"), shell_quote(&query))
+    }
+
+    pub fn call(&self, schema: &SatsSchema, reducer: &ReducerDef) -> String {
+        let mut command = format!("{} {}", self.prefix("call"), shell_quote(&reducer.name));
+        for param in &reducer.params.elements {
+            let sample = sample_value(schema, &param.algebraic_type, &mut Vec::new());
+            command.push(' ');
+            command.push_str(&shell_quote(&sample.to_string()));
+        }
+        command
+    }
+
+    fn prefix(&self, subcommand: &str) -> String {
+        format!(
+            "spacetime {subcommand} --server {} {}",
+            shell_quote(&self.server),
+            shell_quote(&self.db)
+        )
+    }
+}
+
+pub fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Placeholder argument in SATS-JSON form, typed after the parameter
+fn sample_value(schema: &SatsSchema, alg_type: &AlgebraicType, visiting: &mut Vec<u32>) -> Value {
+    match alg_type {
+        AlgebraicType::Bool { .. } => json!(false),
+        AlgebraicType::String { .. } => json!(""),
+        AlgebraicType::F32 { .. } | AlgebraicType::F64 { .. } => json!(0.0),
+        AlgebraicType::Array { .. } | AlgebraicType::Map { .. } => json!([]),
+        AlgebraicType::Ref { Ref } => {
+            if visiting.contains(Ref) {
+                return Value::Null;
+            }
+            visiting.push(*Ref);
+            let value = match schema.typespace.types.get(*Ref as usize) {
+                Some(TypeDef::Product { Product }) => sample_product(schema, Product, visiting),
+                Some(TypeDef::Sum { Sum }) => sample_sum(schema, Sum, visiting),
+                _ => Value::Null,
+            };
+            visiting.pop();
+            value
+        }
+        AlgebraicType::Product { Product } => sample_product(schema, Product, visiting),
+        AlgebraicType::Sum { Sum } => sample_sum(schema, Sum, visiting),
+        // Every remaining variant is an integer
+        _ => json!(0),
+    }
+}
+
+fn sample_sum(schema: &SatsSchema, sum: &SumType, visiting: &mut Vec<u32>) -> Value {
+    if detect_spacetimedb_sum_type(sum).is_some() {
+        // ScheduledAt: run once at the epoch
+        return json!({ "Time": { "__timestamp_micros_since_unix_epoch__": 0 } });
+    }
+    if is_option_type(sum) {
+        return json!({ "none": [] });
+    }
+
+    match sum.variants.first() {
+        Some(variant) => {
+            let name = variant.name.as_option().unwrap_or("0");
+            let payload = sample_value(schema, &variant.algebraic_type, visiting);
+            json!({ name: payload })
+        }
+        None => Value::Null,
+    }
+}
+
+fn sample_product(schema: &SatsSchema, product: &ProductType, visiting: &mut Vec<u32>) -> Value {
+    match detect_spacetimedb_type(product).as_deref() {
+        Some("Identity") => {
+            return json!({ "__identity__": format!("0x{}", "0".repeat(64)) });
+        }
+        Some("Timestamp") => return json!({ "__timestamp_micros_since_unix_epoch__": 0 }),
+        Some("Duration") => return json!({ "__time_duration_micros__": 0 }),
+        _ => {}
+    }
+
+    if product.elements.is_empty() {
+        // Unit, e.g. the payload of a unit enum variant
+        return json!([]);
+    }
+
+    let mut fields = serde_json::Map::new();
+    for (i, element) in product.elements.iter().enumerate() {
+        let name = element
+            .name
+            .as_option()
+            .map_or_else(|| i.to_string(), str::to_string);
+        fields.insert(
+            name,
+            sample_value(schema, &element.algebraic_type, visiting),
+        );
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, demo_schema_with, recursive_schema, schema};
+
+    fn commands() -> SpacetimeCommands {
+        SpacetimeCommands {
+            db: "game".to_string(),
+            server: "http://localhost:3000".to_string(),
+        }
+    }
+
+    /// The game module plus reducers taking an enum, an Identity and both
+    fn schema_with_extra_reducers() -> SatsSchema {
+        demo_schema_with(|document| {
+            let player_status = json!({"Ref": 1});
+            // The `identity` column of the player row
+            let identity = document["typespace"]["types"][5]["Product"]["elements"][1]
+                ["algebraic_type"]
+                .clone();
+            let param =
+                |name: &str, ty: &Value| json!({"name": {"some": name}, "algebraic_type": ty});
+            let reducers = document["reducers"].as_array_mut().unwrap();
+            reducers.push(json!({
+                "name": "set_status",
+                "params": {"elements": [param("status", &player_status)]},
+            }));
+            reducers.push(json!({
+                "name": "kick",
+                "params": {"elements": [param("target", &identity), param("reason", &player_status)]},
+            }));
+            reducers.push(json!({"name": "it's", "params": {"elements": []}}));
+        })
+    }
+
+    fn call(schema: &SatsSchema, reducer: &str) -> String {
+        let reducer = schema.reducers.iter().find(|r| r.name == reducer).unwrap();
+        commands().call(schema, reducer)
+    }
+
+    #[test]
+    fn shell_quoting() {
+        assert_eq!(shell_quote("demo"), "demo");
+        assert_eq!(
+            shell_quote("http://localhost:3000"),
+            "http://localhost:3000"
+        );
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn sql_sample_quotes_names_that_need_it() {
+        assert_eq!(
+            commands().sql_sample("player", &["id", "name"]),
+            "spacetime sql --server http://localhost:3000 game 'SELECT id, name FROM player LIMIT 10'"
+        );
+        assert_eq!(
+            commands().sql_sample("user-data", &["select", "it's"]),
+            "spacetime sql --server http://localhost:3000 game \
+             'SELECT \"select\", \"it'\\''s\" FROM \"user-data\" LIMIT 10'"
+        );
+        assert_eq!(
+            commands().sql_sample("player", &[]),
+            "spacetime sql --server http://localhost:3000 game 'SELECT * FROM player LIMIT 10'"
+        );
+    }
+
+    #[test]
+    fn subscribe_embeds_server_and_database() {
+        let commands = SpacetimeCommands {
+            db: "my db".to_string(),
+            server: "https://maincloud.spacetimedb.com".to_string(),
+        };
+        assert_eq!(
+            commands.subscribe("player"),
+            "spacetime subscribe --server https://maincloud.spacetimedb.com 'my db' 'SELECT * FROM player'"
+        );
+    }
+
+    #[test]
+    fn call_uses_the_first_variant_of_an_enum() {
+        let schema = schema_with_extra_reducers();
+        assert_eq!(
+            call(&schema, "set_status"),
+            "spacetime call --server http://localhost:3000 game set_status '{\"Online\":[]}'"
+        );
+    }
+
+    #[test]
+    fn call_uses_a_zero_identity() {
+        let schema = schema_with_extra_reducers();
+        let identity = format!("{{\"__identity__\":\"0x{}\"}}", "0".repeat(64));
+        assert_eq!(
+            call(&schema, "kick"),
+            format!(
+                "spacetime call --server http://localhost:3000 game kick '{identity}' '{{\"Online\":[]}}'"
+            )
+        );
+    }
+
+    #[test]
+    fn call_quotes_reducer_names() {
+        let schema = schema_with_extra_reducers();
+        assert_eq!(
+            call(&schema, "it's"),
+            "spacetime call --server http://localhost:3000 game 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn call_samples_special_and_recursive_types() {
+        let schema = demo_schema();
+        assert_eq!(
+            call(&schema, "tick"),
+            "spacetime call --server http://localhost:3000 game tick \
+             '{\"scheduled_at\":{\"Time\":{\"__timestamp_micros_since_unix_epoch__\":0}},\
+             \"scheduled_id\":0,\"tick_rate\":{\"__time_duration_micros__\":0}}'"
+        );
+        // ItemStack refers to itself through an Option
+        assert_eq!(
+            call(&schema, "give_item"),
+            "spacetime call --server http://localhost:3000 game give_item 0 \
+             '{\"count\":0,\"item_id\":0,\"next\":{\"none\":[]}}'"
+        );
+        assert_eq!(
+            call(&schema, "init"),
+            "spacetime call --server http://localhost:3000 game init"
+        );
+    }
+
+    #[test]
+    fn sample_values_break_direct_recursion() {
+        let schema = schema(json!({
+            "typespace": {"types": [{"Product": {"elements": [
+                {"name": {"some": "child"}, "algebraic_type": {"Ref": 0}},
+                {"name": {"none": []}, "algebraic_type": {"Bool": []}},
+            ]}}]},
+            "tables": [],
+            "reducers": [],
+            "types": [],
+        }));
+        assert_eq!(
+            sample_value(&schema, &AlgebraicType::Ref { Ref: 0 }, &mut Vec::new()),
+            json!({"child": null, "1": false})
+        );
+    }
+
+    #[test]
+    fn call_samples_recursive_arrays_as_empty() {
+        let schema = recursive_schema();
+        assert_eq!(
+            call(&schema, "plant"),
+            "spacetime call --server http://localhost:3000 game plant '{\"children\":[],\"value\":0}'"
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::identifiers::postgres_identifier;
+use crate::schema::sats_types::{AlgebraicType, BuiltinType, SatsSchema, SumType, TypeDef};
+use crate::schema::{
+    build_type_names, detect_spacetimedb_sum_type, detect_spacetimedb_type, format_type,
+    get_option_inner_type, is_option_type,
+};
+
+/// A column's PostgreSQL type. `note` explains a `jsonb` fallback.
+struct SqlColumn {
+    ty: String,
+    nullable: bool,
+    note: Option<String>,
+}
+
+impl SqlColumn {
+    fn plain(ty: &str) -> Self {
+        Self {
+            ty: ty.to_string(),
+            nullable: false,
+            note: None,
+        }
+    }
+
+    fn json(original: String) -> Self {
+        Self {
+            ty: "jsonb".to_string(),
+            nullable: false,
+            note: Some(format!("SATS type: {original}")),
+        }
+    }
+}
+
+/// Render `CREATE TABLE` statements for PostgreSQL, one per table (or just
+/// the `--table` one).
+///
+/// Unsigned integers get the next wider signed type (`numeric` for u64 and
+/// up), `Option<T>` becomes a nullable column and everything else is
+/// `NOT NULL`. Structs, enums and arrays have no direct SQL equivalent and
+/// are stored as `jsonb`, with a comment giving the original type. A column
+/// holding another table's row type also names that table, since SATS has
+/// no foreign keys to declare the relationship with.
+pub fn generate_sql(schema: &SatsSchema, table: Option<&str>) -> Result<String> {
+    let tables: Vec<_> = match table {
+        Some(name) => {
+            let table = schema
+                .tables
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+            vec![table]
+        }
+        None => schema.tables.iter().collect(),
+    };
+    let type_names = build_type_names(schema);
+    let mut row_tables: HashMap<usize, &str> = HashMap::new();
+    for table in &schema.tables {
+        row_tables
+            .entry(table.product_type_ref)
+            .or_insert(table.name.as_str());
+    }
+
+    let mut out = String::new();
+    for table in tables {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let Some(TypeDef::Product { Product }) = schema.typespace.types.get(table.product_type_ref)
+        else {
+            let _ = writeln!(
+                out,
+                "-- Skipped table {}: its row type (typespace entry {}) is not a product",
+                table.name, table.product_type_ref
+            );
+            continue;
+        };
+
+        let mut lines: Vec<(String, Option<String>)> = Vec::new();
+        let mut names = Vec::new();
+        for (i, element) in Product.elements.iter().enumerate() {
+            let name = postgres_identifier(
+                &element
+                    .name
+                    .as_option()
+                    .map_or_else(|| format!("col_{i}"), str::to_string),
+            );
+            let column = column_type(schema, &type_names, &element.algebraic_type);
+            let null = if column.nullable { "" } else { " NOT NULL" };
+            let note = match referenced_table(&row_tables, &element.algebraic_type) {
+                Some(other) => Some(format!(
+                    "row of table {other}, embedded (SATS has no foreign keys){}",
+                    column.note.map(|n| format!("; {n}")).unwrap_or_default()
+                )),
+                None => column.note,
+            };
+            lines.push((format!("{name} {}{null}", column.ty), note));
+            names.push(name);
+        }
+        if !table.primary_key.is_empty() {
+            let key: Vec<_> = table
+                .primary_key
+                .iter()
+                .filter_map(|&i| names.get(i).cloned())
+                .collect();
+            lines.push((format!("PRIMARY KEY ({})", key.join(", ")), None));
+        }
+
+        let _ = writeln!(out, "CREATE TABLE {} (", postgres_identifier(&table.name));
+        let last = lines.len().saturating_sub(1);
+        for (i, (line, note)) in lines.iter().enumerate() {
+            let comma = if i == last { "" } else { "," };
+            match note {
+                Some(note) => {
+                    let _ = writeln!(out, "    {line}{comma} -- {note}");
+                }
+                None => {
+                    let _ = writeln!(out, "    {line}{comma}");
+                }
+            }
+        }
+        out.push_str(");\n");
+    }
+    Ok(out)
+}
+
+/// The table whose row type `alg_type` refers to, looking through an option
+fn referenced_table<'a>(
+    row_tables: &HashMap<usize, &'a str>,
+    alg_type: &AlgebraicType,
+) -> Option<&'a str> {
+    match alg_type {
+        AlgebraicType::Ref { Ref } => row_tables.get(&(*Ref as usize)).copied(),
+        AlgebraicType::Sum { Sum } if is_option_type(Sum) => {
+            referenced_table(row_tables, get_option_inner_type(Sum)?)
+        }
+        _ => None,
+    }
+}
+
+fn column_type(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    alg_type: &AlgebraicType,
+) -> SqlColumn {
+    match alg_type {
+        AlgebraicType::Bool { .. } => SqlColumn::plain("boolean"),
+        AlgebraicType::I8 { .. } | AlgebraicType::U8 { .. } | AlgebraicType::I16 { .. } => {
+            SqlColumn::plain("smallint")
+        }
+        AlgebraicType::U16 { .. } | AlgebraicType::I32 { .. } => SqlColumn::plain("integer"),
+        AlgebraicType::U32 { .. } | AlgebraicType::I64 { .. } => SqlColumn::plain("bigint"),
+        AlgebraicType::U64 { .. }
+        | AlgebraicType::I128 { .. }
+        | AlgebraicType::U128 { .. }
+        | AlgebraicType::I256 { .. }
+        | AlgebraicType::U256 { .. } => SqlColumn::plain("numeric"),
+        AlgebraicType::F32 { .. } => SqlColumn::plain("real"),
+        AlgebraicType::F64 { .. } => SqlColumn::plain("double precision"),
+        AlgebraicType::String { .. } => SqlColumn::plain("text"),
+        AlgebraicType::Array { Array } if matches!(**Array, AlgebraicType::U8 { .. }) => {
+            SqlColumn::plain("bytea")
+        }
+        AlgebraicType::Array { .. } | AlgebraicType::Map { .. } => {
+            SqlColumn::json(format_type(schema, alg_type, type_names))
+        }
+        AlgebraicType::Product { Product } => product_column(
+            schema,
+            detect_spacetimedb_type(Product),
+            alg_type,
+            type_names,
+        ),
+        AlgebraicType::Sum { Sum } => sum_column(schema, type_names, Sum, alg_type),
+        AlgebraicType::Ref { Ref } => ref_type(schema, type_names, *Ref as usize, alg_type),
+    }
+}
+
+/// Look through a ref: a named struct or enum is still `jsonb`, but refs to
+/// SpacetimeDB types, options and builtins map like their inline forms
+fn ref_type(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    idx: usize,
+    alg_type: &AlgebraicType,
+) -> SqlColumn {
+    match schema.typespace.types.get(idx) {
+        Some(TypeDef::Product { Product }) => product_column(
+            schema,
+            detect_spacetimedb_type(Product),
+            alg_type,
+            type_names,
+        ),
+        Some(TypeDef::Sum { Sum }) => sum_column(schema, type_names, Sum, alg_type),
+        Some(TypeDef::Builtin { Builtin }) => match Builtin {
+            BuiltinType::Bool { .. } => SqlColumn::plain("boolean"),
+            BuiltinType::I8 { .. } | BuiltinType::U8 { .. } | BuiltinType::I16 { .. } => {
+                SqlColumn::plain("smallint")
+            }
+            BuiltinType::U16 { .. } | BuiltinType::I32 { .. } => SqlColumn::plain("integer"),
+            BuiltinType::U32 { .. } | BuiltinType::I64 { .. } => SqlColumn::plain("bigint"),
+            BuiltinType::U64 { .. }
+            | BuiltinType::I128 { .. }
+            | BuiltinType::U128 { .. }
+            | BuiltinType::I256 { .. }
+            | BuiltinType::U256 { .. } => SqlColumn::plain("numeric"),
+            BuiltinType::F32 { .. } => SqlColumn::plain("real"),
+            BuiltinType::F64 { .. } => SqlColumn::plain("double precision"),
+            BuiltinType::String { .. } => SqlColumn::plain("text"),
+            BuiltinType::Array { .. } | BuiltinType::Map { .. } => {
+                SqlColumn::json(format_type(schema, alg_type, type_names))
+            }
+        },
+        // Aliases are not followed, so a cyclic chain can't loop
+        Some(TypeDef::Ref { .. }) | None => {
+            SqlColumn::json(format_type(schema, alg_type, type_names))
+        }
+    }
+}
+
+/// `special` is what `detect_spacetimedb_type` found, if anything
+fn product_column(
+    schema: &SatsSchema,
+    special: Option<String>,
+    alg_type: &AlgebraicType,
+    type_names: &HashMap<usize, String>,
+) -> SqlColumn {
+    match special.as_deref() {
+        Some("Identity") => SqlColumn::plain("bytea"),
+        Some("Timestamp") => SqlColumn::plain("timestamptz"),
+        Some("Duration") => SqlColumn::plain("interval"),
+        _ => SqlColumn::json(format_type(schema, alg_type, type_names)),
+    }
+}
+
+/// Options become nullable columns of their inner type; other enums,
+/// `ScheduledAt` included, are `jsonb`
+fn sum_column(
+    schema: &SatsSchema,
+    type_names: &HashMap<usize, String>,
+    sum: &SumType,
+    alg_type: &AlgebraicType,
+) -> SqlColumn {
+    if detect_spacetimedb_sum_type(sum).is_none() && is_option_type(sum) {
+        if let Some(inner) = get_option_inner_type(sum) {
+            return SqlColumn {
+                nullable: true,
+                ..column_type(schema, type_names, inner)
+            };
+        }
+    }
+    SqlColumn::json(format_type(schema, alg_type, type_names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, demo_schema_with, recursive_schema};
+    use serde_json::json;
+
+    #[test]
+    fn unsigned_integers_get_the_next_wider_type() {
+        let sql = generate_sql(&demo_schema(), Some("item_kind")).unwrap();
+        assert_eq!(
+            sql,
+            concat!(
+                "CREATE TABLE item_kind (\n",
+                "    id bigint NOT NULL,\n",
+                "    name text NOT NULL,\n",
+                "    rarity jsonb NOT NULL, -- SATS type: Rarity\n",
+                "    stack_size integer NOT NULL,\n",
+                "    description text,\n",
+                "    PRIMARY KEY (id)\n",
+                ");\n",
+            )
+        );
+    }
+
+    #[test]
+    fn spacetimedb_types_and_composite_keys() {
+        let sql = generate_sql(&demo_schema(), None).unwrap();
+        assert!(sql.contains("    id numeric NOT NULL,\n"), "{sql}");
+        assert!(sql.contains("    identity bytea NOT NULL,\n"), "{sql}");
+        assert!(
+            sql.contains("    created_at timestamptz NOT NULL,\n"),
+            "{sql}"
+        );
+        assert!(
+            sql.contains("    PRIMARY KEY (guild_id, player_id)\n"),
+            "{sql}"
+        );
+        assert_eq!(sql.matches("CREATE TABLE").count(), 6);
+    }
+
+    #[test]
+    fn tables_without_a_primary_key_end_at_the_last_column() {
+        let sql = generate_sql(&recursive_schema(), None).unwrap();
+        assert!(
+            sql.ends_with("    children jsonb NOT NULL -- SATS type: Vec<Node>\n);\n"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn columns_holding_a_row_name_its_table() {
+        // player embeds a game_tick row, directly and as an option
+        let schema = demo_schema_with(|schema| {
+            let player = &mut schema["typespace"]["types"][5]["Product"]["elements"];
+            player.as_array_mut().unwrap().extend([
+                json!({"name": {"some": "last_tick"}, "algebraic_type": {"Ref": 10}}),
+                json!({"name": {"some": "next_tick"}, "algebraic_type": {"Sum": {"variants": [
+                    {"name": {"some": "some"}, "algebraic_type": {"Ref": 10}},
+                    {"name": {"some": "none"}, "algebraic_type": {"Product": {"elements": []}}},
+                ]}}}),
+            ]);
+        });
+        let sql = generate_sql(&schema, Some("player")).unwrap();
+        assert!(
+            sql.contains(
+                "    last_tick jsonb NOT NULL, -- row of table game_tick, embedded (SATS has no foreign keys); SATS type: GameTick\n"
+            ),
+            "{sql}"
+        );
+        assert!(
+            sql.contains(
+                "    next_tick jsonb, -- row of table game_tick, embedded (SATS has no foreign keys); SATS type: GameTick\n"
+            ),
+            "{sql}"
+        );
+        assert!(!generate_sql(&demo_schema(), None)
+            .unwrap()
+            .contains("row of table"));
+    }
+
+    #[test]
+    fn unknown_table_is_an_error() {
+        let error = generate_sql(&demo_schema(), Some("nope")).unwrap_err();
+        assert_eq!(error.to_string(), "Table 'nope' not found");
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use handlebars::Handlebars;
+use serde_json::{json, Value};
+
+use crate::schema::sats_types::{SatsSchema, TypeDef};
+use crate::schema::{is_nullable, SchemaStats};
+
+/// SpacetimeDB's own types, as the keys of the context's `well_known` object
+const WELL_KNOWN: [&str; 4] = ["Identity", "Timestamp", "Duration", "ScheduledAt"];
+
+/// What a --format template template is rendered with: the
+/// `--format resolved` document (tables, structs and enums with their
+/// types written out), plus `database`, a `primary_key` and `optional`
+/// flag on every table field, and `well_known.<Name>` telling whether the
+/// schema uses each SpacetimeDB type anywhere.
+pub fn template_context(schema: &SatsSchema, db: &str) -> Value {
+    let mut context = crate::resolved::to_resolved_json(schema);
+
+    if let Some(tables) = context["tables"].as_array_mut() {
+        for (table, info) in tables.iter_mut().zip(&schema.tables) {
+            let Some(TypeDef::Product { Product }) =
+                schema.typespace.types.get(info.product_type_ref)
+            else {
+                continue;
+            };
+            let Some(fields) = table["fields"].as_array_mut() else {
+                continue;
+            };
+            for (i, (field, element)) in fields.iter_mut().zip(&Product.elements).enumerate() {
+                field["primary_key"] = json!(info.primary_key.contains(&i));
+                field["optional"] = json!(is_nullable(schema, &element.algebraic_type));
+            }
+        }
+    }
+
+    let used = SchemaStats::new(schema).well_known;
+    context["well_known"] = WELL_KNOWN
+        .iter()
+        .map(|name| (name.to_string(), json!(used.contains(*name))))
+        .collect();
+    context["database"] = json!(db);
+    context
+}
+
+/// Render a Handlebars template file with `context`. Output is not
+/// HTML-escaped, and a misspelled variable is an error rather than an
+/// empty string; both kinds of error name the line and column.
+pub fn render_template(path: &Path, context: &Value) -> Result<String> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template {}", path.display()))?;
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_escape_fn(handlebars::no_escape);
+
+    let name = path.display().to_string();
+    handlebars
+        .register_template_string(&name, source)
+        .map_err(|e| anyhow!("{e}"))?;
+    handlebars
+        .render(&name, context)
+        .map_err(|e| anyhow!("{e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    #[test]
+    fn fields_are_flagged_as_keys_and_optional() {
+        let context = template_context(&demo_schema(), "game");
+        assert_eq!(context["database"], "game");
+        let guild = context["tables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "guild")
+            .unwrap();
+        let flags: Vec<_> = guild["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| (f["primary_key"].clone(), f["optional"].clone()))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                (json!(true), json!(false)),
+                (json!(false), json!(false)),
+                (json!(false), json!(false)),
+                (json!(false), json!(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn well_known_flags_cover_every_spacetimedb_type() {
+        let context = template_context(&demo_schema(), "game");
+        assert_eq!(
+            context["well_known"],
+            json!({"Identity": true, "Timestamp": true, "Duration": true, "ScheduledAt": true})
+        );
+        let context = template_context(&recursive_schema(), "trees");
+        assert_eq!(context["well_known"]["Identity"], false);
+    }
+}
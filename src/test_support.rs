@@ -0,0 +1,146 @@
+//! Schemas and arguments shared by the unit tests
+//!
+//! `demo_schema` is the schema `--demo` shows, a real module's, for tests
+//! of what a typical database renders as. `args` builds the arguments a
+//! command line would. The builders make synthetic schemas for the
+//! edges: huge numbers of tables, very wide tables, very large enums and
+//! recursive types. Every builder names its types, so renderers show
+//! readable names. `CountingWriter` is a sink for checking that a
+//! renderer streams its output.
+
+use clap::Parser;
+use serde_json::{json, Value};
+
+use crate::schema::sats_types::SatsSchema;
+use crate::schema::SchemaArgs;
+
+pub(crate) use crate::schema::DEMO_SCHEMA;
+
+/// The schema `--demo` shows: players, items, guilds, chat and a
+/// `game_tick` table scheduled on the `tick` reducer
+pub fn demo_schema() -> SatsSchema {
+    demo_schema_with(|_| {})
+}
+
+/// The demo schema with `edit` applied to the JSON before parsing
+pub fn demo_schema_with(edit: impl FnOnce(&mut Value)) -> SatsSchema {
+    let mut document: Value = serde_json::from_str(DEMO_SCHEMA).expect("fixture is JSON");
+    edit(&mut document);
+    schema(document)
+}
+
+/// A schema from the JSON given inline, for fixtures written in the test
+pub fn schema(document: Value) -> SatsSchema {
+    serde_json::from_value(document).expect("fixture parses")
+}
+
+/// `SchemaArgs` as `main` builds them for `--demo` followed by `flags`
+pub fn args(flags: &[&str]) -> SchemaArgs {
+    let argv = ["spacetime-schema-tool", "--demo"].iter().chain(flags);
+    crate::schema_args(crate::Cli::parse_from(argv)).expect("valid flags")
+}
+
+/// `tables` tables, each with its own three-field row struct referring to
+/// one shared enum, for checking how renderers cope with huge schemas
+pub fn wide_schema(tables: usize) -> SatsSchema {
+    let status = tables;
+    let mut types = Vec::new();
+    let mut names = Vec::new();
+    let mut table_defs = Vec::new();
+    for i in 0..tables {
+        types.push(json!({"Product": {"elements": [
+            {"name": {"some": "id"}, "algebraic_type": {"U64": []}},
+            {"name": {"some": "label"}, "algebraic_type": {"String": []}},
+            {"name": {"some": "status"}, "algebraic_type": {"Ref": status}},
+        ]}}));
+        names.push(json!({"name": {"scope": [], "name": format!("Row{i}")}, "ty": i, "custom_ordering": true}));
+        table_defs.push(json!({
+            "name": format!("table_{i}"),
+            "product_type_ref": i,
+            "primary_key": [0],
+        }));
+    }
+    types.push(json!({"Sum": {"variants": [
+        {"name": {"some": "Active"}, "algebraic_type": {"Product": {"elements": []}}},
+        {"name": {"some": "Retired"}, "algebraic_type": {"Product": {"elements": []}}},
+    ]}}));
+    names.push(
+        json!({"name": {"scope": [], "name": "Status"}, "ty": status, "custom_ordering": true}),
+    );
+    schema(json!({
+        "typespace": {"types": types},
+        "tables": table_defs,
+        "types": names,
+    }))
+}
+
+/// A `wide` table whose `Wide` row has `fields` u32 columns `f0`, `f1`, ...
+pub fn wide_table_schema(fields: usize) -> SatsSchema {
+    let elements: Vec<_> = (0..fields)
+        .map(|i| json!({"name": {"some": format!("f{i}")}, "algebraic_type": {"U32": []}}))
+        .collect();
+    schema(json!({
+        "typespace": {"types": [{"Product": {"elements": elements}}]},
+        "tables": [{"name": "wide", "product_type_ref": 0, "primary_key": [0]}],
+        "types": [{"name": {"scope": [], "name": "Wide"}, "ty": 0, "custom_ordering": true}],
+    }))
+}
+
+/// A `text` table whose `key` column is a `LocalizationKey` enum with
+/// `variants` unit variants (`Key0`, `Key1`, ...), like a generated
+/// localization enum
+pub fn big_enum_schema(variants: usize) -> SatsSchema {
+    let variants: Vec<_> = (0..variants)
+        .map(|i| json!({"name": {"some": format!("Key{i}")}, "algebraic_type": {"Product": {"elements": []}}}))
+        .collect();
+    schema(json!({
+        "typespace": {"types": [
+            {"Product": {"elements": [
+                {"name": {"some": "id"}, "algebraic_type": {"U32": []}},
+                {"name": {"some": "key"}, "algebraic_type": {"Ref": 1}},
+            ]}},
+            {"Sum": {"variants": variants}},
+        ]},
+        "tables": [{"name": "text", "product_type_ref": 0, "primary_key": [0]}],
+        "types": [
+            {"name": {"scope": [], "name": "Text"}, "ty": 0, "custom_ordering": true},
+            {"name": {"scope": [], "name": "LocalizationKey"}, "ty": 1, "custom_ordering": true},
+        ],
+    }))
+}
+
+/// A `tree` table of `Node { value: i32, children: Vec<Node> }` rows: a
+/// type that refers to itself
+pub fn recursive_schema() -> SatsSchema {
+    schema(json!({
+        "typespace": {"types": [{"Product": {"elements": [
+            {"name": {"some": "value"}, "algebraic_type": {"I32": []}},
+            {"name": {"some": "children"}, "algebraic_type": {"Array": {"Ref": 0}}},
+        ]}}]},
+        "tables": [{"name": "tree", "product_type_ref": 0, "primary_key": []}],
+        "reducers": [{"name": "plant", "params": {"elements": [
+            {"name": {"some": "root"}, "algebraic_type": {"Ref": 0}},
+        ]}}],
+        "types": [{"name": {"scope": [], "name": "Node"}, "ty": 0, "custom_ordering": true}],
+    }))
+}
+
+/// Records how much was written and the largest single write, to check
+/// that a renderer streams instead of building the document in memory
+#[derive(Default)]
+pub struct CountingWriter {
+    pub total: usize,
+    pub largest_write: usize,
+}
+
+impl std::io::Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.total += buf.len();
+        self.largest_write = self.largest_write.max(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::identifiers::{string_literal, typescript_key};
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{
+    detect_spacetimedb_sum_type, detect_spacetimedb_type, get_option_inner_type, is_option_type,
+};
+use crate::zod::constant_names;
+
+/// Longest union written on a single line
+const UNION_LINE_WIDTH: usize = 80;
+
+/// Aliases for SpacetimeDB's own types, declared at the top of the file when used
+const SPECIAL_ALIASES: &[(&str, &str)] = &[
+    ("Identity", "/** Hex-encoded 256-bit identity */\nexport type Identity = string;"),
+    ("Timestamp", "export type Timestamp = Date;"),
+    ("Duration", "/** Microseconds */\nexport type Duration = bigint;"),
+    (
+        "ScheduledAt",
+        "export type ScheduledAt = { tag: \"Interval\"; value: Duration } | { tag: \"Time\"; value: Timestamp };",
+    ),
+];
+
+/// Render TypeScript type declarations for the schema: an `export interface`
+/// per table row type and struct, and a union type per enum (string
+/// literals when no variant carries data, `{ tag, value }` objects
+/// otherwise). Declarations are sorted by name so the output diffs cleanly.
+pub fn generate_typescript(schema: &SatsSchema) -> String {
+    let (names, renamed) = constant_names(schema);
+    let mut generator = TypeScriptGenerator {
+        schema,
+        names: &names,
+        specials: BTreeSet::new(),
+    };
+
+    let mut entries: Vec<usize> = (0..schema.typespace.types.len())
+        .filter(|idx| special_name(schema, *idx).is_none())
+        .collect();
+    entries.sort_by(|a, b| names[a].cmp(&names[b]));
+
+    let mut body = String::new();
+    for idx in entries {
+        let name = &names[&idx];
+        body.push('\n');
+        if let Some(original) = renamed.get(&idx) {
+            let _ = writeln!(
+                body,
+                "// {} is exported as {name}",
+                string_literal(original)
+            );
+        }
+        let declaration = match &schema.typespace.types[idx] {
+            TypeDef::Product { Product } if !is_tuple(Product) => {
+                format!("export interface {name} {}", generator.object(Product, 0))
+            }
+            def => {
+                let body = generator.type_def(def);
+                let separator = if body.starts_with('\n') { "" } else { " " };
+                format!("export type {name} ={separator}{body};")
+            }
+        };
+        body.push_str(&declaration);
+        body.push('\n');
+    }
+
+    // ScheduledAt is written in terms of the other two
+    if generator.specials.contains("ScheduledAt") {
+        generator.specials.insert("Duration");
+        generator.specials.insert("Timestamp");
+    }
+    let mut out = String::new();
+    for (name, alias) in SPECIAL_ALIASES {
+        if generator.specials.contains(name) {
+            let _ = writeln!(out, "{alias}");
+        }
+    }
+    out.push_str(&body);
+    out
+}
+
+/// What `detect_spacetimedb_type` calls the typespace entry, if it is one
+/// of SpacetimeDB's own types
+fn special_name(schema: &SatsSchema, idx: usize) -> Option<String> {
+    match schema.typespace.types.get(idx)? {
+        TypeDef::Product { Product } => detect_spacetimedb_type(Product),
+        TypeDef::Sum { Sum } => detect_spacetimedb_sum_type(Sum),
+        _ => None,
+    }
+}
+
+/// Products whose fields are all unnamed are written as tuples
+fn is_tuple(product: &ProductType) -> bool {
+    !product.elements.is_empty()
+        && product
+            .elements
+            .iter()
+            .all(|e| e.name.as_option().is_none())
+}
+
+struct TypeScriptGenerator<'a> {
+    schema: &'a SatsSchema,
+    names: &'a HashMap<usize, String>,
+    /// SpacetimeDB types referenced, whose aliases go at the top
+    specials: BTreeSet<&'static str>,
+}
+
+impl TypeScriptGenerator<'_> {
+    fn type_def(&mut self, def: &TypeDef) -> String {
+        match def {
+            TypeDef::Product { Product } => self.product(Product, 0),
+            TypeDef::Sum { Sum } => self.sum(Sum, 0),
+            TypeDef::Builtin { Builtin } => match Builtin {
+                BuiltinType::Bool { .. } => "boolean".to_string(),
+                BuiltinType::I8 { .. }
+                | BuiltinType::U8 { .. }
+                | BuiltinType::I16 { .. }
+                | BuiltinType::U16 { .. }
+                | BuiltinType::I32 { .. }
+                | BuiltinType::U32 { .. }
+                | BuiltinType::F32 { .. }
+                | BuiltinType::F64 { .. } => "number".to_string(),
+                BuiltinType::I64 { .. }
+                | BuiltinType::U64 { .. }
+                | BuiltinType::I128 { .. }
+                | BuiltinType::U128 { .. }
+                | BuiltinType::I256 { .. }
+                | BuiltinType::U256 { .. } => "bigint".to_string(),
+                BuiltinType::String { .. } => "string".to_string(),
+                BuiltinType::Array { Array } => self.array(Array, 0),
+                BuiltinType::Map { Map } => format!(
+                    "Map<{}, {}>",
+                    self.alg_type(&Map.key_ty, 0),
+                    self.alg_type(&Map.ty, 0)
+                ),
+            },
+            TypeDef::Ref { Ref } => self.reference(*Ref as usize),
+        }
+    }
+
+    /// `depth` is the nesting level of inline objects, for indentation
+    fn alg_type(&mut self, alg_type: &AlgebraicType, depth: usize) -> String {
+        match alg_type {
+            AlgebraicType::Bool { .. } => "boolean".to_string(),
+            AlgebraicType::I8 { .. }
+            | AlgebraicType::U8 { .. }
+            | AlgebraicType::I16 { .. }
+            | AlgebraicType::U16 { .. }
+            | AlgebraicType::I32 { .. }
+            | AlgebraicType::U32 { .. }
+            | AlgebraicType::F32 { .. }
+            | AlgebraicType::F64 { .. } => "number".to_string(),
+            AlgebraicType::I64 { .. }
+            | AlgebraicType::U64 { .. }
+            | AlgebraicType::I128 { .. }
+            | AlgebraicType::U128 { .. }
+            | AlgebraicType::I256 { .. }
+            | AlgebraicType::U256 { .. } => "bigint".to_string(),
+            AlgebraicType::String { .. } => "string".to_string(),
+            AlgebraicType::Array { Array } => self.array(Array, depth),
+            AlgebraicType::Map { Map } => format!(
+                "Map<{}, {}>",
+                self.alg_type(&Map.key_ty, depth),
+                self.alg_type(&Map.ty, depth)
+            ),
+            AlgebraicType::Product { Product } => match detect_spacetimedb_type(Product) {
+                Some(special) => self.special(&special),
+                None => self.product(Product, depth),
+            },
+            AlgebraicType::Sum { Sum } => match detect_spacetimedb_sum_type(Sum) {
+                Some(special) => self.special(&special),
+                None => self.sum(Sum, depth),
+            },
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize),
+        }
+    }
+
+    /// Unions are parenthesized so `[]` applies to the whole element type
+    fn array(&mut self, element: &AlgebraicType, depth: usize) -> String {
+        let inner = self.alg_type(element, depth);
+        if inner.contains(" | ") {
+            format!("({inner})[]")
+        } else {
+            format!("{inner}[]")
+        }
+    }
+
+    fn reference(&mut self, idx: usize) -> String {
+        if let Some(special) = special_name(self.schema, idx) {
+            return self.special(&special);
+        }
+        self.names
+            .get(&idx)
+            .cloned()
+            .unwrap_or_else(|| format!("Type_{idx}"))
+    }
+
+    fn special(&mut self, detected: &str) -> String {
+        match SPECIAL_ALIASES.iter().find(|(name, _)| *name == detected) {
+            Some((name, _)) => {
+                self.specials.insert(name);
+                name.to_string()
+            }
+            None => "unknown".to_string(),
+        }
+    }
+
+    fn product(&mut self, product: &ProductType, depth: usize) -> String {
+        if product.elements.is_empty() {
+            return "Record<string, never>".to_string();
+        }
+        if is_tuple(product) {
+            let types: Vec<_> = product
+                .elements
+                .iter()
+                .map(|e| self.alg_type(&e.algebraic_type, depth))
+                .collect();
+            return format!("[{}]", types.join(", "));
+        }
+        self.object(product, depth)
+    }
+
+    /// A multi-line object type body, `{ ... }`
+    fn object(&mut self, product: &ProductType, depth: usize) -> String {
+        let indent = "  ".repeat(depth + 1);
+        let mut out = String::from("{\n");
+        for (i, element) in product.elements.iter().enumerate() {
+            let key = element
+                .name
+                .as_option()
+                .map_or_else(|| i.to_string(), str::to_string);
+            let ty = self.alg_type(&element.algebraic_type, depth + 1);
+            let _ = writeln!(out, "{indent}{}: {ty};", typescript_key(&key));
+        }
+        let _ = write!(out, "{}}}", "  ".repeat(depth));
+        out
+    }
+
+    fn sum(&mut self, sum: &SumType, depth: usize) -> String {
+        if is_option_type(sum) {
+            if let Some(inner) = get_option_inner_type(sum) {
+                return format!("{} | null", self.alg_type(inner, depth));
+            }
+        }
+        if sum.variants.is_empty() {
+            return "never".to_string();
+        }
+
+        let tag = |i: usize, name: Option<&str>| name.map_or_else(|| i.to_string(), str::to_string);
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+
+        let members: Vec<_> = if sum.variants.iter().all(|v| is_unit(&v.algebraic_type)) {
+            sum.variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| string_literal(&tag(i, v.name.as_option())))
+                .collect()
+        } else {
+            sum.variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let literal = string_literal(&tag(i, v.name.as_option()));
+                    if is_unit(&v.algebraic_type) {
+                        format!("{{ tag: {literal} }}")
+                    } else {
+                        format!(
+                            "{{ tag: {literal}; value: {} }}",
+                            self.alg_type(&v.algebraic_type, depth + 1)
+                        )
+                    }
+                })
+                .collect()
+        };
+
+        // Nested unions stay on one line; top-level ones get a line per
+        // member once they would run past UNION_LINE_WIDTH
+        let single = members.join(" | ");
+        if depth > 0 || single.len() <= UNION_LINE_WIDTH {
+            return single;
+        }
+        let lines: Vec<_> = members.iter().map(|m| format!("  | {m}")).collect();
+        format!("\n{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{demo_schema, recursive_schema};
+
+    #[test]
+    fn structs_become_interfaces() {
+        let ts = generate_typescript(&demo_schema());
+        assert!(
+            ts.contains(concat!(
+                "export interface ItemStack {\n",
+                "  item_id: number;\n",
+                "  count: number;\n",
+                "  next: ItemStack | null;\n",
+                "}\n",
+            )),
+            "{ts}"
+        );
+        assert!(ts.contains("  id: bigint;\n"), "{ts}");
+        assert!(ts.contains("  inventory: ItemStack[];\n"), "{ts}");
+    }
+
+    #[test]
+    fn enums_become_unions() {
+        let ts = generate_typescript(&demo_schema());
+        assert!(
+            ts.contains("export type Rarity = \"Common\" | \"Uncommon\" | \"Rare\" | \"Epic\" | \"Legendary\";\n"),
+            "{ts}"
+        );
+        assert!(
+            ts.contains("export type PlayerStatus = { tag: \"Online\" } | { tag: \"Away\" } | { tag: \"Banned\"; value: string };\n"),
+            "{ts}"
+        );
+    }
+
+    #[test]
+    fn spacetimedb_types_are_declared_when_used() {
+        let ts = generate_typescript(&demo_schema());
+        assert!(
+            ts.starts_with("/** Hex-encoded 256-bit identity */\nexport type Identity = string;\n"),
+            "{ts}"
+        );
+
+        let ts = generate_typescript(&recursive_schema());
+        assert!(!ts.contains("export type Identity"), "{ts}");
+        assert!(ts.contains("  children: Node[];\n"), "{ts}");
+    }
+}
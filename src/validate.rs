@@ -0,0 +1,604 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::output::outln;
+use crate::schema::sats_types::SatsSchema;
+
+/// Type names whose payload is the empty array, e.g. `{"U64": []}`
+const PRIMITIVES: &[&str] = &[
+    "Bool", "I8", "U8", "I16", "U16", "I32", "U32", "I64", "U64", "I128", "U128", "I256", "U256",
+    "F32", "F64", "String",
+];
+
+/// A problem found in a schema document, located by a JSON pointer
+pub struct Problem {
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Check a hand-written schema JSON file and report every problem found,
+/// each with a JSON pointer into the document. Returns an error (and so a
+/// non-zero exit) unless the file is fully valid.
+pub fn validate_file(path: &str) -> Result<()> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?;
+    let document: Value =
+        serde_json::from_str(&text).with_context(|| format!("{path} is not valid JSON"))?;
+
+    let problems = find_problems(&document);
+    if problems.is_empty() {
+        outln!("{} {} is a valid schema", "✅".green(), path);
+        return Ok(());
+    }
+
+    outln!("{} {}", "❌".red(), path);
+    for problem in &problems {
+        let pointer = if problem.pointer.is_empty() {
+            "/"
+        } else {
+            problem.pointer.as_str()
+        };
+        outln!("  {} {}", pointer.yellow(), problem.message);
+    }
+    Err(anyhow!("{} problem(s) found in {}", problems.len(), path))
+}
+
+/// Every structural and referential problem in a schema document
+pub fn find_problems(document: &Value) -> Vec<Problem> {
+    let mut validator = Validator::default();
+    validator.check_schema(document);
+
+    // The walk above mirrors the SATS types; anything it missed still has
+    // to get through the real parser
+    if validator.problems.is_empty() {
+        if let Err(e) = serde_json::from_value::<SatsSchema>(document.clone()) {
+            validator.problem("", format!("does not parse as a schema: {e}"));
+        }
+    }
+    validator.problems
+}
+
+#[derive(Default)]
+struct Validator {
+    problems: Vec<Problem>,
+    /// Number of entries in `typespace.types`, the bound for every `Ref`
+    type_count: usize,
+}
+
+impl Validator {
+    fn problem(&mut self, pointer: &str, message: impl Into<String>) {
+        self.problems.push(Problem {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn field<'v>(&mut self, value: &'v Value, pointer: &str, key: &str) -> Option<&'v Value> {
+        let found = value.get(key);
+        if found.is_none() {
+            self.problem(pointer, format!("missing \"{key}\""));
+        }
+        found
+    }
+
+    fn array<'v>(&mut self, value: &'v Value, pointer: &str) -> Option<&'v Vec<Value>> {
+        let array = value.as_array();
+        if array.is_none() {
+            self.problem(pointer, "expected an array");
+        }
+        array
+    }
+
+    fn index(&mut self, value: &Value, pointer: &str) -> Option<usize> {
+        let index = value.as_u64().and_then(|n| usize::try_from(n).ok());
+        if index.is_none() {
+            self.problem(pointer, "expected a non-negative integer");
+        }
+        index
+    }
+
+    fn string<'v>(&mut self, value: &'v Value, pointer: &str) -> Option<&'v str> {
+        let string = value.as_str();
+        if string.is_none() {
+            self.problem(pointer, "expected a string");
+        }
+        string
+    }
+
+    /// The single `{"Key": payload}` entry SATS uses for its enums
+    fn tagged<'v>(&mut self, value: &'v Value, pointer: &str) -> Option<(&'v str, &'v Value)> {
+        match value.as_object() {
+            Some(object) if object.len() == 1 => object.iter().next().map(|(k, v)| (k.as_str(), v)),
+            _ => {
+                self.problem(pointer, "expected an object with exactly one key");
+                None
+            }
+        }
+    }
+
+    fn check_schema(&mut self, document: &Value) {
+        if !document.is_object() {
+            self.problem("", "expected an object");
+            return;
+        }
+
+        // Collect the typespace first so refs anywhere can be bounds-checked
+        let types = self
+            .field(document, "", "typespace")
+            .and_then(|t| self.field(t, "/typespace", "types"))
+            .and_then(|t| self.array(t, "/typespace/types"));
+        if let Some(types) = types {
+            self.type_count = types.len();
+            for (i, ty) in types.iter().enumerate() {
+                self.check_type_def(ty, &format!("/typespace/types/{i}"));
+            }
+        }
+        let types = types.map(Vec::as_slice).unwrap_or_default();
+
+        let reducer_names = match document.get("reducers") {
+            Some(reducers) => self.check_reducers(reducers),
+            None => HashSet::new(),
+        };
+
+        if let Some(tables) = self
+            .field(document, "", "tables")
+            .and_then(|t| self.array(t, "/tables"))
+        {
+            let mut seen = HashMap::new();
+            for (i, table) in tables.iter().enumerate() {
+                let pointer = format!("/tables/{i}");
+                if let Some(name) = table.get("name").and_then(Value::as_str) {
+                    if let Some(first) = seen.insert(name, i) {
+                        self.problem(
+                            &format!("{pointer}/name"),
+                            format!("table '{name}' is already defined at /tables/{first}"),
+                        );
+                    }
+                }
+                self.check_table(table, &pointer, types, &reducer_names);
+            }
+        }
+
+        if let Some(names) = self
+            .field(document, "", "types")
+            .and_then(|t| self.array(t, "/types"))
+        {
+            self.check_type_names(names);
+        }
+    }
+
+    fn check_type_def(&mut self, value: &Value, pointer: &str) {
+        let Some((tag, payload)) = self.tagged(value, pointer) else {
+            return;
+        };
+        let pointer = format!("{pointer}/{tag}");
+        match tag {
+            "Product" => self.check_product(payload, &pointer),
+            "Sum" => self.check_sum(payload, &pointer),
+            "Ref" => self.check_ref(payload, &pointer),
+            "Builtin" => self.check_builtin(payload, &pointer),
+            _ => self.problem(&pointer, format!("unknown type definition '{tag}'")),
+        }
+    }
+
+    fn check_builtin(&mut self, value: &Value, pointer: &str) {
+        let Some((tag, payload)) = self.tagged(value, pointer) else {
+            return;
+        };
+        if tag != "Map" {
+            return self.check_algebraic_type(value, pointer);
+        }
+        self.check_map(payload, &format!("{pointer}/Map"));
+    }
+
+    /// Builtin and inline maps share the same `{key_ty, ty}` shape
+    fn check_map(&mut self, value: &Value, pointer: &str) {
+        for key in ["key_ty", "ty"] {
+            if let Some(ty) = self.field(value, pointer, key) {
+                self.check_algebraic_type(ty, &format!("{pointer}/{key}"));
+            }
+        }
+    }
+
+    fn check_algebraic_type(&mut self, value: &Value, pointer: &str) {
+        let Some((tag, payload)) = self.tagged(value, pointer) else {
+            return;
+        };
+        let pointer = format!("{pointer}/{tag}");
+        match tag {
+            "Array" => self.check_algebraic_type(payload, &pointer),
+            "Map" => self.check_map(payload, &pointer),
+            "Product" => self.check_product(payload, &pointer),
+            "Sum" => self.check_sum(payload, &pointer),
+            "Ref" => self.check_ref(payload, &pointer),
+            _ if PRIMITIVES.contains(&tag) => {
+                if payload.as_array().is_none_or(|a| !a.is_empty()) {
+                    self.problem(&pointer, "expected []");
+                }
+            }
+            _ => self.problem(&pointer, format!("unknown type '{tag}'")),
+        }
+    }
+
+    fn check_ref(&mut self, value: &Value, pointer: &str) {
+        if let Some(index) = self.index(value, pointer) {
+            if index >= self.type_count {
+                self.problem(
+                    pointer,
+                    format!(
+                        "Ref {index} is out of bounds (typespace has {} types)",
+                        self.type_count
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_product(&mut self, value: &Value, pointer: &str) {
+        self.check_members(value, pointer, "elements");
+    }
+
+    fn check_sum(&mut self, value: &Value, pointer: &str) {
+        self.check_members(value, pointer, "variants");
+    }
+
+    /// Product elements and sum variants share the same `{name, algebraic_type}` shape
+    fn check_members(&mut self, value: &Value, pointer: &str, key: &str) {
+        let Some(members) = self
+            .field(value, pointer, key)
+            .and_then(|m| self.array(m, &format!("{pointer}/{key}")))
+        else {
+            return;
+        };
+
+        for (i, member) in members.iter().enumerate() {
+            let pointer = format!("{pointer}/{key}/{i}");
+            if let Some(name) = self.field(member, &pointer, "name") {
+                self.check_optional_name(name, &format!("{pointer}/name"));
+            }
+            if let Some(ty) = self.field(member, &pointer, "algebraic_type") {
+                self.check_algebraic_type(ty, &format!("{pointer}/algebraic_type"));
+            }
+        }
+    }
+
+    fn check_optional_name(&mut self, value: &Value, pointer: &str) {
+        match self.tagged(value, pointer) {
+            Some(("some", name)) => {
+                self.string(name, &format!("{pointer}/some"));
+            }
+            Some(("none", none)) if none.as_array().is_none_or(|a| !a.is_empty()) => {
+                self.problem(&format!("{pointer}/none"), "expected []");
+            }
+            Some(("none", _)) => {}
+            Some((tag, _)) => {
+                self.problem(
+                    pointer,
+                    format!("expected \"some\" or \"none\", found \"{tag}\""),
+                );
+            }
+            None => {}
+        }
+    }
+
+    /// Returns the reducer names so schedules can be checked against them
+    fn check_reducers<'v>(&mut self, value: &'v Value) -> HashSet<&'v str> {
+        let mut names = HashSet::new();
+        let Some(reducers) = self.array(value, "/reducers") else {
+            return names;
+        };
+
+        for (i, reducer) in reducers.iter().enumerate() {
+            let pointer = format!("/reducers/{i}");
+            if let Some(name) = self
+                .field(reducer, &pointer, "name")
+                .and_then(|n| self.string(n, &format!("{pointer}/name")))
+            {
+                if !names.insert(name) {
+                    self.problem(
+                        &format!("{pointer}/name"),
+                        format!("reducer '{name}' is defined more than once"),
+                    );
+                }
+            }
+            if let Some(params) = self.field(reducer, &pointer, "params") {
+                self.check_product(params, &format!("{pointer}/params"));
+            }
+            if let Some(lifecycle) = reducer.get("lifecycle") {
+                let pointer = format!("{pointer}/lifecycle");
+                if let Some(("some", lifecycle)) = self.tagged(lifecycle, &pointer) {
+                    let pointer = format!("{pointer}/some");
+                    if let Some((tag, _)) = self.tagged(lifecycle, &pointer) {
+                        if !["Init", "OnConnect", "OnDisconnect"].contains(&tag) {
+                            self.problem(&pointer, format!("unknown lifecycle '{tag}'"));
+                        }
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    fn check_table(
+        &mut self,
+        table: &Value,
+        pointer: &str,
+        types: &[Value],
+        reducer_names: &HashSet<&str>,
+    ) {
+        if let Some(name) = self.field(table, pointer, "name") {
+            self.string(name, &format!("{pointer}/name"));
+        }
+
+        // Column count of the table's row type, when it resolves to a product
+        let mut columns = None;
+        let ref_pointer = format!("{pointer}/product_type_ref");
+        if let Some(index) = self
+            .field(table, pointer, "product_type_ref")
+            .and_then(|r| self.index(r, &ref_pointer))
+        {
+            match types.get(index) {
+                None => self.problem(
+                    &ref_pointer,
+                    format!(
+                        "type {index} is out of bounds (typespace has {} types)",
+                        types.len()
+                    ),
+                ),
+                Some(ty) => match ty.pointer("/Product/elements").and_then(Value::as_array) {
+                    Some(elements) => columns = Some(elements.len()),
+                    None => {
+                        self.problem(&ref_pointer, format!("type {index} is not a product type"))
+                    }
+                },
+            }
+        }
+
+        let pk_pointer = format!("{pointer}/primary_key");
+        if let Some(primary_key) = self
+            .field(table, pointer, "primary_key")
+            .and_then(|pk| self.array(pk, &pk_pointer))
+        {
+            for (i, column) in primary_key.iter().enumerate() {
+                self.check_column(column, &format!("{pk_pointer}/{i}"), columns);
+            }
+        }
+
+        let Some(schedule) = table.get("schedule") else {
+            return;
+        };
+        let pointer = format!("{pointer}/schedule");
+        if let Some(("some", schedule)) = self.tagged(schedule, &pointer) {
+            let pointer = format!("{pointer}/some");
+            if let Some(reducer) = self
+                .field(schedule, &pointer, "reducer_name")
+                .and_then(|r| self.string(r, &format!("{pointer}/reducer_name")))
+            {
+                if !reducer_names.contains(reducer) {
+                    self.problem(
+                        &format!("{pointer}/reducer_name"),
+                        format!("no reducer named '{reducer}'"),
+                    );
+                }
+            }
+            if let Some(column) = self.field(schedule, &pointer, "scheduled_at_column") {
+                self.check_column(column, &format!("{pointer}/scheduled_at_column"), columns);
+            }
+        }
+    }
+
+    fn check_column(&mut self, value: &Value, pointer: &str, columns: Option<usize>) {
+        if let (Some(column), Some(columns)) = (self.index(value, pointer), columns) {
+            if column >= columns {
+                self.problem(
+                    pointer,
+                    format!("column {column} is out of bounds (table has {columns} columns)"),
+                );
+            }
+        }
+    }
+
+    /// The `types` list maps names onto typespace entries; each name and each
+    /// entry may appear only once
+    fn check_type_names(&mut self, names: &[Value]) {
+        let mut seen_names = HashMap::new();
+        let mut seen_refs = HashMap::new();
+
+        for (i, named) in names.iter().enumerate() {
+            let pointer = format!("/types/{i}");
+
+            if let Some(name) = self.field(named, &pointer, "name") {
+                let name_pointer = format!("{pointer}/name");
+                let scope = self
+                    .field(name, &name_pointer, "scope")
+                    .and_then(|s| self.array(s, &format!("{name_pointer}/scope")));
+                let mut qualified = Vec::new();
+                for (j, part) in scope.into_iter().flatten().enumerate() {
+                    if let Some(part) = self.string(part, &format!("{name_pointer}/scope/{j}")) {
+                        qualified.push(part);
+                    }
+                }
+                if let Some(base) = self
+                    .field(name, &name_pointer, "name")
+                    .and_then(|n| self.string(n, &format!("{name_pointer}/name")))
+                {
+                    qualified.push(base);
+                    let qualified = qualified.join("::");
+                    if let Some(first) = seen_names.insert(qualified.clone(), i) {
+                        self.problem(
+                            &name_pointer,
+                            format!("name '{qualified}' is already used by /types/{first}"),
+                        );
+                    }
+                }
+            }
+
+            let ty_pointer = format!("{pointer}/ty");
+            if let Some(index) = self
+                .field(named, &pointer, "ty")
+                .and_then(|t| self.index(t, &ty_pointer))
+            {
+                if index >= self.type_count {
+                    self.problem(
+                        &ty_pointer,
+                        format!(
+                            "type {index} is out of bounds (typespace has {} types)",
+                            self.type_count
+                        ),
+                    );
+                } else if let Some(first) = seen_refs.insert(index, i) {
+                    self.problem(
+                        &ty_pointer,
+                        format!("type {index} is already named by /types/{first}"),
+                    );
+                }
+            }
+
+            if let Some(ordering) = self.field(named, &pointer, "custom_ordering") {
+                if !ordering.is_boolean() {
+                    self.problem(&format!("{pointer}/custom_ordering"), "expected a boolean");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pointers(document: &Value) -> Vec<String> {
+        find_problems(document)
+            .into_iter()
+            .map(|p| p.pointer)
+            .collect()
+    }
+
+    fn fixture(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn demo_is_valid() {
+        assert_eq!(
+            pointers(&fixture(crate::test_support::DEMO_SCHEMA)),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn every_seeded_problem_is_reported() {
+        let document = fixture(include_str!("../tests/fixtures/invalid_schema.json"));
+        let problems = find_problems(&document);
+        let found: Vec<(&str, &str)> = problems
+            .iter()
+            .map(|p| (p.pointer.as_str(), p.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/typespace/types/0/Product/elements/0/algebraic_type/U64",
+                    "expected []"
+                ),
+                (
+                    "/typespace/types/0/Product/elements/1/name",
+                    "expected \"some\" or \"none\", found \"other\""
+                ),
+                (
+                    "/typespace/types/0/Product/elements/1/algebraic_type/Ref",
+                    "Ref 5 is out of bounds (typespace has 2 types)"
+                ),
+                (
+                    "/typespace/types/0/Product/elements/2/algebraic_type/Float",
+                    "unknown type 'Float'"
+                ),
+                (
+                    "/typespace/types/1/Sum/variants/1",
+                    "missing \"algebraic_type\""
+                ),
+                (
+                    "/reducers/1/name",
+                    "reducer 'spawn' is defined more than once"
+                ),
+                (
+                    "/reducers/1/lifecycle/some",
+                    "unknown lifecycle 'OnRestart'"
+                ),
+                (
+                    "/tables/0/primary_key/0",
+                    "column 3 is out of bounds (table has 3 columns)"
+                ),
+                (
+                    "/tables/0/schedule/some/reducer_name",
+                    "no reducer named 'tick'"
+                ),
+                (
+                    "/tables/1/name",
+                    "table 'player' is already defined at /tables/0"
+                ),
+                ("/tables/1/product_type_ref", "type 1 is not a product type"),
+                (
+                    "/tables/2/product_type_ref",
+                    "type 7 is out of bounds (typespace has 2 types)"
+                ),
+                ("/types/1/name", "name 'Player' is already used by /types/0"),
+                ("/types/1/custom_ordering", "expected a boolean"),
+                ("/types/2/ty", "type 0 is already named by /types/0"),
+                (
+                    "/types/3/ty",
+                    "type 4 is out of bounds (typespace has 2 types)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_object_document_is_one_problem() {
+        let found = find_problems(&serde_json::json!([]));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].pointer, "");
+        assert_eq!(found[0].message, "expected an object");
+    }
+
+    #[test]
+    fn parser_errors_are_reported_when_the_walk_finds_nothing() {
+        // The walk doesn't look at a schedule's name; the parser does
+        let mut document = fixture(crate::test_support::DEMO_SCHEMA);
+        document["tables"][5]["schedule"]["some"]["name"] = serde_json::json!(5);
+        let found = find_problems(&document);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].pointer, "");
+        assert!(
+            found[0].message.starts_with("does not parse as a schema"),
+            "{}",
+            found[0].message
+        );
+    }
+
+    #[test]
+    fn map_columns_are_valid() {
+        let document = fixture(include_str!("../tests/fixtures/map_table.json"));
+        assert_eq!(pointers(&document), Vec::<String>::new());
+    }
+
+    #[test]
+    fn map_key_and_value_types_are_checked() {
+        let mut document = fixture(include_str!("../tests/fixtures/map_table.json"));
+        let elements = &mut document["typespace"]["types"][0]["Product"]["elements"];
+        elements[1]["algebraic_type"]["Map"]["key_ty"] = serde_json::json!({"Ref": 9});
+        elements[2]["algebraic_type"]["Map"]
+            .as_object_mut()
+            .unwrap()
+            .remove("ty");
+        assert_eq!(
+            pointers(&document),
+            [
+                "/typespace/types/0/Product/elements/1/algebraic_type/Map/key_ty/Ref",
+                "/typespace/types/0/Product/elements/2/algebraic_type/Map",
+            ]
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::identifiers::{string_literal, typescript_identifier, typescript_key};
+use crate::schema::sats_types::{
+    AlgebraicType, BuiltinType, ProductType, SatsSchema, SumType, TypeDef,
+};
+use crate::schema::{get_option_inner_type, is_option_type};
+
+/// Render the schema as a TypeScript module with one Zod schema constant per
+/// typespace entry, declared so that every schema is defined before it is
+/// referenced. Recursive types are referenced through `z.lazy`.
+///
+/// With `table`, only that table's row type and the types it reaches are emitted.
+pub fn generate_zod(schema: &SatsSchema, table: Option<&str>) -> Result<String> {
+    let tables: Vec<_> = match table {
+        Some(name) => {
+            let table = schema
+                .tables
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Table '{name}' not found"))?;
+            vec![table]
+        }
+        None => schema.tables.iter().collect(),
+    };
+
+    let (names, renamed) = constant_names(schema);
+    let mut roots: Vec<usize> = tables.iter().map(|t| t.product_type_ref).collect();
+    if table.is_none() {
+        roots.extend(schema.types.iter().map(|t| t.ty));
+    }
+    roots.sort_by(|a, b| names.get(a).cmp(&names.get(b)));
+
+    let (order, recursive) = declaration_order(schema, &roots);
+
+    let mut generator = ZodGenerator {
+        names: &names,
+        declared: HashSet::new(),
+    };
+    let mut out = String::from("import { z } from \"zod\";\n");
+
+    for idx in order {
+        let Some(name) = names.get(&idx) else {
+            continue;
+        };
+        let body = match schema.typespace.types.get(idx) {
+            Some(def) => generator.type_def(def),
+            None => "z.never()".to_string(),
+        };
+        let annotation = if recursive.contains(&idx) {
+            ": z.ZodTypeAny"
+        } else {
+            ""
+        };
+        out.push('\n');
+        if let Some(original) = renamed.get(&idx) {
+            let _ = writeln!(out, "// {} is exported as {name}", string_literal(original));
+        }
+        let _ = writeln!(out, "export const {name}Schema{annotation} = {body};");
+        let _ = writeln!(out, "export type {name} = z.infer<typeof {name}Schema>;");
+        generator.declared.insert(idx);
+    }
+
+    out.push_str("\nexport const tableSchemas = {\n");
+    for table in tables {
+        if let Some(name) = names.get(&table.product_type_ref) {
+            let _ = writeln!(out, "  {}: {name}Schema,", typescript_key(&table.name));
+        }
+    }
+    out.push_str("} as const;\n");
+
+    Ok(out)
+}
+
+/// TypeScript identifier for each typespace entry. Named types use their
+/// name, falling back to the scoped name when two scopes share a name;
+/// unnamed entries become `Type_<index>`. Names that had to be sanitized
+/// (or numbered to stay unique) are also returned with their original
+/// spelling, for the mapping comments.
+pub fn constant_names(schema: &SatsSchema) -> (HashMap<usize, String>, HashMap<usize, String>) {
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    for named in &schema.types {
+        *uses.entry(named.name.name.as_str()).or_default() += 1;
+    }
+
+    let mut names = HashMap::new();
+    let mut renamed = HashMap::new();
+    let mut taken = HashSet::new();
+    let mut unique = |base: String| {
+        let mut name = base.clone();
+        let mut n = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        name
+    };
+
+    for named in &schema.types {
+        let original = if uses[named.name.name.as_str()] > 1 {
+            named
+                .name
+                .scope
+                .iter()
+                .chain(std::iter::once(&named.name.name))
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("_")
+        } else {
+            named.name.name.clone()
+        };
+        let name = unique(typescript_identifier(&original));
+        if name != original {
+            renamed.insert(named.ty, original);
+        }
+        names.insert(named.ty, name);
+    }
+    for idx in 0..schema.typespace.types.len() {
+        names
+            .entry(idx)
+            .or_insert_with(|| unique(format!("Type_{idx}")));
+    }
+    (names, renamed)
+}
+
+/// Depth-first post-order over the refs reachable from `roots`, so each type
+/// comes after everything it references. Also returns the types referenced
+/// from inside their own cycle, which need `z.lazy` and a type annotation.
+fn declaration_order(schema: &SatsSchema, roots: &[usize]) -> (Vec<usize>, BTreeSet<usize>) {
+    fn visit(
+        schema: &SatsSchema,
+        idx: usize,
+        stack: &mut Vec<usize>,
+        done: &mut HashSet<usize>,
+        order: &mut Vec<usize>,
+        recursive: &mut BTreeSet<usize>,
+    ) {
+        if stack.contains(&idx) {
+            recursive.insert(idx);
+            return;
+        }
+        if !done.insert(idx) {
+            return;
+        }
+
+        stack.push(idx);
+        let mut refs = Vec::new();
+        if let Some(def) = schema.typespace.types.get(idx) {
+            def_refs(def, &mut refs);
+        }
+        for r in refs {
+            visit(schema, r, stack, done, order, recursive);
+        }
+        stack.pop();
+        order.push(idx);
+    }
+
+    let mut order = Vec::new();
+    let mut recursive = BTreeSet::new();
+    let mut done = HashSet::new();
+    for &root in roots {
+        visit(
+            schema,
+            root,
+            &mut Vec::new(),
+            &mut done,
+            &mut order,
+            &mut recursive,
+        );
+    }
+    (order, recursive)
+}
+
+fn def_refs(def: &TypeDef, refs: &mut Vec<usize>) {
+    match def {
+        TypeDef::Product { Product } => product_refs(Product, refs),
+        TypeDef::Sum { Sum } => {
+            for variant in &Sum.variants {
+                type_refs(&variant.algebraic_type, refs);
+            }
+        }
+        TypeDef::Builtin { Builtin } => match Builtin {
+            BuiltinType::Array { Array } => type_refs(Array, refs),
+            BuiltinType::Map { Map } => {
+                type_refs(&Map.key_ty, refs);
+                type_refs(&Map.ty, refs);
+            }
+            _ => {}
+        },
+        TypeDef::Ref { Ref } => refs.push(*Ref as usize),
+    }
+}
+
+fn product_refs(product: &ProductType, refs: &mut Vec<usize>) {
+    for element in &product.elements {
+        type_refs(&element.algebraic_type, refs);
+    }
+}
+
+fn type_refs(alg_type: &AlgebraicType, refs: &mut Vec<usize>) {
+    match alg_type {
+        AlgebraicType::Array { Array } => type_refs(Array, refs),
+        AlgebraicType::Map { Map } => {
+            type_refs(&Map.key_ty, refs);
+            type_refs(&Map.ty, refs);
+        }
+        AlgebraicType::Product { Product } => product_refs(Product, refs),
+        AlgebraicType::Sum { Sum } => {
+            for variant in &Sum.variants {
+                type_refs(&variant.algebraic_type, refs);
+            }
+        }
+        AlgebraicType::Ref { Ref } => refs.push(*Ref as usize),
+        _ => {}
+    }
+}
+
+/// Larger enums are split over several lines (this many tags per line, one
+/// variant per line for unions) so editors don't choke on them
+const ENUM_TAGS_PER_LINE: usize = 16;
+
+struct ZodGenerator<'a> {
+    names: &'a HashMap<usize, String>,
+    /// Constants already written; refs to anything else go through `z.lazy`
+    declared: HashSet<usize>,
+}
+
+impl ZodGenerator<'_> {
+    fn type_def(&self, def: &TypeDef) -> String {
+        match def {
+            TypeDef::Product { Product } => self.product(Product),
+            TypeDef::Sum { Sum } => self.sum(Sum),
+            TypeDef::Builtin { Builtin } => match Builtin {
+                BuiltinType::Bool { .. } => "z.boolean()".to_string(),
+                BuiltinType::I8 { .. } => int_schema("I8"),
+                BuiltinType::U8 { .. } => int_schema("U8"),
+                BuiltinType::I16 { .. } => int_schema("I16"),
+                BuiltinType::U16 { .. } => int_schema("U16"),
+                BuiltinType::I32 { .. } => int_schema("I32"),
+                BuiltinType::U32 { .. } => int_schema("U32"),
+                BuiltinType::I64 { .. } => int_schema("I64"),
+                BuiltinType::U64 { .. } => int_schema("U64"),
+                BuiltinType::I128 { .. } => int_schema("I128"),
+                BuiltinType::U128 { .. } => int_schema("U128"),
+                BuiltinType::I256 { .. } => int_schema("I256"),
+                BuiltinType::U256 { .. } => int_schema("U256"),
+                BuiltinType::F32 { .. } | BuiltinType::F64 { .. } => "z.number()".to_string(),
+                BuiltinType::String { .. } => "z.string()".to_string(),
+                BuiltinType::Array { Array } => format!("z.array({})", self.alg_type(Array)),
+                BuiltinType::Map { Map } => format!(
+                    "z.map({}, {})",
+                    self.alg_type(&Map.key_ty),
+                    self.alg_type(&Map.ty)
+                ),
+            },
+            TypeDef::Ref { Ref } => self.reference(*Ref as usize),
+        }
+    }
+
+    fn alg_type(&self, alg_type: &AlgebraicType) -> String {
+        match alg_type {
+            AlgebraicType::Bool { .. } => "z.boolean()".to_string(),
+            AlgebraicType::I8 { .. } => int_schema("I8"),
+            AlgebraicType::U8 { .. } => int_schema("U8"),
+            AlgebraicType::I16 { .. } => int_schema("I16"),
+            AlgebraicType::U16 { .. } => int_schema("U16"),
+            AlgebraicType::I32 { .. } => int_schema("I32"),
+            AlgebraicType::U32 { .. } => int_schema("U32"),
+            AlgebraicType::I64 { .. } => int_schema("I64"),
+            AlgebraicType::U64 { .. } => int_schema("U64"),
+            AlgebraicType::I128 { .. } => int_schema("I128"),
+            AlgebraicType::U128 { .. } => int_schema("U128"),
+            AlgebraicType::I256 { .. } => int_schema("I256"),
+            AlgebraicType::U256 { .. } => int_schema("U256"),
+            AlgebraicType::F32 { .. } | AlgebraicType::F64 { .. } => "z.number()".to_string(),
+            AlgebraicType::String { .. } => "z.string()".to_string(),
+            AlgebraicType::Array { Array } => format!("z.array({})", self.alg_type(Array)),
+            AlgebraicType::Map { Map } => format!(
+                "z.map({}, {})",
+                self.alg_type(&Map.key_ty),
+                self.alg_type(&Map.ty)
+            ),
+            AlgebraicType::Product { Product } => self.product(Product),
+            AlgebraicType::Sum { Sum } => self.sum(Sum),
+            AlgebraicType::Ref { Ref } => self.reference(*Ref as usize),
+        }
+    }
+
+    fn reference(&self, idx: usize) -> String {
+        let name = self
+            .names
+            .get(&idx)
+            .cloned()
+            .unwrap_or_else(|| format!("Type_{idx}"));
+        if self.declared.contains(&idx) {
+            format!("{name}Schema")
+        } else {
+            format!("z.lazy(() => {name}Schema)")
+        }
+    }
+
+    fn product(&self, product: &ProductType) -> String {
+        if product.elements.is_empty() {
+            return "z.object({})".to_string();
+        }
+        let fields: Vec<_> = product
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let key = element
+                    .name
+                    .as_option()
+                    .map_or_else(|| i.to_string(), str::to_string);
+                format!(
+                    "{}: {}",
+                    typescript_key(&key),
+                    self.alg_type(&element.algebraic_type)
+                )
+            })
+            .collect();
+        format!("z.object({{ {} }})", fields.join(", "))
+    }
+
+    fn sum(&self, sum: &SumType) -> String {
+        if is_option_type(sum) {
+            if let Some(inner) = get_option_inner_type(sum) {
+                return format!("{}.nullable()", self.alg_type(inner));
+            }
+        }
+
+        let tag = |i: usize, name: Option<&str>| name.map_or_else(|| i.to_string(), str::to_string);
+        let is_unit = |alg_type: &AlgebraicType| matches!(alg_type, AlgebraicType::Product { Product } if Product.elements.is_empty());
+
+        if !sum.variants.is_empty() && sum.variants.iter().all(|v| is_unit(&v.algebraic_type)) {
+            let tags: Vec<_> = sum
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, v)| string_literal(&tag(i, v.name.as_option())))
+                .collect();
+            if tags.len() <= ENUM_TAGS_PER_LINE {
+                return format!("z.enum([{}])", tags.join(", "));
+            }
+            let lines: Vec<_> = tags
+                .chunks(ENUM_TAGS_PER_LINE)
+                .map(|chunk| format!("  {},", chunk.join(", ")))
+                .collect();
+            return format!("z.enum([\n{}\n])", lines.join("\n"));
+        }
+
+        let variants: Vec<_> = sum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let literal = string_literal(&tag(i, v.name.as_option()));
+                if is_unit(&v.algebraic_type) {
+                    format!("z.object({{ tag: z.literal({literal}) }})")
+                } else {
+                    format!(
+                        "z.object({{ tag: z.literal({literal}), value: {} }})",
+                        self.alg_type(&v.algebraic_type)
+                    )
+                }
+            })
+            .collect();
+        if variants.len() <= ENUM_TAGS_PER_LINE {
+            return format!("z.discriminatedUnion(\"tag\", [{}])", variants.join(", "));
+        }
+        let lines: Vec<_> = variants.iter().map(|v| format!("  {v},")).collect();
+        format!("z.discriminatedUnion(\"tag\", [\n{}\n])", lines.join("\n"))
+    }
+}
+
+/// Sized integers: `number` with bounds up to 32 bits, `bigint` beyond
+fn int_schema(kind: &str) -> String {
+    let (min, max) = match kind {
+        "I8" => ("-128", "127"),
+        "U8" => ("0", "255"),
+        "I16" => ("-32768", "32767"),
+        "U16" => ("0", "65535"),
+        "I32" => ("-2147483648", "2147483647"),
+        "U32" => ("0", "4294967295"),
+        "I64" => ("-9223372036854775808", "9223372036854775807"),
+        "U64" => ("0", "18446744073709551615"),
+        "I128" => (
+            "-170141183460469231731687303715884105728",
+            "170141183460469231731687303715884105727",
+        ),
+        "U128" => ("0", "340282366920938463463374607431768211455"),
+        "I256" => (
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968",
+            "57896044618658097711785492504343953926634992332820282019728792003956564819967",
+        ),
+        _ => (
+            "0",
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        ),
+    };
+    if matches!(kind, "I8" | "U8" | "I16" | "U16" | "I32" | "U32") {
+        format!("z.number().int().min({min}).max({max})")
+    } else {
+        format!("z.bigint().min({min}n).max({max}n)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{big_enum_schema, demo_schema, schema};
+
+    /// Constant names in declaration order, each with the constants its
+    /// body uses directly (not through `z.lazy`)
+    fn declarations(module: &str) -> Vec<(String, Vec<String>)> {
+        module
+            .lines()
+            .filter_map(|line| line.strip_prefix("export const "))
+            .filter_map(|line| {
+                let (name, body) = line.split_once(" = ")?;
+                let name = name.trim_end_matches(": z.ZodTypeAny").to_string();
+                let mut body = body.to_string();
+                const LAZY: &str = "z.lazy(() => ";
+                while let Some(start) = body.find(LAZY) {
+                    let end = start + LAZY.len() + body[start + LAZY.len()..].find(')').unwrap();
+                    body.replace_range(start..=end, "");
+                }
+                let uses = body
+                    .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .filter(|word| word.ends_with("Schema") && *word != "Schema")
+                    .map(str::to_string)
+                    .collect();
+                Some((name, uses))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn demo_matches_fixture() {
+        let module = generate_zod(&demo_schema(), None).unwrap();
+        assert_eq!(module, include_str!("../tests/fixtures/demo.zod.ts"));
+    }
+
+    #[test]
+    fn output_is_deterministic() {
+        let schema = demo_schema();
+        let first = generate_zod(&schema, None).unwrap();
+        for _ in 0..5 {
+            assert_eq!(generate_zod(&schema, None).unwrap(), first);
+        }
+    }
+
+    #[test]
+    fn no_schema_is_used_before_its_declaration() {
+        let module = generate_zod(&demo_schema(), None).unwrap();
+        let mut declared = HashSet::new();
+        for (name, uses) in declarations(&module) {
+            if name == "tableSchemas" {
+                continue;
+            }
+            for used in uses {
+                assert!(
+                    declared.contains(&used),
+                    "{name} uses {used} before it is declared"
+                );
+            }
+            declared.insert(name);
+        }
+        assert!(declared.contains("PlayerSchema"));
+    }
+
+    #[test]
+    fn recursive_types_are_lazy_and_annotated() {
+        let module = generate_zod(&demo_schema(), None).unwrap();
+        assert!(module.contains(
+            "export const ItemStackSchema: z.ZodTypeAny = z.object({ item_id: z.number().int().min(0).max(4294967295), count: z.number().int().min(0).max(65535), next: z.lazy(() => ItemStackSchema).nullable() });"
+        ));
+    }
+
+    #[test]
+    fn table_filter_keeps_only_the_types_it_reaches() {
+        let module = generate_zod(&demo_schema(), Some("GUILD_MEMBER")).unwrap();
+        let names: Vec<_> = declarations(&module)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            ["GuildRoleSchema", "GuildMemberSchema", "tableSchemas"]
+        );
+        assert!(module.contains("  guild_member: GuildMemberSchema,\n"));
+
+        let error = generate_zod(&demo_schema(), Some("nope")).unwrap_err();
+        assert_eq!(error.to_string(), "Table 'nope' not found");
+    }
+
+    #[test]
+    fn sized_ints_are_refined_to_their_range() {
+        assert_eq!(int_schema("I8"), "z.number().int().min(-128).max(127)");
+        assert_eq!(int_schema("U32"), "z.number().int().min(0).max(4294967295)");
+        assert_eq!(
+            int_schema("I64"),
+            "z.bigint().min(-9223372036854775808n).max(9223372036854775807n)"
+        );
+        assert!(int_schema("U256").starts_with("z.bigint().min(0n).max(11579"));
+    }
+
+    #[test]
+    fn large_enums_keep_every_variant_over_short_lines() {
+        let module = generate_zod(&big_enum_schema(400), None).unwrap();
+        let start = module
+            .find("export const LocalizationKeySchema = z.enum([\n")
+            .unwrap();
+        let lines: Vec<_> = module[start..]
+            .lines()
+            .skip(1)
+            .take_while(|line| *line != "]);")
+            .collect();
+        assert_eq!(lines.len(), 400usize.div_ceil(ENUM_TAGS_PER_LINE));
+        let tags: Vec<_> = lines
+            .iter()
+            .flat_map(|line| line.trim().trim_end_matches(',').split(", "))
+            .collect();
+        let expected: Vec<_> = (0..400).map(|i| format!("\"Key{i}\"")).collect();
+        assert_eq!(tags, expected);
+    }
+
+    #[test]
+    fn large_unions_put_one_variant_per_line() {
+        let variants: Vec<_> = (0..20)
+            .map(|i| serde_json::json!({"name": {"some": format!("V{i}")}, "algebraic_type": {"U8": []}}))
+            .collect();
+        let schema = schema(serde_json::json!({
+            "typespace": {"types": [{"Sum": {"variants": variants}}]},
+            "tables": [],
+            "types": [{"name": {"scope": [], "name": "Wide"}, "ty": 0, "custom_ordering": true}],
+        }));
+        let module = generate_zod(&schema, None).unwrap();
+        assert!(module.contains("export const WideSchema = z.discriminatedUnion(\"tag\", [\n  z.object({ tag: z.literal(\"V0\"), value: z.number().int().min(0).max(255) }),\n"));
+        assert!(module.contains("  z.object({ tag: z.literal(\"V19\"), value: z.number().int().min(0).max(255) }),\n]);\n"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Code generation for names that are keywords, start with digits or contain
+//! punctuation in the target language
+
+mod common;
+
+use std::collections::HashSet;
+
+use common::{serve_schema, stdout};
+
+const ADVERSARIAL: &str = include_str!("fixtures/adversarial_names.json");
+
+/// Code formats with the marker that starts a line comment
+const FORMATS: &[(&str, &str)] = &[("zod", "//"), ("kdl", "//"), ("rust", "//"), ("dot", "//")];
+
+fn generate(name: &str, format: &str) -> String {
+    let server = serve_schema(ADVERSARIAL);
+    stdout(
+        &format!("{name}_{format}"),
+        &[
+            "--server",
+            &server,
+            "--db",
+            "names",
+            "--format",
+            format,
+            "--no-metadata-footer",
+        ],
+    )
+}
+
+/// Checks that brackets nest outside of strings and comments, so no name
+/// broke out of its quotes
+fn assert_balanced(format: &str, comment: &str, code: &str) {
+    let mut stack = Vec::new();
+    for (number, line) in code.lines().enumerate() {
+        let mut quote = None;
+        let mut chars = line.char_indices();
+        while let Some((at, c)) = chars.next() {
+            match quote {
+                Some(_) if c == '\\' => {
+                    chars.next();
+                }
+                Some(open) if c == open => quote = None,
+                Some(_) => {}
+                None if line[at..].starts_with(comment) => break,
+                None if matches!(c, '"' | '`') => quote = Some(c),
+                None if matches!(c, '(' | '[' | '{') => stack.push(c),
+                None if matches!(c, ')' | ']' | '}') => {
+                    let open = stack.pop();
+                    let expected = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    assert_eq!(open, Some(expected), "{format} line {}: {line}", number + 1);
+                }
+                None => {}
+            }
+        }
+        assert_eq!(
+            quote,
+            None,
+            "{format} line {}: unterminated string: {line}",
+            number + 1
+        );
+    }
+    assert!(stack.is_empty(), "{format}: unclosed {stack:?}");
+}
+
+#[test]
+fn every_code_format_accepts_adversarial_names() {
+    for (format, comment) in FORMATS {
+        let code = generate("adversarial_balanced", format);
+        assert_balanced(format, comment, &code);
+        assert_eq!(
+            generate("adversarial_balanced", format),
+            code,
+            "{format} output is not deterministic"
+        );
+    }
+}
+
+#[test]
+fn sanitized_zod_declarations_stay_unique() {
+    let code = generate("adversarial_unique", "zod");
+    for prefix in ["export const ", "export type "] {
+        let mut names = HashSet::new();
+        for line in code.lines() {
+            if let Some(rest) = line.strip_prefix(prefix) {
+                let name = rest.split([' ', ':']).next().unwrap().to_string();
+                assert!(names.insert(name.clone()), "{name} declared twice\n{code}");
+            }
+        }
+        assert!(names.len() >= 3, "{prefix}: only {names:?}\n{code}");
+    }
+}
+
+/// The member a line of a struct declares, if any
+fn member(format: &str, line: &str) -> Option<String> {
+    let line = line.trim();
+    match format {
+        "rust" => line
+            .strip_prefix("pub ")
+            .filter(|l| l.contains(':'))
+            .and_then(|l| l.split(':').next().map(str::to_string)),
+        _ => None,
+    }
+}
+
+#[test]
+fn sanitized_member_names_stay_unique() {
+    let code = generate("adversarial_members", "rust");
+    let mut members = HashSet::new();
+    let mut count = 0;
+    for line in code.lines() {
+        if line.trim_end().ends_with('{') {
+            members.clear();
+        }
+        if let Some(name) = member("rust", line) {
+            count += 1;
+            assert!(members.insert(name.clone()), "rust: {name} declared twice");
+        }
+    }
+    // The user-data table alone has 13 fields
+    assert!(count >= 13, "rust: only {count} members found\n{code}");
+}
+
+#[test]
+fn reserved_words_are_renamed_with_a_note() {
+    let zod = generate("adversarial_renamed", "zod");
+    assert!(zod.contains("// \"type\" is exported as type_\n"), "{zod}");
+    assert!(zod.contains("export type type_ = "), "{zod}");
+    assert!(zod.contains(" \"quote\\\"d\": z.string(),"), "{zod}");
+
+    let rust = generate("adversarial_renamed", "rust");
+    assert!(rust.contains("pub r#type: String,"), "{rust}");
+    assert!(rust.contains("pub enum r#type {"), "{rust}");
+    assert!(rust.contains("    pub self_: u32,"), "{rust}");
+    assert!(rust.contains("    pub user_data2: String,"), "{rust}");
+
+    let kdl = generate("adversarial_renamed", "kdl");
+    assert!(
+        kdl.contains("table \"user-data\" type=\"user-data\" {"),
+        "{kdl}"
+    );
+    assert!(
+        kdl.contains("    field \"quote\\\"d\" \"String\"\n"),
+        "{kdl}"
+    );
+}
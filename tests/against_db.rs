@@ -0,0 +1,65 @@
+//! --against-db: one table side by side in two databases
+
+mod common;
+
+use common::{serve, stdout, Response, DEMO_SCHEMA};
+use serde_json::{json, Value};
+
+/// The demo schema with `Player.position` renamed to `pos`
+fn renamed_schema() -> String {
+    let mut document: Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+    let player = document["tables"][0]["product_type_ref"].as_u64().unwrap() as usize;
+    document["typespace"]["types"][player]["Product"]["elements"][4]["name"] =
+        json!({"some": "pos"});
+    document.to_string()
+}
+
+/// Serves the demo schema as `game` and the renamed one as `game_v2`
+fn compare(name: &str, args: &[&str]) -> String {
+    let renamed = renamed_schema();
+    let server = serve(move |path| {
+        if path.starts_with("/v1/database/game_v2/") {
+            Response::ok(renamed.clone())
+        } else {
+            Response::ok(DEMO_SCHEMA)
+        }
+    });
+    let base = [
+        "--server",
+        &server,
+        "--db",
+        "game",
+        "--table",
+        "player",
+        "--against-db",
+        "game_v2",
+    ];
+    stdout(name, &[&base[..], args].concat())
+}
+
+#[test]
+fn renamed_fields_share_a_row() {
+    let stdout = compare("against_db_pretty", &[]);
+    let row = stdout
+        .lines()
+        .find(|line| line.starts_with("position: Vec2"))
+        .unwrap_or_else(|| panic!("no position row in {stdout}"));
+    assert!(row.contains("pos: Vec2 (renamed from position)"), "{row}");
+}
+
+#[test]
+fn markdown_is_a_two_column_table() {
+    let stdout = compare("against_db_markdown", &["--format", "markdown"]);
+    assert!(
+        stdout.starts_with(concat!(
+            "## Compare table `player`\n\n",
+            "| `game`: `player` | `game_v2`: `player` |\n",
+            "| --- | --- |\n",
+        )),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("| `position`: `Vec2` | `pos`: `Vec2` (renamed from `position`) |\n"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,52 @@
+//! The --automation view
+
+mod common;
+
+use common::{serve_schema, stdout, terminal, DEMO_SCHEMA};
+
+#[test]
+fn game_tick_loop_matches_snapshot() {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = terminal(
+        "automation_game",
+        &["--server", &server, "--db", "game", "--automation"],
+    );
+    assert_eq!(output, include_str!("fixtures/demo_automation.txt"));
+}
+
+#[test]
+fn database_without_automation_says_so() {
+    let mut schema: serde_json::Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+    schema["reducers"] = serde_json::json!([]);
+    schema["tables"].as_array_mut().unwrap().truncate(5);
+    let server = serve_schema(&schema.to_string());
+    let output = stdout(
+        "automation_none",
+        &["--server", &server, "--db", "game", "--automation"],
+    );
+    assert!(
+        output.contains("No lifecycle reducers or scheduled tables in this database."),
+        "{output}"
+    );
+    assert!(!output.contains("SCHEDULED TABLES"), "{output}");
+}
+
+#[test]
+fn json_output_is_the_automation_object() {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = stdout(
+        "automation_json",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--automation",
+            "--format",
+            "json",
+        ],
+    );
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report["scheduled_tables"][0]["reducer"], "tick");
+    assert_eq!(report["lifecycle_reducers"].as_array().unwrap().len(), 3);
+}
@@ -0,0 +1,59 @@
+//! Typespace entries that are 256-bit integer builtins rather than inline
+//! types, in every code format
+
+mod common;
+
+fn generate(format: &str) -> String {
+    common::stdout(
+        &format!("builtin_256_{format}"),
+        &[
+            "--file",
+            "tests/fixtures/builtin_256.json",
+            "--format",
+            format,
+            "--no-metadata-footer",
+        ],
+    )
+}
+
+#[test]
+fn every_format_maps_the_builtins() {
+    let expected: &[(&str, &[&str])] = &[
+        ("pretty", &["balance: Balance", "delta: i256", "history: Vec<Balance>"]),
+        ("rust", &["pub type Balance = u256;", "pub delta: Type2,", "pub type Type2 = i256;"]),
+        ("ts", &["export type Balance = bigint;", "export type Type_2 = bigint;"]),
+        (
+            "zod",
+            &["z.bigint().min(0n).max(115792089237316195423570985008687907853269984665640564039457584007913129639935n)"],
+        ),
+        ("sql", &["balance numeric NOT NULL", "delta numeric NOT NULL"]),
+        ("proto", &["bytes balance = 2; // u256", "bytes delta = 3; // i256"]),
+        ("go", &["*big.Int"]),
+        ("csharp", &["SpacetimeDB.U256 Balance", "SpacetimeDB.I256 Delta"]),
+        ("kotlin", &["val delta: @Contextual BigInteger"]),
+    ];
+    for (format, snippets) in expected {
+        let output = generate(format);
+        for snippet in *snippets {
+            assert!(
+                output.contains(snippet),
+                "{format}: missing {snippet:?} in\n{output}"
+            );
+        }
+    }
+}
+
+#[test]
+fn json_schema_uses_unbounded_integers() {
+    let document: serde_json::Value = serde_json::from_str(&generate("jsonschema")).unwrap();
+    let properties = &document["$defs"]["Account"]["properties"];
+    assert_eq!(
+        properties["balance"],
+        serde_json::json!({"type": "integer"})
+    );
+    assert_eq!(properties["delta"], serde_json::json!({"type": "integer"}));
+    assert_eq!(
+        document["$defs"]["Balance"],
+        serde_json::json!({"type": "integer"})
+    );
+}
@@ -0,0 +1,219 @@
+//! Requests the schema client sends and how it reports failures
+
+mod common;
+
+use common::{run, serve_recording, Response, DEMO_SCHEMA};
+
+#[test]
+fn requests_the_schema_of_the_database_at_the_version() {
+    let (server, paths) = serve_recording(|_| Response::ok(DEMO_SCHEMA));
+    let output = run(
+        "client_version",
+        &["--server", &server, "--db", "game", "--schema-version", "8"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        *paths.lock().unwrap(),
+        ["/v1/database/game/schema?version=8"]
+    );
+}
+
+#[test]
+fn version_defaults_to_9() {
+    let (server, paths) = serve_recording(|_| Response::ok(DEMO_SCHEMA));
+    let output = run(
+        "client_default_version",
+        &["--server", &server, "--db", "game"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        *paths.lock().unwrap(),
+        ["/v1/database/game/schema?version=9"]
+    );
+}
+
+#[test]
+fn failed_fetch_reports_the_server_message() {
+    let (server, _) = serve_recording(|_| Response::status("404 Not Found", "no such database"));
+    let output = run("client_404", &["--server", &server, "--db", "missing"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Schema fetch failed: no such database"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn query_parameters_are_sent_encoded_after_the_version() {
+    let (server, paths) = serve_recording(|_| Response::ok(DEMO_SCHEMA));
+    let output = run(
+        "client_query",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--query",
+            "expand=all",
+            "--query",
+            "filter=a b&c",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        *paths.lock().unwrap(),
+        ["/v1/database/game/schema?version=9&expand=all&filter=a+b%26c"]
+    );
+}
+
+#[test]
+fn query_cannot_set_the_version() {
+    let (server, paths) = serve_recording(|_| Response::ok(DEMO_SCHEMA));
+    let output = run(
+        "client_query_version",
+        &["--server", &server, "--db", "game", "--query", "version=8"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("use --schema-version instead"), "{stderr}");
+    assert!(paths.lock().unwrap().is_empty());
+}
+
+#[test]
+fn verbose_logs_the_request_url() {
+    let (server, _) = serve_recording(|_| Response::ok(DEMO_SCHEMA));
+    let output = run(
+        "client_verbose",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--query",
+            "expand=all",
+            "-v",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!(
+            "GET {server}/v1/database/game/schema?version=9&expand=all"
+        )),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn unauthorized_fetch_asks_for_a_token() {
+    let (server, _) = serve_recording(|_| Response::status("401 Unauthorized", ""));
+    let output = run("client_401", &["--server", &server, "--db", "private"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Authentication required: pass --token or set SPACETIME_TOKEN"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn rejected_token_is_reported_as_such() {
+    let (server, _) = serve_recording(|_| Response::status("401 Unauthorized", ""));
+    let output = run(
+        "client_401_token",
+        &["--server", &server, "--db", "private", "--token", "expired"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("the server rejected the token"), "{stderr}");
+}
+
+#[test]
+fn slow_server_times_out_with_the_limit_named() {
+    // Accepts the connection but never answers
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let _held: Vec<_> = listener.incoming().collect();
+    });
+    let output = run(
+        "client_timeout",
+        &["--server", &server, "--db", "game", "--timeout", "1"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("timed out after 1s"), "{stderr}");
+    assert!(stderr.contains("--timeout (0 disables it)"), "{stderr}");
+}
+
+#[test]
+fn server_errors_are_retried_with_backoff() {
+    let (server, paths) = serve_recording({
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        move |_| match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            0 | 1 => Response::status("503 Service Unavailable", "warming up"),
+            _ => Response::ok(DEMO_SCHEMA),
+        }
+    });
+    let output = run("client_retry", &["--server", &server, "--db", "game"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(paths.lock().unwrap().len(), 3);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("failed (server returned 503 Service Unavailable); attempt 2 of 3 in 0.5s"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("attempt 3 of 3 in 1.0s"), "{stderr}");
+}
+
+#[test]
+fn client_errors_and_retries_0_fail_on_the_first_attempt() {
+    let (server, paths) =
+        serve_recording(|_| Response::status("404 Not Found", "no such database"));
+    let output = run(
+        "client_no_retry_404",
+        &["--server", &server, "--db", "missing"],
+    );
+    assert!(!output.status.success());
+    assert_eq!(paths.lock().unwrap().len(), 1);
+
+    let (server, paths) =
+        serve_recording(|_| Response::status("500 Internal Server Error", "boom"));
+    let output = run(
+        "client_retries_0",
+        &["--server", &server, "--db", "game", "--retries", "0"],
+    );
+    assert!(!output.status.success());
+    assert_eq!(paths.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn list_prints_one_database_per_line() {
+    let (server, paths) =
+        serve_recording(|_| Response::ok(r#"{"databases": ["game", {"name": "chat"}, 42]}"#));
+    let output = run("client_list", &["--server", &server, "--list"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "game\nchat\n");
+    assert_eq!(*paths.lock().unwrap(), ["/v1/database"]);
+}
+
+#[test]
+fn list_reports_a_server_without_a_listing() {
+    let (server, _) = serve_recording(|_| Response::status("404 Not Found", ""));
+    let output = run("client_list_404", &["--server", &server, "--list"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("This server does not offer a database listing"),
+        "{stderr}"
+    );
+
+    let (server, _) = serve_recording(|_| Response::ok("[]"));
+    let output = run("client_list_empty", &["--server", &server, "--list"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "No databases found\n"
+    );
+}
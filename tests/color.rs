@@ -0,0 +1,62 @@
+//! Color decisions as seen from a piped stdout
+
+mod common;
+
+use common::{command, serve_schema, DEMO_SCHEMA};
+
+fn has_ansi(name: &str, args: &[&str], env: &[(&str, &str)]) -> bool {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = command(
+        name,
+        &["--server", &server, "--db", "game", "--table", "player"],
+    )
+    .args(args)
+    .env_remove("NO_COLOR")
+    .env_remove("CLICOLOR_FORCE")
+    .envs(env.iter().copied())
+    .output()
+    .expect("binary runs");
+    assert!(output.status.success(), "{output:?}");
+    output.stdout.contains(&0x1b)
+}
+
+#[test]
+fn piped_output_is_plain_by_default() {
+    assert!(!has_ansi("color_piped", &[], &[]));
+    assert!(!has_ansi("color_piped", &["--color", "auto"], &[]));
+}
+
+#[test]
+fn color_always_forces_escapes_into_a_pipe() {
+    assert!(has_ansi("color_always", &["--color", "always"], &[]));
+    assert!(has_ansi(
+        "color_always",
+        &["--color", "always"],
+        &[("NO_COLOR", "1")]
+    ));
+}
+
+#[test]
+fn clicolor_force_and_no_color_apply_under_auto() {
+    assert!(has_ansi("color_auto", &[], &[("CLICOLOR_FORCE", "1")]));
+    assert!(!has_ansi("color_auto", &[], &[("CLICOLOR_FORCE", "0")]));
+    assert!(!has_ansi(
+        "color_auto",
+        &[],
+        &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]
+    ));
+    assert!(!has_ansi(
+        "color_auto",
+        &["--color", "never"],
+        &[("CLICOLOR_FORCE", "1")]
+    ));
+}
+
+#[test]
+fn no_color_is_color_never() {
+    assert!(!has_ansi(
+        "color_no_color",
+        &["--no-color"],
+        &[("CLICOLOR_FORCE", "1")]
+    ));
+}
@@ -0,0 +1,176 @@
+//! Helpers shared by the integration tests: a stand-in for the SpacetimeDB
+//! HTTP API that serves fixtures, and a way to run the binary against it
+//! with its output captured instead of going to a terminal
+//!
+//! Every `serve` call listens on its own port, so tests that run in
+//! parallel never share a server, and every run gets its own home
+//! directory under the target directory.
+
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+
+pub const BIN: &str = env!("CARGO_BIN_EXE_spacetime-schema-tool");
+
+/// The schema `--demo` shows, src/demo_schema.json
+pub const DEMO_SCHEMA: &str = include_str!("../../src/demo_schema.json");
+
+/// What the stand-in server answers to one request
+pub struct Response {
+    pub status: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            status: "200 OK",
+            body: body.into(),
+        }
+    }
+
+    pub fn status(status: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Answer every request with `respond(path)` until the test process
+/// exits, and return the server's base URL. `path` includes the query.
+pub fn serve(respond: impl Fn(&str) -> Response + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request);
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let response = respond(path);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.status,
+                response.body.len(),
+                response.body
+            );
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Serve `schema` as the schema of every database
+pub fn serve_schema(schema: &str) -> String {
+    let schema = schema.to_string();
+    serve(move |_| Response::ok(schema.clone()))
+}
+
+/// Like `serve`, also recording the path of every request
+pub fn serve_recording(
+    respond: impl Fn(&str) -> Response + Send + 'static,
+) -> (String, Arc<Mutex<Vec<String>>>) {
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&paths);
+    let url = serve(move |path| {
+        recorded.lock().unwrap().push(path.to_string());
+        respond(path)
+    });
+    (url, paths)
+}
+
+/// An empty directory for one test to use as the home directory
+pub fn home(name: &str) -> PathBuf {
+    let dir = home_dir(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Where `home(name)` is, without emptying it
+pub fn home_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+/// The binary with `args`, reading no user configuration and writing
+/// nothing outside `home(name)`. Its state (history, the legend-shown
+/// marker) goes to `home(name)/state`.
+pub fn command(name: &str, args: &[&str]) -> Command {
+    let home = home(name);
+    let mut command = Command::new(BIN);
+    command
+        .args(args)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("SPACETIME_SCHEMA_TOOL_STATE_DIR", home.join("state"))
+        .env("NO_COLOR", "1")
+        .env_remove("SPACETIME_TOKEN");
+    command
+}
+
+/// Run the binary with `args` and return everything it printed
+pub fn run(name: &str, args: &[&str]) -> Output {
+    command(name, args).output().expect("binary runs")
+}
+
+/// Run the binary with `args` with its stdout on a pseudo-terminal, as at
+/// an interactive shell, check that it succeeded and return what it
+/// printed there
+#[cfg(unix)]
+pub fn terminal(name: &str, args: &[&str]) -> String {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    let (mut master, mut slave) = (0, 0);
+    // SAFETY: openpty only writes the two descriptors it opens
+    let status = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(status, 0, "openpty failed");
+    // SAFETY: both descriptors were just opened and nothing else owns them
+    let (mut master, slave) = unsafe {
+        (
+            std::fs::File::from_raw_fd(master),
+            OwnedFd::from_raw_fd(slave),
+        )
+    };
+
+    // The command, and with it this process's copy of the terminal end,
+    // is dropped once the binary is running
+    let mut child = command(name, args)
+        .env("TERM", "xterm")
+        .stdout(slave)
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("binary runs");
+    let mut printed = Vec::new();
+    // Reading fails with EIO rather than returning 0 once the binary exits
+    let _ = master.read_to_end(&mut printed);
+    assert!(child.wait().unwrap().success());
+    // The terminal turns every \n into \r\n
+    String::from_utf8(printed).unwrap().replace("\r\n", "\n")
+}
+
+/// Run the binary with `args`, check that it succeeded and return stdout
+pub fn stdout(name: &str, args: &[&str]) -> String {
+    let output = run(name, args);
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,60 @@
+//! --coverage and the coverage line --verbose prints
+
+mod common;
+
+use std::process::Output;
+
+use common::{run, serve_schema};
+
+const EXTRA_KEYS: &str = include_str!("fixtures/extra_keys.json");
+
+fn coverage(name: &str, args: &[&str]) -> Output {
+    let server = serve_schema(EXTRA_KEYS);
+    let args = [&["--server", &server, "--db", "extra"][..], args].concat();
+    let output = run(name, &args);
+    assert!(output.status.success(), "{output:?}");
+    output
+}
+
+#[test]
+fn json_report_matches_snapshot() {
+    let output = coverage("coverage_json", &["--coverage", "--format", "json"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let expected: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/extra_keys_coverage.json")).unwrap();
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn pretty_report_lists_every_ignored_key() {
+    let output = coverage("coverage_pretty", &["--coverage"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Modeled:  91.0% of 1996 bytes"), "{stdout}");
+    assert!(
+        stdout.contains("Ignored:  8 of 135 keys (180 bytes)"),
+        "{stdout}"
+    );
+    for line in [
+        "/future_feature x1 e.g. /future_feature",
+        "/reducers/*/params/elements/*/default x1 e.g. /reducers/0/params/elements/1/default",
+        "/tables/*/table_access x2 e.g. /tables/0/table_access",
+        "/types/*/docs x1 e.g. /types/0/docs",
+    ] {
+        assert!(stdout.contains(line), "missing {line:?} in\n{stdout}");
+    }
+}
+
+#[test]
+fn verbose_prints_the_percentage_on_stderr() {
+    // raw and json stream the document unparsed, so they have no coverage to report
+    let verbose = coverage("coverage_verbose", &["--verbose", "--format", "markdown"]);
+    let stderr = String::from_utf8_lossy(&verbose.stderr);
+    assert!(
+        stderr.contains("Coverage: 91.0% of the document modeled, 8 of 135 keys ignored"),
+        "{stderr}"
+    );
+    let quiet = coverage("coverage_quiet", &["--format", "markdown"]);
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("Coverage:"));
+    assert_eq!(verbose.stdout, quiet.stdout);
+}
@@ -0,0 +1,51 @@
+//! --demo: the bundled schema, shown without a server
+
+mod common;
+
+use common::{home_dir, run, stdout};
+
+#[test]
+fn walkthrough_runs_without_a_server() {
+    // Nothing listens on the default server in the test environment
+    let output = run("demo_walkthrough", &["--demo"]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr, "* Using the bundled demo schema\n");
+    let output = String::from_utf8(output.stdout).unwrap();
+    for caption in [
+        "[i] The whole schema: tables, then the other types",
+        "[i] One table with its field markers",
+        "[i] Everything whose name matches a pattern",
+        "[i] One enum and its variants",
+    ] {
+        assert!(output.contains(caption), "no {caption:?} in {output}");
+    }
+    assert!(output.contains("* TABLES (6)"));
+}
+
+#[test]
+fn output_format_renders_the_bundled_schema() {
+    let kdl = stdout("demo_kdl", &["--demo", "--format", "kdl"]);
+    assert!(kdl.starts_with(include_str!("fixtures/demo.kdl")));
+    assert!(kdl.contains("\n// server: bundled demo schema\n"));
+    assert!(kdl.contains("\n// database: demo\n"));
+}
+
+#[test]
+fn demo_runs_are_not_recorded_in_the_history() {
+    assert!(run("demo_history", &["--demo", "--format", "kdl"])
+        .status
+        .success());
+    assert!(!home_dir("demo_history")
+        .join("state")
+        .join("history.json")
+        .exists());
+}
+
+#[test]
+fn demo_conflicts_with_flags_that_need_a_server() {
+    for flag in ["--selftest", "--probe-endpoints", "--last-seen"] {
+        let output = run("demo_conflicts", &["--demo", flag]);
+        assert!(!output.status.success(), "--demo {flag} ran");
+    }
+}
@@ -0,0 +1,88 @@
+//! --diff-version: the tables and fields that changed between two
+//! versions of a database's schema
+
+mod common;
+
+use common::{serve, stdout, Response, DEMO_SCHEMA};
+use serde_json::{json, Value};
+
+/// The demo schema as it was before `chat_message`, `Player.inventory`
+/// and the optional `Player.nickname`
+fn older_schema() -> String {
+    let mut document: Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+    let tables = document["tables"].as_array_mut().unwrap();
+    tables.retain(|table| table["name"] != "chat_message");
+    let player = tables[0]["product_type_ref"].as_u64().unwrap() as usize;
+    let fields = document["typespace"]["types"][player]["Product"]["elements"]
+        .as_array_mut()
+        .unwrap();
+    fields.retain(|field| field["name"]["some"] != "inventory");
+    fields[5]["algebraic_type"] = json!({"String": []});
+    document.to_string()
+}
+
+fn server() -> String {
+    let older = older_schema();
+    serve(move |path| {
+        if path.contains("version=8") {
+            Response::ok(older.clone())
+        } else {
+            Response::ok(DEMO_SCHEMA)
+        }
+    })
+}
+
+#[test]
+fn reports_added_tables_and_changed_fields() {
+    let server = server();
+    let stdout = stdout(
+        "diff_version",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--schema-version",
+            "8",
+            "--diff-version",
+            "9",
+        ],
+    );
+    assert!(
+        stdout.contains("* SCHEMA DIFF: version 8 -> version 9"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("\n+ table chat_message\n"), "{stdout}");
+    assert!(stdout.contains("\n~ table player\n"), "{stdout}");
+    assert!(
+        stdout.contains("\n    ~ nickname: String -> Option<String>\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("\n    + inventory: Vec<ItemStack>\n"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("table guild\n"), "{stdout}");
+}
+
+#[test]
+fn identical_versions_have_no_differences() {
+    let server = server();
+    let stdout = stdout(
+        "diff_version_same",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--schema-version",
+            "9",
+            "--diff-version",
+            "10",
+        ],
+    );
+    assert!(
+        stdout.contains("[ok] No differences in tables or fields"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,23 @@
+//! --expand-all on the demo's self-referencing ItemStack
+
+mod common;
+
+use common::stdout;
+
+#[test]
+fn recursive_field_types_are_expanded_once() {
+    let stdout = stdout(
+        "expand_all",
+        &["--demo", "--expand-all", "--table", "player"],
+    );
+    assert!(
+        stdout.contains(
+            "inventory: Vec<ItemStack { item_id: u32, count: u16, next: Option<ItemStack> }>"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("status: PlayerStatus { Online | Away | Banned(String) }"),
+        "{stdout}"
+    );
+}
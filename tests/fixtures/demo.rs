@@ -0,0 +1,96 @@
+use spacetimedb_sdk::{Identity, ScheduleAt, TimeDuration, Timestamp};
+
+/// Row type of the `player` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Player {
+    pub id: u64,
+    pub identity: Identity,
+    pub name: String,
+    pub status: PlayerStatus,
+    pub position: Vec2,
+    pub nickname: Option<String>,
+    pub created_at: Timestamp,
+    pub inventory: Vec<ItemStack>,
+}
+
+/// Row type of the `item_kind` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ItemKind {
+    pub id: u32,
+    pub name: String,
+    pub rarity: Rarity,
+    pub stack_size: u16,
+    pub description: Option<String>,
+}
+
+/// Row type of the `guild` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Guild {
+    pub id: u64,
+    pub name: String,
+    pub founded: Timestamp,
+    pub motto: Option<String>,
+}
+
+/// Row type of the `guild_member` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GuildMember {
+    pub guild_id: u64,
+    pub player_id: u64,
+    pub role: GuildRole,
+    pub joined: Timestamp,
+}
+
+/// Row type of the `chat_message` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChatMessage {
+    pub id: u64,
+    pub sender: Identity,
+    pub channel: String,
+    pub text: String,
+    pub sent: Timestamp,
+}
+
+/// Row type of the `game_tick` table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameTick {
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    pub tick_rate: TimeDuration,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum GuildRole {
+    Member,
+    Officer,
+    Leader,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ItemStack {
+    pub item_id: u32,
+    pub count: u16,
+    pub next: Option<Box<ItemStack>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum PlayerStatus {
+    Online,
+    Away,
+    Banned(String),
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
@@ -0,0 +1,43 @@
+import { z } from "zod";
+
+export const ChatMessageSchema = z.object({ id: z.bigint().min(0n).max(18446744073709551615n), sender: z.object({ __identity__: z.bigint().min(0n).max(115792089237316195423570985008687907853269984665640564039457584007913129639935n) }), channel: z.string(), text: z.string(), sent: z.object({ __timestamp_micros_since_unix_epoch__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }) });
+export type ChatMessage = z.infer<typeof ChatMessageSchema>;
+
+export const GameTickSchema = z.object({ scheduled_id: z.bigint().min(0n).max(18446744073709551615n), scheduled_at: z.discriminatedUnion("tag", [z.object({ tag: z.literal("Interval"), value: z.object({ __time_duration_micros__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }) }), z.object({ tag: z.literal("Time"), value: z.object({ __timestamp_micros_since_unix_epoch__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }) })]), tick_rate: z.object({ __time_duration_micros__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }) });
+export type GameTick = z.infer<typeof GameTickSchema>;
+
+export const GuildSchema = z.object({ id: z.bigint().min(0n).max(18446744073709551615n), name: z.string(), founded: z.object({ __timestamp_micros_since_unix_epoch__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }), motto: z.string().nullable() });
+export type Guild = z.infer<typeof GuildSchema>;
+
+export const GuildRoleSchema = z.enum(["Member", "Officer", "Leader"]);
+export type GuildRole = z.infer<typeof GuildRoleSchema>;
+
+export const GuildMemberSchema = z.object({ guild_id: z.bigint().min(0n).max(18446744073709551615n), player_id: z.bigint().min(0n).max(18446744073709551615n), role: GuildRoleSchema, joined: z.object({ __timestamp_micros_since_unix_epoch__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }) });
+export type GuildMember = z.infer<typeof GuildMemberSchema>;
+
+export const RaritySchema = z.enum(["Common", "Uncommon", "Rare", "Epic", "Legendary"]);
+export type Rarity = z.infer<typeof RaritySchema>;
+
+export const ItemKindSchema = z.object({ id: z.number().int().min(0).max(4294967295), name: z.string(), rarity: RaritySchema, stack_size: z.number().int().min(0).max(65535), description: z.string().nullable() });
+export type ItemKind = z.infer<typeof ItemKindSchema>;
+
+export const ItemStackSchema: z.ZodTypeAny = z.object({ item_id: z.number().int().min(0).max(4294967295), count: z.number().int().min(0).max(65535), next: z.lazy(() => ItemStackSchema).nullable() });
+export type ItemStack = z.infer<typeof ItemStackSchema>;
+
+export const PlayerStatusSchema = z.discriminatedUnion("tag", [z.object({ tag: z.literal("Online") }), z.object({ tag: z.literal("Away") }), z.object({ tag: z.literal("Banned"), value: z.string() })]);
+export type PlayerStatus = z.infer<typeof PlayerStatusSchema>;
+
+export const Vec2Schema = z.object({ x: z.number(), y: z.number() });
+export type Vec2 = z.infer<typeof Vec2Schema>;
+
+export const PlayerSchema = z.object({ id: z.bigint().min(0n).max(18446744073709551615n), identity: z.object({ __identity__: z.bigint().min(0n).max(115792089237316195423570985008687907853269984665640564039457584007913129639935n) }), name: z.string(), status: PlayerStatusSchema, position: Vec2Schema, nickname: z.string().nullable(), created_at: z.object({ __timestamp_micros_since_unix_epoch__: z.bigint().min(-9223372036854775808n).max(9223372036854775807n) }), inventory: z.array(ItemStackSchema) });
+export type Player = z.infer<typeof PlayerSchema>;
+
+export const tableSchemas = {
+  player: PlayerSchema,
+  item_kind: ItemKindSchema,
+  guild: GuildSchema,
+  guild_member: GuildMemberSchema,
+  chat_message: ChatMessageSchema,
+  game_tick: GameTickSchema,
+} as const;
@@ -0,0 +1,15 @@
+//! The pretty --index-report view
+
+mod common;
+
+use common::{serve_schema, terminal};
+
+#[test]
+fn pretty_report_matches_snapshot() {
+    let server = serve_schema(include_str!("fixtures/index_tables.json"));
+    let stdout = terminal(
+        "index_report",
+        &["--server", &server, "--db", "guilds", "--index-report"],
+    );
+    assert_eq!(stdout, include_str!("fixtures/index_report.txt"));
+}
@@ -0,0 +1,75 @@
+//! --infer-names on the game module with its `types` list stripped
+
+mod common;
+
+use std::process::Output;
+
+use common::{command, serve_schema, DEMO_SCHEMA};
+
+fn stripped() -> String {
+    let mut document: serde_json::Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+    document["types"] = serde_json::json!([]);
+    document.to_string()
+}
+
+fn run(name: &str, args: &[&str]) -> Output {
+    let server = serve_schema(&stripped());
+    let output = command(name, &["--server", &server, "--db", "game"])
+        .args(args)
+        .output()
+        .expect("binary runs");
+    assert!(output.status.success(), "{output:?}");
+    output
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).expect("stdout is UTF-8")
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).expect("stderr is UTF-8")
+}
+
+#[test]
+fn empty_types_suggest_infer_names() {
+    let output = run("infer_names_hint", &[]);
+    assert!(stderr(&output).contains("--infer-names can guess them"));
+    assert!(stdout(&output).contains("Type_5"));
+}
+
+#[test]
+fn inferred_names_are_marked_in_the_output() {
+    let output = run(
+        "infer_names_marked",
+        &["--infer-names", "--table", "player"],
+    );
+    assert!(stderr(&output).contains("Inferred 11 type names (marked with ~)"));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Type: ~Player"), "{stdout}");
+    assert!(stdout.contains("position: ~Position"), "{stdout}");
+    assert!(stdout.contains("inventory: Vec<~Inventory>"), "{stdout}");
+    assert!(!stdout.contains("Type_"), "{stdout}");
+}
+
+#[test]
+fn inferred_names_do_not_change_the_fingerprint() {
+    let state = common::home("infer_names_fingerprint_state");
+    let server = serve_schema(&stripped());
+    for args in [&[][..], &["--infer-names"][..]] {
+        let output = command(
+            "infer_names_fingerprint",
+            &["--server", &server, "--db", "game"],
+        )
+        .args(args)
+        .env("SPACETIME_SCHEMA_TOOL_STATE_DIR", &state)
+        .output()
+        .expect("binary runs");
+        assert!(output.status.success(), "{output:?}");
+    }
+    // Both fetches land in one history entry: the fingerprint is the same
+    let history = std::fs::read_to_string(state.join("history.json")).unwrap();
+    let history: serde_json::Value = serde_json::from_str(&history).unwrap();
+    let entries = history[&server]["game"].as_array().unwrap();
+    assert_eq!(entries.len(), 1, "{history}");
+    assert_eq!(entries[0]["fetches"], 2, "{history}");
+}
@@ -0,0 +1,46 @@
+//! --format jsonschema from the command line
+
+mod common;
+
+use std::process::Output;
+
+use common::run;
+
+fn jsonschema(name: &str, args: &[&str]) -> Output {
+    let argv: Vec<_> = ["--demo", "--format", "jsonschema"]
+        .iter()
+        .chain(args)
+        .copied()
+        .collect();
+    run(name, &argv)
+}
+
+#[test]
+fn demo_matches_snapshot() {
+    let output = jsonschema("jsonschema_demo", &[]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        include_str!("fixtures/demo.schema.json")
+    );
+}
+
+#[test]
+fn table_filter_emits_one_row_schema() {
+    let output = jsonschema("jsonschema_table", &["--table", "guild"]);
+    assert!(output.status.success(), "{output:?}");
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["title"], "guild");
+    assert_eq!(document["$ref"], "#/$defs/Guild");
+    let defs: Vec<_> = document["$defs"].as_object().unwrap().keys().collect();
+    assert_eq!(defs, ["Guild"]);
+}
+
+#[test]
+fn unknown_table_fails() {
+    let output = jsonschema("jsonschema_unknown_table", &["--table", "nope"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Table 'nope' not found"));
+}
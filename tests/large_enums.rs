@@ -0,0 +1,105 @@
+//! Collapsing and paging of enums with hundreds of variants in the pretty
+//! view, and folding them in Markdown and HTML
+
+mod common;
+
+use common::{serve_schema, stdout};
+
+/// One table keyed by a 400-variant `LocalizationKey`
+const BIG_ENUM: &str = include_str!("fixtures/big_enum.json");
+
+fn pretty(name: &str, args: &[&str]) -> String {
+    let server = serve_schema(BIG_ENUM);
+    let args = [
+        &["--server", &server, "--db", "text", "--no-warnings"][..],
+        args,
+    ]
+    .concat();
+    stdout(name, &args)
+}
+
+#[test]
+fn overview_collapses_large_enums_to_one_line() {
+    let stdout = pretty("large_enums_overview", &[]);
+    assert!(
+        stdout.contains(
+            "  * LocalizationKey (enum with 400 variants) -> --enum LocalizationKey to list them\n"
+        ),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("Key0"));
+}
+
+#[test]
+fn threshold_is_configurable() {
+    let stdout = pretty(
+        "large_enums_threshold",
+        &["--enum-collapse-threshold", "400"],
+    );
+    assert!(!stdout.contains("to list them"));
+    assert!(stdout.contains("  * LocalizationKey (enum with 400 variants)\n    |- Key0\n"));
+    assert!(stdout.contains("Key399"));
+
+    let stdout = pretty("large_enums_threshold", &["--enum-collapse-threshold", "0"]);
+    assert!(stdout.contains("Key399"));
+}
+
+#[test]
+fn enum_view_pages_with_top_and_skip() {
+    let stdout = pretty(
+        "large_enums_paging",
+        &["--enum", "LocalizationKey", "--skip", "100", "--top", "3"],
+    );
+    assert!(
+        stdout.contains(
+            "Variants (400):\n  showing 101-103 of 400\n  * Key100\n  * Key101\n  * Key102\n  ... 297 more (--skip 103)\n"
+        ),
+        "{stdout}"
+    );
+
+    let stdout = pretty(
+        "large_enums_paging",
+        &["--enum", "LocalizationKey", "--skip", "398"],
+    );
+    assert!(
+        stdout.contains("  showing 399-400 of 400\n  * Key398\n  * Key399\n"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("more (--skip"));
+}
+
+#[test]
+fn enum_view_lists_everything_by_default() {
+    let stdout = pretty("large_enums_all", &["--enum", "LocalizationKey"]);
+    assert!(stdout.contains("  * Key0\n"));
+    assert!(stdout.contains("  * Key399\n"));
+    assert!(!stdout.contains("showing"));
+}
+
+#[test]
+fn markdown_folds_large_enums_into_details() {
+    let stdout = pretty("large_enums_markdown", &["--format", "markdown"]);
+    assert!(
+        stdout.contains(
+            "### `LocalizationKey` (enum)\n\n<details>\n<summary>400 variants</summary>\n\n- `Key0`\n"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("- `Key399`\n\n</details>\n"));
+
+    let stdout = pretty(
+        "large_enums_markdown",
+        &["--format", "markdown", "--enum-collapse-threshold", "0"],
+    );
+    assert!(!stdout.contains("<details>"));
+    assert!(stdout.contains("- `Key399`\n"));
+}
+
+#[test]
+fn html_folds_large_enums_into_details() {
+    let stdout = pretty("large_enums_html", &["--format", "html"]);
+    assert!(
+        stdout.contains("<details>\n<summary>400 variants</summary>\n<table>\n"),
+        "{stdout}"
+    );
+}
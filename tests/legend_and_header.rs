@@ -0,0 +1,137 @@
+//! The first-run marker legend and the --header field layout
+
+mod common;
+
+use std::path::Path;
+
+use common::{command, home, serve_schema};
+
+const LONG_FIELD_NAMES: &str = include_str!("fixtures/long_field_names.json");
+
+/// Stdout of a run that keeps its state in `state`
+fn run(name: &str, state: &Path, args: &[&str]) -> String {
+    let server = serve_schema(LONG_FIELD_NAMES);
+    let output = command(name, &["--server", &server, "--db", "sync"])
+        .args(args)
+        .env("SPACETIME_SCHEMA_TOOL_STATE_DIR", state)
+        .output()
+        .expect("binary runs");
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).expect("stdout is UTF-8")
+}
+
+fn legend(stdout: &str) -> Option<&str> {
+    stdout.lines().find(|line| line.starts_with("Markers:"))
+}
+
+#[test]
+fn legend_is_shown_on_the_first_run_only() {
+    let state = home("legend_first_run_state").join("state");
+
+    let first = run("legend_first_run", &state, &[]);
+    assert!(legend(&first)
+        .unwrap()
+        .ends_with("(--legend shows this again)"));
+    assert!(state.join("legend-shown").exists());
+
+    let second = run("legend_first_run", &state, &[]);
+    assert_eq!(legend(&second), None);
+
+    let asked = run("legend_first_run", &state, &["--legend"]);
+    let line = legend(&asked).unwrap();
+    assert!(line.contains("[pk] primary key"), "{line}");
+    assert!(!line.contains("--legend shows this again"));
+}
+
+#[test]
+fn machine_formats_leave_the_legend_state_alone() {
+    let state = home("legend_machine_formats_state").join("state");
+    let stdout = run("legend_machine_formats", &state, &["--format", "json"]);
+    assert!(!stdout.contains("Markers:"));
+    assert!(!state.join("legend-shown").exists());
+
+    // So the pretty view still shows it the first time
+    assert!(legend(&run("legend_machine_formats", &state, &[])).is_some());
+}
+
+#[test]
+fn unwritable_state_shows_the_legend_every_time() {
+    let dir = home("legend_unwritable_state");
+    // A file where the state directory should be
+    let state = dir.join("state");
+    std::fs::write(&state, "").unwrap();
+    for _ in 0..2 {
+        assert!(legend(&run("legend_unwritable", &state, &[])).is_some());
+    }
+}
+
+/// Character offsets of the `type` and `flags` columns in each line of
+/// the field list under the header
+fn column_offsets(stdout: &str) -> Vec<(String, usize, Option<usize>)> {
+    let mut lines = stdout
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("field "));
+    let header: Vec<char> = lines.next().expect("a header row").chars().collect();
+    let type_at = header
+        .windows(4)
+        .position(|w| w.iter().collect::<String>() == "type")
+        .unwrap();
+    let flags_at = header
+        .windows(5)
+        .position(|w| w.iter().collect::<String>() == "flags")
+        .unwrap();
+    let field_at = header.iter().position(|c| !c.is_whitespace()).unwrap();
+
+    let mut offsets = vec![("field".to_string(), type_at, Some(flags_at))];
+    for line in lines.take_while(|line| !line.trim().is_empty()) {
+        let chars: Vec<char> = line.chars().collect();
+        let name: String = chars[field_at..]
+            .iter()
+            .take_while(|c| !c.is_whitespace())
+            .collect();
+        assert!(!chars[field_at - 1].is_alphanumeric(), "{line}");
+        let type_start = (field_at + name.chars().count()..chars.len())
+            .find(|&i| !chars[i].is_whitespace())
+            .unwrap();
+        let type_end = (type_start..chars.len())
+            .find(|&i| chars[i].is_whitespace())
+            .unwrap_or(chars.len());
+        let flags_start = (type_end..chars.len()).find(|&i| !chars[i].is_whitespace());
+        offsets.push((name, type_start, flags_start));
+    }
+    offsets
+}
+
+#[test]
+fn header_aligns_with_long_and_non_ascii_field_names() {
+    let state = home("header_alignment_state").join("state");
+    let stdout = run("header_alignment", &state, &["--header"]);
+    let offsets = column_offsets(&stdout);
+    let names: Vec<_> = offsets.iter().map(|(name, ..)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "field",
+            "id",
+            "last_successful_synchronization_timestamp_micros",
+            "größe",
+            "note"
+        ],
+        "{stdout}"
+    );
+    let (_, type_at, flags_at) = offsets[0];
+    for (name, type_start, flags_start) in &offsets[1..] {
+        assert_eq!(*type_start, type_at, "type of {name}:\n{stdout}");
+        if let Some(flags_start) = flags_start {
+            assert_eq!(Some(*flags_start), flags_at, "flags of {name}:\n{stdout}");
+        }
+    }
+}
+
+#[test]
+fn header_is_off_by_default() {
+    let state = home("header_default_state").join("state");
+    let stdout = run("header_default", &state, &[]);
+    assert!(!stdout.contains("field "), "{stdout}");
+    assert!(stdout.contains(" id: u64"), "{stdout}");
+}
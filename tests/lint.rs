@@ -0,0 +1,56 @@
+//! --lint and the naming warning count in the summary
+
+mod common;
+
+use common::{serve_schema, stdout, DEMO_SCHEMA};
+
+/// The game module with a table named like a system table
+fn server() -> String {
+    let mut schema: serde_json::Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+    schema["tables"][0]["name"] = "st_player".into();
+    serve_schema(&schema.to_string())
+}
+
+#[test]
+fn summary_only_counts_warnings() {
+    let output = stdout("lint_summary", &["--server", &server(), "--db", "game"]);
+    assert!(
+        output.contains("2 naming warnings (--lint lists them)"),
+        "{output}"
+    );
+    assert!(!output.contains("chat_message.sender"), "{output}");
+}
+
+#[test]
+fn lint_lists_each_warning() {
+    let output = stdout(
+        "lint_pretty",
+        &["--server", &server(), "--db", "game", "--lint"],
+    );
+    assert!(
+        output.contains("error st_player: `st_` is the prefix"),
+        "{output}"
+    );
+    assert!(output.contains("warning chat_message.sender:"), "{output}");
+}
+
+#[test]
+fn lint_as_json() {
+    let output = stdout(
+        "lint_json",
+        &[
+            "--server",
+            &server(),
+            "--db",
+            "game",
+            "--lint",
+            "--format",
+            "json",
+        ],
+    );
+    let json = &output[output.find('[').unwrap()..];
+    let warnings: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(warnings[0]["severity"], "error");
+    assert_eq!(warnings[0]["table"], "st_player");
+    assert_eq!(warnings[1]["column"], "sender");
+}
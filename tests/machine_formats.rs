@@ -0,0 +1,129 @@
+//! Every format other than pretty must leave stdout holding nothing but
+//! the document, so it can be piped or redirected as-is
+
+mod common;
+
+use serde_json::Value;
+
+/// What stdout must look like for a format
+enum Expect {
+    /// One JSON document
+    Json,
+    /// One JSON document per line
+    JsonLines,
+    /// Text starting with this line
+    FirstLine(&'static str),
+}
+
+const GENERATED: &str = "Generated by spacetime-schema-tool";
+
+const DEMO_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/demo_schema.json");
+
+fn expectation(format: &str) -> Expect {
+    match format {
+        "json" | "raw" | "catalog" | "jsonschema" | "resolved" | "model" => Expect::Json,
+        "jsonl" => Expect::JsonLines,
+        "zod" | "rust" | "dot" | "ts" | "csharp" | "proto" | "go" | "kotlin" => {
+            Expect::FirstLine("// ")
+        }
+        "mermaid" => Expect::FirstLine("%% "),
+        "sql" => Expect::FirstLine("-- "),
+        "kdl" => Expect::FirstLine("table \"player\" type=\"Player\" {"),
+        "markdown" => Expect::FirstLine("# Schema: demo"),
+        "html" => Expect::FirstLine("<!DOCTYPE html>"),
+        "template" => Expect::FirstLine("player"),
+        "compact" => Expect::FirstLine("chat_message (5 fields, pk: id) -> ChatMessage"),
+        "csv" => Expect::FirstLine(
+            "table,field_index,field_name,resolved_type,is_primary_key,is_optional,is_array",
+        ),
+        other => panic!("no expectation for --format {other}; add one here"),
+    }
+}
+
+fn run(name: &str, args: &[&str]) -> (String, String) {
+    let output = common::run(name, args);
+    assert!(
+        output.status.success(),
+        "{args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (
+        String::from_utf8(output.stdout).expect("stdout is UTF-8"),
+        String::from_utf8(output.stderr).expect("stderr is UTF-8"),
+    )
+}
+
+/// Output formats as listed by `--capabilities`, so a new format can't be
+/// added without an expectation here
+fn formats() -> Vec<String> {
+    let (stdout, _) = run("machine_formats_capabilities", &["--capabilities"]);
+    let manifest: Value = serde_json::from_str(&stdout).expect("capabilities are JSON");
+    manifest["output_formats"]
+        .as_array()
+        .expect("output_formats is an array")
+        .iter()
+        .map(|f| f.as_str().expect("format names are strings").to_string())
+        .filter(|f| f != "pretty")
+        .collect()
+}
+
+fn check(format: &str, stdout: &str) {
+    match expectation(format) {
+        Expect::Json => {
+            if let Err(e) = serde_json::from_str::<Value>(stdout) {
+                panic!("--format {format} is not JSON ({e}):\n{stdout}");
+            }
+        }
+        Expect::JsonLines => {
+            for line in stdout.lines() {
+                if let Err(e) = serde_json::from_str::<Value>(line) {
+                    panic!("--format {format} line is not JSON ({e}): {line}");
+                }
+            }
+        }
+        Expect::FirstLine(first) => {
+            let line = stdout.lines().next().unwrap_or_default();
+            assert!(
+                line.starts_with(first),
+                "--format {format} starts with {line:?}, expected {first:?}"
+            );
+            if first.ends_with(' ') {
+                assert!(line.contains(GENERATED), "--format {format}: {line:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn machine_formats_keep_stdout_clean() {
+    for format in formats() {
+        let mut args = vec!["--demo", "--format", format.as_str(), "--reproducible"];
+        if format == "template" {
+            args.extend(["--template", "tests/fixtures/tables.hbs"]);
+        }
+        let (stdout, stderr) = run(&format!("machine_formats_{format}"), &args);
+        check(&format, &stdout);
+        assert!(
+            stderr.contains("Using the bundled demo schema"),
+            "--format {format}: status line missing from stderr"
+        );
+    }
+}
+
+#[test]
+fn loading_a_file_is_reported_on_stderr() {
+    for format in ["resolved", "rust"] {
+        let (stdout, stderr) = run(
+            &format!("machine_formats_file_{format}"),
+            &["--file", DEMO_FILE, "--format", format],
+        );
+        assert!(!stdout.contains("Loading from file"), "--format {format}");
+        assert!(stderr.contains("Loading from file"), "--format {format}");
+    }
+    let (stdout, _) = run(
+        "machine_formats_file",
+        &["--file", DEMO_FILE, "--format", "resolved"],
+    );
+    let resolved: Value = serde_json::from_str(&stdout).expect("resolved output is JSON");
+    assert!(resolved["tables"].is_array());
+}
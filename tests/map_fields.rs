@@ -0,0 +1,83 @@
+//! Map-typed columns in every output format
+
+mod common;
+
+use common::{serve_schema, stdout};
+
+/// An `inventory` table with `HashMap<String, u32>` and
+/// `HashMap<u8, ItemStack>` columns
+const MAP_TABLE: &str = include_str!("fixtures/map_table.json");
+
+fn render(format: &str) -> String {
+    let server = serve_schema(MAP_TABLE);
+    stdout(
+        &format!("map_fields_{format}"),
+        &[
+            "--server",
+            &server,
+            "--db",
+            "maps",
+            "--format",
+            format,
+            "--no-metadata-footer",
+        ],
+    )
+}
+
+#[test]
+fn pretty_view_shows_key_and_value_types() {
+    let pretty = render("pretty");
+    assert!(
+        pretty.contains("    |- counts: HashMap<String, u32>\n"),
+        "{pretty}"
+    );
+    assert!(
+        pretty.contains("    |- slots: HashMap<u8, ItemStack>\n"),
+        "{pretty}"
+    );
+}
+
+#[test]
+fn generators_map_to_their_map_types() {
+    let zod = render("zod");
+    assert!(
+        zod.contains("counts: z.map(z.string(), z.number().int().min(0).max(4294967295))"),
+        "{zod}"
+    );
+    assert!(
+        zod.contains("slots: z.map(z.number().int().min(0).max(255), ItemStackSchema)"),
+        "{zod}"
+    );
+
+    let rust = render("rust");
+    assert!(
+        rust.contains("    pub counts: std::collections::HashMap<String, u32>,\n"),
+        "{rust}"
+    );
+    assert!(
+        rust.contains("    pub slots: std::collections::HashMap<u8, ItemStack>,\n"),
+        "{rust}"
+    );
+
+    let kdl = render("kdl");
+    assert!(
+        kdl.contains("    field \"slots\" \"HashMap<u8, ItemStack>\"\n"),
+        "{kdl}"
+    );
+}
+
+#[test]
+fn map_values_are_followed_to_their_types() {
+    let dot = render("dot");
+    assert!(
+        dot.contains("    \"table:inventory\" -> \"type:ItemStack\" [label=\"slots\"];\n"),
+        "{dot}"
+    );
+
+    let catalog = render("catalog");
+    assert!(catalog.contains("\"dataType\": \"MAP\""), "{catalog}");
+    assert!(
+        catalog.contains("\"dataTypeDisplay\": \"HashMap<u8, ItemStack>\""),
+        "{catalog}"
+    );
+}
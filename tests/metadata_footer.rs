@@ -0,0 +1,116 @@
+//! The provenance block written into generated documents
+
+mod common;
+
+use common::{serve_schema, stdout, DEMO_SCHEMA};
+
+/// Formats with a metadata block, and the comment marker each one uses
+const FORMATS: &[(&str, &str)] = &[("kdl", "//"), ("zod", "//"), ("rust", "//"), ("dot", "//")];
+
+/// The server serving the game module, and what `args` print against it
+fn generate(name: &str, args: &[&str]) -> (String, String) {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = generate_from(&server, name, args);
+    (server, output)
+}
+
+fn generate_from(server: &str, name: &str, args: &[&str]) -> String {
+    stdout(
+        name,
+        &[&["--server", server, "--db", "game"][..], args].concat(),
+    )
+}
+
+fn expected_block(comment: &str, server: &str, query: Option<&str>, command: &str) -> String {
+    [
+        Some(format!(
+            "Generated by spacetime-schema-tool {}",
+            env!("CARGO_PKG_VERSION")
+        )),
+        Some(format!("server: {server}")),
+        Some("database: game".to_string()),
+        Some("schema version: 9".to_string()),
+        query.map(|query| format!("query: {query}")),
+        Some("schema fingerprint: a1f98cf78d950290".to_string()),
+        Some(format!("command: spacetime-schema-tool {command}")),
+    ]
+    .iter()
+    .flatten()
+    .map(|line| format!("{comment} {line}\n"))
+    .collect()
+}
+
+#[test]
+fn reproducible_block_matches_golden() {
+    for (format, comment) in FORMATS {
+        let args = ["--format", format, "--reproducible"];
+        let (server, output) = generate("metadata_reproducible", &args);
+        let block = expected_block(
+            comment,
+            &server,
+            None,
+            &format!("--server {server} --db game --format {format} --reproducible"),
+        );
+        assert!(output.contains(&block), "{format}:\n{output}");
+        assert!(!output.contains("fetched at:"), "{format}");
+        let again = generate_from(&server, "metadata_reproducible", &args);
+        assert_eq!(again, output, "{format} is not reproducible");
+    }
+}
+
+#[test]
+fn html_footer_matches_golden() {
+    let args = ["--format", "html", "--reproducible"];
+    let (server, output) = generate("metadata_html", &args);
+    let block: String = expected_block(
+        "",
+        &server,
+        None,
+        &format!("--server {server} --db game --format html --reproducible"),
+    )
+    .lines()
+    .map(|line| format!("<div>{}</div>\n", line.trim_start()))
+    .collect();
+    assert!(
+        output.contains(&format!("<footer>\n{block}</footer>")),
+        "{output}"
+    );
+}
+
+#[test]
+fn query_parameters_are_recorded() {
+    let args = ["--format", "kdl", "--reproducible", "--query", "filter=a b"];
+    let (server, output) = generate("metadata_query", &args);
+    let block = expected_block(
+        "//",
+        &server,
+        Some("filter=a+b"),
+        &format!("--server {server} --db game --format kdl --reproducible --query 'filter=a b'"),
+    );
+    assert!(output.contains(&block), "{output}");
+}
+
+#[test]
+fn timestamp_is_included_unless_reproducible() {
+    let (_, output) = generate("metadata_timestamp", &["--format", "kdl"]);
+    let fetched_at = output
+        .lines()
+        .find_map(|line| line.strip_prefix("// fetched at: "))
+        .expect("a fetched at line");
+    assert!(fetched_at.ends_with('Z'), "{fetched_at}");
+}
+
+#[test]
+fn no_metadata_footer_removes_the_block() {
+    for (format, _) in FORMATS {
+        let (_, output) = generate(
+            "metadata_none",
+            &["--format", format, "--no-metadata-footer"],
+        );
+        assert!(
+            !output.contains("Generated by spacetime-schema-tool"),
+            "{format}"
+        );
+        assert!(!output.contains("schema fingerprint:"), "{format}");
+    }
+}
@@ -0,0 +1,141 @@
+//! --output, and progress messages staying off stdout
+
+mod common;
+
+use common::{command, home, run, serve, serve_schema, Response, DEMO_SCHEMA};
+
+#[test]
+fn results_go_to_the_file_and_progress_to_stderr() {
+    let server = serve_schema(DEMO_SCHEMA);
+    let path = home("output_file_target").join("schema.kdl");
+    let output = run(
+        "output_file",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--format",
+            "kdl",
+            "--output",
+            path.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("🌐 Fetching schema from:"), "{stderr}");
+    assert!(stderr.contains("✅ Fetched"), "{stderr}");
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.starts_with(include_str!("fixtures/demo.kdl")));
+}
+
+#[test]
+fn file_output_has_no_color_codes() {
+    let path = home("output_file_color_target").join("schema.txt");
+    let output = command(
+        "output_file_color",
+        &["--demo", "--output", path.to_str().unwrap()],
+    )
+    .env_remove("NO_COLOR")
+    .env("CLICOLOR_FORCE", "0")
+    .output()
+    .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("* TABLES (6)"), "{written}");
+    assert!(!written.contains('\x1b'), "{written}");
+}
+
+#[test]
+fn stdout_holds_only_the_results() {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = run(
+        "output_stdout",
+        &["--server", &server, "--db", "game", "--format", "catalog"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let catalog: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(catalog.is_array() || catalog.is_object());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Fetching schema from"));
+}
+
+#[test]
+fn unwritable_output_path_is_an_error() {
+    let path = home("output_file_missing").join("no/such/dir/schema.txt");
+    let output = run(
+        "output_file_missing",
+        &["--demo", "--output", path.to_str().unwrap()],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Could not create output file"), "{stderr}");
+}
+
+/// Run `--demo --format kdl --output path` with `extra` flags
+fn write_kdl(name: &str, path: &std::path::Path, extra: &[&str]) -> std::process::Output {
+    let path = path.to_str().unwrap();
+    command(name, &["--demo", "--format", "kdl", "--output", path])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn rerun_with_the_same_results_leaves_the_file_alone() {
+    let path = home("output_file_rerun_target").join("schema.kdl");
+    assert!(write_kdl("output_file_rerun", &path, &[]).status.success());
+    let output = write_kdl("output_file_rerun", &path, &[]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("schema.kdl is unchanged"), "{stderr}");
+    assert!(!path.with_extension("kdl.partial").exists());
+}
+
+#[test]
+fn different_content_needs_force() {
+    let path = home("output_file_clobber_target").join("notes.txt");
+    std::fs::write(&path, "my notes\n").unwrap();
+
+    let output = write_kdl("output_file_clobber", &path, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("already exists with different content; pass --force"),
+        "{stderr}"
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "my notes\n");
+    assert!(!path.with_extension("txt.partial").exists());
+
+    let output = write_kdl("output_file_clobber", &path, &["--force"]);
+    assert!(output.status.success(), "{output:?}");
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.starts_with(include_str!("fixtures/demo.kdl")));
+}
+
+#[test]
+fn failed_run_keeps_the_existing_file() {
+    let server = serve(|_| Response::status("500 Internal Server Error", "down"));
+    let path = home("output_file_failed_target").join("schema.kdl");
+    std::fs::write(&path, "previous\n").unwrap();
+    let output = run(
+        "output_file_failed",
+        &[
+            "--server",
+            &server,
+            "--db",
+            "game",
+            "--retries",
+            "0",
+            "--force",
+            "--output",
+            path.to_str().unwrap(),
+        ],
+    );
+    assert!(!output.status.success());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "previous\n");
+    assert!(!path.with_extension("kdl.partial").exists());
+}
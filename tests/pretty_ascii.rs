@@ -0,0 +1,53 @@
+//! The pretty view in both modes: emoji and box drawing on a terminal,
+//! plain ASCII when stdout is piped or --ascii is given
+
+mod common;
+
+use common::{stdout, terminal};
+
+#[test]
+fn piped_demo_matches_ascii_snapshot() {
+    let stdout = stdout("pretty_ascii_piped", &["--demo"]);
+    assert!(stdout.is_ascii());
+    assert_eq!(stdout, include_str!("fixtures/demo_pretty_ascii.txt"));
+}
+
+#[test]
+fn terminal_demo_matches_unicode_snapshot() {
+    let stdout = terminal("pretty_ascii_terminal", &["--demo"]);
+    assert_eq!(stdout, include_str!("fixtures/demo_pretty.txt"));
+}
+
+#[test]
+fn flag_turns_ascii_on_at_a_terminal() {
+    let stdout = terminal("pretty_ascii_flag", &["--demo", "--ascii"]);
+    assert_eq!(stdout, include_str!("fixtures/demo_pretty_ascii.txt"));
+}
+
+#[test]
+fn ascii_mode_leaves_schema_names_alone() {
+    let stdout = stdout(
+        "pretty_ascii_names",
+        &["--file", "tests/fixtures/glyph_names.json"],
+    );
+    for name in ["latency_µs", "from→to", "size_2×2…"] {
+        assert!(stdout.contains(name), "{name} was rewritten:\n{stdout}");
+    }
+    assert!(stdout.contains("  * sample -> Sample"));
+    assert!(stdout.contains("    |- id: u64 [pk]"));
+    assert!(!stdout.contains('▸') && !stdout.contains('├') && !stdout.contains('🔑'));
+}
+
+#[test]
+fn other_output_is_never_transliterated() {
+    let stdout = common::stdout(
+        "pretty_ascii_dump_names",
+        &["--file", "tests/fixtures/glyph_names.json", "--dump-names"],
+    );
+    assert!(stdout.contains("Sample"));
+    let stdout = common::stdout(
+        "pretty_ascii_validate",
+        &["--validate-file", "tests/fixtures/glyph_names.json"],
+    );
+    assert!(stdout.starts_with("✅ "), "{stdout}");
+}
@@ -0,0 +1,192 @@
+//! --selftest against the stand-in server, with a failure injected at
+//! each step
+
+mod common;
+
+use std::net::TcpListener;
+use std::process::Command;
+
+use serde_json::{json, Value};
+
+use common::{command, serve, Response, DEMO_SCHEMA};
+
+/// Serve `/v1/ping` and `/v1/database/{db}/schema`. The database name
+/// picks the schema response:
+///
+/// - `good`: the game module
+/// - `locked`: 401
+/// - `unparsable`: valid JSON that isn't a schema
+/// - `invalid`: the game module with a dangling type reference
+fn server() -> String {
+    serve(|path| {
+        if path == "/v1/ping" {
+            return Response::ok("");
+        }
+        let db = path.split('/').nth(3).unwrap_or_default();
+        match db {
+            "good" => Response::ok(DEMO_SCHEMA),
+            "locked" => Response::status("401 Unauthorized", "no"),
+            "unparsable" => Response::ok(json!({"tables": 5}).to_string()),
+            "invalid" => {
+                let mut schema: Value = serde_json::from_str(DEMO_SCHEMA).unwrap();
+                schema["typespace"]["types"][0]["Product"]["elements"][0]["algebraic_type"] =
+                    json!({"Ref": 99});
+                Response::ok(schema.to_string())
+            }
+            _ => Response::status("404 Not Found", "no such database"),
+        }
+    })
+}
+
+/// A port nothing listens on
+fn closed_port() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+fn selftest_command(name: &str, server: &str, db: &str) -> Command {
+    command(
+        name,
+        &[
+            "--selftest",
+            "--format",
+            "json",
+            "--server",
+            server,
+            "--db",
+            db,
+        ],
+    )
+}
+
+/// The JSON report and whether the run succeeded
+fn report(mut command: Command) -> (Value, bool) {
+    let output = command.output().expect("binary runs");
+    let report = serde_json::from_slice(&output.stdout).expect("stdout is a JSON report");
+    (report, output.status.success())
+}
+
+fn selftest(name: &str, server: &str, db: &str) -> (Value, bool) {
+    report(selftest_command(name, server, db))
+}
+
+/// Step names with their statuses
+fn statuses(report: &Value) -> Vec<(String, String)> {
+    report["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| {
+            (
+                step["name"].as_str().unwrap().to_string(),
+                step["status"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Expected statuses when `failed` is the first failing step, given the
+/// steps that are always skipped
+fn expected(failed: Option<&str>) -> Vec<(String, String)> {
+    let mut seen_failure = false;
+    [
+        "resolve server",
+        "ping",
+        "authenticate",
+        "fetch schema",
+        "parse schema",
+        "validate schema",
+        "fingerprint",
+        "sql query",
+    ]
+    .iter()
+    .map(|&name| {
+        let status = if seen_failure || ["authenticate", "sql query"].contains(&name) {
+            "skipped"
+        } else if Some(name) == failed {
+            seen_failure = true;
+            "failed"
+        } else {
+            "passed"
+        };
+        (name.to_string(), status.to_string())
+    })
+    .collect()
+}
+
+fn detail<'r>(report: &'r Value, name: &str) -> &'r str {
+    report["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|step| step["name"] == name)
+        .and_then(|step| step["detail"].as_str())
+        .unwrap()
+}
+
+#[test]
+fn every_step_passes_against_a_healthy_server() {
+    let (report, success) = selftest("selftest_good", &server(), "good");
+    assert!(success);
+    assert_eq!(report["passed"], true);
+    assert_eq!(report["database"], "good");
+    assert_eq!(statuses(&report), expected(None));
+    assert!(detail(&report, "fingerprint").ends_with("(fnv1a-64/sorted-json)"));
+    assert_eq!(
+        detail(&report, "parse schema"),
+        "6 tables, 10 reducers, 11 types"
+    );
+}
+
+#[test]
+fn bad_server_config_fails_resolution() {
+    let command = selftest_command("selftest_resolve", "staging", "good");
+    let config = common::home_dir("selftest_resolve")
+        .join(".config")
+        .join("spacetime");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(config.join("cli.toml"), "server_configs = [").unwrap();
+
+    let (report, success) = report(command);
+    assert!(!success);
+    assert_eq!(statuses(&report), expected(Some("resolve server")));
+}
+
+#[test]
+fn unreachable_server_fails_ping() {
+    let (report, success) = selftest("selftest_ping", &closed_port(), "good");
+    assert!(!success);
+    assert_eq!(report["passed"], false);
+    assert_eq!(statuses(&report), expected(Some("ping")));
+    assert_eq!(
+        detail(&report, "fetch schema"),
+        "skipped after an earlier failure"
+    );
+}
+
+#[test]
+fn rejected_or_missing_database_fails_the_fetch() {
+    for db in ["locked", "missing"] {
+        let (report, success) = selftest("selftest_fetch", &server(), db);
+        assert!(!success, "{db}");
+        assert_eq!(statuses(&report), expected(Some("fetch schema")), "{db}");
+    }
+}
+
+#[test]
+fn unparsable_schema_fails_parsing() {
+    let (report, success) = selftest("selftest_parse", &server(), "unparsable");
+    assert!(!success);
+    assert_eq!(statuses(&report), expected(Some("parse schema")));
+}
+
+#[test]
+fn dangling_reference_fails_validation() {
+    let (report, success) = selftest("selftest_validate", &server(), "invalid");
+    assert!(!success);
+    assert_eq!(statuses(&report), expected(Some("validate schema")));
+    assert!(
+        detail(&report, "validate schema").contains("Ref 99 is out of bounds"),
+        "{report}"
+    );
+}
@@ -0,0 +1,59 @@
+//! --show-commands under the focused table and reducer views
+
+mod common;
+
+use common::{serve_schema, stdout, DEMO_SCHEMA};
+
+fn commands(name: &str, args: &[&str]) -> Vec<String> {
+    stdout(name, &[&["--show-commands"], args].concat())
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("$ "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn table_commands_use_the_invocation_database_and_server() {
+    let server = serve_schema(DEMO_SCHEMA);
+    assert_eq!(
+        commands(
+            "show_commands_table",
+            &["--server", &server, "--db", "other", "--table", "guild"]
+        ),
+        [
+            format!("spacetime sql --server {server} other 'SELECT id, name, founded, motto FROM guild LIMIT 10'"),
+            format!("spacetime subscribe --server {server} other 'SELECT * FROM guild'"),
+        ]
+    );
+}
+
+#[test]
+fn reducer_commands_have_typed_placeholders() {
+    let server = serve_schema(DEMO_SCHEMA);
+    assert_eq!(
+        commands(
+            "show_commands_reducer",
+            &[
+                "--server",
+                &server,
+                "--db",
+                "game",
+                "--reducer",
+                "join_guild"
+            ]
+        ),
+        [format!(
+            "spacetime call --server {server} game join_guild 0"
+        )]
+    );
+}
+
+#[test]
+fn commands_are_opt_in() {
+    let server = serve_schema(DEMO_SCHEMA);
+    let output = stdout(
+        "show_commands_off",
+        &["--server", &server, "--db", "game", "--table", "guild"],
+    );
+    assert!(!output.contains("spacetime sql"), "{output}");
+}
@@ -0,0 +1,100 @@
+//! The token `spacetime login` saves in the CLI config: sent only to the
+//! server it was saved for, with a warning or --strict-auth error otherwise
+
+mod common;
+
+use std::process::Output;
+
+use common::{command, home_dir, serve, Response};
+
+/// Run with a CLI config whose token was saved for `default_server`,
+/// against a server that rejects every request. The error then tells
+/// whether the token was sent.
+fn run_with_stored_token(name: &str, default_server: &str, args: &[&str]) -> (String, Output) {
+    let server = serve(|_| Response::status("401 Unauthorized", ""));
+    let default_server = default_server.replace("{server}", &server);
+    let mut command = command(name, &[&["--server", &server][..], args].concat());
+    let config = home_dir(name).join(".config").join("spacetime");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("cli.toml"),
+        format!("default_server = \"{default_server}\"\nspacetimedb_token = \"saved\"\n"),
+    )
+    .unwrap();
+    (server, command.output().unwrap())
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn token_for_another_server_is_not_sent() {
+    let (_, output) =
+        run_with_stored_token("stored_token_mismatch", "maincloud", &["--db", "game"]);
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains(
+            "The spacetime CLI token is for maincloud (maincloud.spacetimedb.com), not localhost, \
+             so it is not sent; pass --server maincloud to use it, or --token for this server"
+        ),
+        "{stderr}"
+    );
+    assert!(stderr.contains("Authentication required"), "{stderr}");
+}
+
+#[test]
+fn token_for_this_server_is_sent_without_a_warning() {
+    let (_, output) = run_with_stored_token("stored_token_match", "{server}", &["--db", "game"]);
+    let stderr = stderr(&output);
+    assert!(!stderr.contains("spacetime CLI token"), "{stderr}");
+    assert!(stderr.contains("the server rejected the token"), "{stderr}");
+}
+
+#[test]
+fn strict_auth_makes_a_mismatch_an_error() {
+    let (_, output) = run_with_stored_token(
+        "stored_token_strict",
+        "maincloud",
+        &["--db", "game", "--strict-auth"],
+    );
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("Error: The spacetime CLI token is for maincloud"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Authentication required"), "{stderr}");
+}
+
+#[test]
+fn explicit_token_skips_the_check() {
+    let (_, output) = run_with_stored_token(
+        "stored_token_explicit",
+        "maincloud",
+        &["--db", "game", "--token", "given", "--strict-auth"],
+    );
+    let stderr = stderr(&output);
+    assert!(!stderr.contains("spacetime CLI token"), "{stderr}");
+    assert!(stderr.contains("the server rejected the token"), "{stderr}");
+}
+
+#[test]
+fn whoami_shows_the_token_and_its_server() {
+    let (server, output) = run_with_stored_token("stored_token_whoami", "{server}", &["--whoami"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("Server: {server}\nToken:  saved by spacetime login for {server} ({server})\n")
+    );
+
+    let (server, output) = run_with_stored_token("stored_token_whoami", "maincloud", &["--whoami"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with(&format!(
+            "Server: {server}\nToken:  none; requests are anonymous\n"
+        )),
+        "{stdout}"
+    );
+    assert!(stdout.contains("pass --server maincloud"), "{stdout}");
+}
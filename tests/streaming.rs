@@ -0,0 +1,143 @@
+//! --format raw and json stream the document without parsing it, so
+//! 128/256-bit number literals are not rounded through f64
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::process::Output;
+
+use common::{run, DEMO_SCHEMA};
+
+const LITERALS: &str = include_str!("fixtures/u256_literals.json");
+
+/// Serve the fixture as the schema of every database, in small chunks
+/// that split number literals and strings
+fn server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+            );
+            for chunk in LITERALS.as_bytes().chunks(37) {
+                let _ = write!(stream, "{:x}\r\n", chunk.len());
+                let _ = stream.write_all(chunk);
+                let _ = stream.write_all(b"\r\n");
+                let _ = stream.flush();
+            }
+            let _ = stream.write_all(b"0\r\n\r\n");
+        }
+    });
+    format!("http://{address}")
+}
+
+fn fetch(format: &str) -> Output {
+    let server = server();
+    let output = run(
+        &format!("streaming_{format}"),
+        &["--server", &server, "--db", "big", "--format", format],
+    );
+    assert!(output.status.success(), "{output:?}");
+    output
+}
+
+/// The document with whitespace outside strings removed
+fn minify(json: &str) -> String {
+    let mut out = String::new();
+    let (mut in_string, mut escaped) = (false, false);
+    for c in json.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c.is_whitespace() {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[test]
+fn raw_preserves_the_exact_bytes() {
+    let output = fetch("raw");
+    assert_eq!(output.stdout, LITERALS.as_bytes());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!("Fetched {} bytes", LITERALS.len())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn json_preserves_every_literal() {
+    let stdout = String::from_utf8(fetch("json").stdout).unwrap();
+    assert_eq!(minify(&stdout), minify(LITERALS));
+    assert!(
+        stdout.contains(
+            "\"value\": 115792089237316195423570985008687907853269984665640564039457584007913129639935\n"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("\"empty\": {},\n"), "{stdout}");
+    assert!(
+        stdout.starts_with("{\n  \"typespace\": {\n    \"types\": []\n  },\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn demo_is_passed_through_too() {
+    let output = run("streaming_demo_raw", &["--demo", "--format", "raw"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(output.stdout, DEMO_SCHEMA.as_bytes());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Using the bundled demo schema"), "{stderr}");
+
+    let output = run("streaming_demo_json", &["--demo", "--format", "json"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(minify(&stdout), minify(DEMO_SCHEMA));
+}
+
+#[test]
+fn file_is_passed_through_too() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/u256_literals.json"
+    );
+    let output = run("streaming_file_raw", &["--file", path, "--format", "raw"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(output.stdout, LITERALS.as_bytes());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Loading from file"), "{stderr}");
+    assert!(!stderr.contains("Fetched"), "{stderr}");
+
+    let output = run("streaming_file_json", &["--file", path, "--format", "json"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(minify(&stdout), minify(LITERALS));
+    assert!(
+        stdout.contains(
+            "\"value\": 115792089237316195423570985008687907853269984665640564039457584007913129639935\n"
+        ),
+        "{stdout}"
+    );
+}
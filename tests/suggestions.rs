@@ -0,0 +1,22 @@
+//! "Did you mean?" suggestions for a --type that doesn't exist
+
+mod common;
+
+use common::stdout;
+
+#[test]
+fn typos_suggest_the_closest_names_first() {
+    let stdout = stdout("suggestions_typo", &["--demo", "--type", "Plyer"]);
+    assert!(
+        stdout.contains("Did you mean one of these?\n  - Player\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn suggestions_ignore_case_and_stop_at_five() {
+    let stdout = stdout("suggestions_case", &["--demo", "--type", "itemstak"]);
+    let suggestions = stdout.split("Did you mean one of these?\n").nth(1).unwrap();
+    assert!(suggestions.starts_with("  - ItemStack\n"), "{stdout}");
+    assert_eq!(suggestions.lines().count(), 5, "{stdout}");
+}
@@ -0,0 +1,62 @@
+//! --format template with a user's Handlebars file, and --template-context
+
+mod common;
+
+use common::{command, home_dir, stdout};
+
+/// Render `source`, saved as the template, against the demo schema
+fn render(name: &str, source: &str) -> std::process::Output {
+    let path = home_dir(name).join("template.hbs");
+    let mut command = command(
+        name,
+        &[
+            "--demo",
+            "--format",
+            "template",
+            "--template",
+            path.to_str().unwrap(),
+        ],
+    );
+    std::fs::write(&path, source).unwrap();
+    command.output().expect("binary runs")
+}
+
+#[test]
+fn templates_render_the_resolved_schema() {
+    let output = render(
+        "template_render",
+        "{{database}}:{{#each tables}} {{name}}{{#each fields}}{{#if primary_key}}[{{name}}]{{/if}}{{/each}}{{/each}}\n",
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "demo: player[id] item_kind[id] guild[id] guild_member[guild_id][player_id] chat_message[id] game_tick[scheduled_id]\n"
+    );
+}
+
+#[test]
+fn output_is_not_html_escaped() {
+    let output = render(
+        "template_escape",
+        "{{#each tables}}<{{name}}> & {{/each}}\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("<player> & <item_kind> & "), "{stdout}");
+}
+
+#[test]
+fn misspelled_variables_fail_with_their_position() {
+    let output = render("template_strict", "{{database}}\n{{databse}}\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("databse"), "{stderr}");
+    assert!(stderr.contains("line 2"), "{stderr}");
+}
+
+#[test]
+fn context_is_printed_as_json() {
+    let stdout = stdout("template_context", &["--demo", "--template-context"]);
+    let context: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(context["database"], "demo");
+    assert_eq!(context["well_known"]["ScheduledAt"], true);
+}
@@ -0,0 +1,36 @@
+//! --validate-file exit status and report
+
+mod common;
+
+use common::run;
+
+#[test]
+fn valid_file_passes() {
+    let output = run(
+        "validate_valid",
+        &["--validate-file", "src/demo_schema.json"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("is a valid schema"), "{stdout}");
+}
+
+#[test]
+fn invalid_file_lists_every_problem_and_fails() {
+    let output = run(
+        "validate_invalid",
+        &["--validate-file", "tests/fixtures/invalid_schema.json"],
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("/typespace/types/0/Product/elements/1/algebraic_type/Ref"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("reducer 'spawn' is defined more than once"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("problem(s) found in"), "{stderr}");
+}